@@ -6,20 +6,20 @@
 //! 
 //! ## What are the benefits of using StarSystem?
 //! - **Fully Serializable** - StarSystem is fully serializable. This means
-//! that you can save the state of your application and load it back in
-//! later.
+//!   that you can save the state of your application and load it back in
+//!   later.
 //! - **Fast** - StarSystem is fast. It uses a lot of parallelism to make
-//! sure that your application is as fast as possible.
+//!   sure that your application is as fast as possible.
 //! 
 //! ## What makes it different from other ECSs?
 //! - **Additional Layer of Use** - When a starsystem is created, you must
-//! pass an enum that contains all of the types of components that you want
-//! to use. StarSystem will then create a new World for each type provided.
-//! Beings live within the starsystem and consist of a collection of Entities.
-//! Entities live on the Worlds and consist of a collection of Properties 
-//! (Components in other ECSs). Properties must be of a type that is provided
-//! in the enum. This means that a Being can hold any number of Properties of
-//! any type that is provided in the enum.
+//!   pass an enum that contains all of the types of components that you want
+//!   to use. StarSystem will then create a new World for each type provided.
+//!   Beings live within the starsystem and consist of a collection of Entities.
+//!   Entities live on the Worlds and consist of a collection of Properties 
+//!   (Components in other ECSs). Properties must be of a type that is provided
+//!   in the enum. This means that a Being can hold any number of Properties of
+//!   any type that is provided in the enum.
 //! 
 //! ## How do I use it?
 //! ### Defining the types of components
@@ -29,6 +29,7 @@
 //! 
 //! ```rust
 //! use starsystem::{EnumIndex, StarSystem, AscendedBeing};
+//! use serde::{Deserialize, Serialize};
 //! use strum_macros::EnumIter;
 //! use futures::executor::block_on;
 //!
@@ -40,7 +41,7 @@
 //!     #[default]
 //!     None,
 //! }
-//! 
+//!
 //! impl EnumIndex for Edification {
 //!     fn index(&self) -> usize {
 //!         match self {
@@ -53,13 +54,51 @@
 //! }
 //! ```
 //! 
+//! If you do not need your own enum, the shipped `StarValue` already implements
+//! everything a starsystem needs. It converts from and to the common Rust types.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//!     let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let being = starsystem.conceive_being("config".to_string()).await.unwrap();
+//! # let entity = starsystem.constitute_being(being.clone(), "server".to_string()).await.unwrap();
+//!     starsystem.add_property(being.clone(), entity.clone(), 8080.into(), "port".to_string()).await.unwrap();
+//!     starsystem.add_property(being, entity, "localhost".into(), "host".to_string()).await.unwrap();
+//!     let port: u16 = StarValue::Int(8080).try_into().unwrap();
+//! # });
+//! ```
+//! 
 //! ### Creating a new starsystem and loading it with data
 //! Next, you must create a new starsystem. This is done by calling the
 //! `new` method on the `StarSystem` struct. You must pass the enum that
 //! contains all of the types of components that you want to use.
 //! 
 //! ```rust
-//! ...
+//! # use starsystem::*;
+//! # use serde::{Deserialize, Serialize};
+//! # use strum_macros::EnumIter;
+//! use futures::executor::block_on;
+//! # #[derive(EnumIter, Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+//! # pub enum Edification {
+//! #     String(String),
+//! #     Number(usize),
+//! #     Boolean(bool),
+//! #     #[default]
+//! #     None,
+//! # }
+//! #
+//! # impl EnumIndex for Edification {
+//! #     fn index(&self) -> usize {
+//! #         match self {
+//! #             Edification::String(_) => 0,
+//! #             Edification::Number(_) => 1,
+//! #             Edification::Boolean(_) => 2,
+//! #             Edification::None => 3,
+//! #         }
+//! #     }
+//! # }
+//! #
 //! fn main() {
 //!     block_on(async_main());
 //! }
@@ -102,7 +141,6 @@
 //!         serde_json::to_string_pretty(&ascended_configuration).unwrap()
 //!     );
 //! }
-//! ...
 //! ```
 //! 
 //! The output of the above code will be:
@@ -130,6 +168,284 @@
 //! ]
 //! ```
 //! 
+//! ### Loading data in bulk
+//! `spawn_batch` creates many beings with their entities and properties at
+//! once, locking each world a single time, and returns the new ids in the order
+//! of the specs. `add_properties_batch` does the same for properties of
+//! existing entities.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # struct Person { name: String, age: i64 }
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let people = vec![Person { name: "ada".to_string(), age: 36 }];
+//!     let specs = people.iter().map(|p| {
+//!         BeingSpec::new(p.name.clone()).entity(EntitySpec::new("profile".to_string()).property("age".to_string(), StarValue::Int(p.age)))
+//!     });
+//!     let spawned = starsystem.spawn_batch(specs).await.unwrap();
+//!     let profile = &spawned[0].entities[0];
+//!     let age = &profile.properties[0];
+//! # });
+//! ```
+//! 
+//! ### Storing structs
+//! Any `Serialize` struct or map can be stored as a being. Top level fields
+//! become entities and nested fields become properties named by their path
+//! below the entity, so `headers["Content-Type"]` becomes the property
+//! `Content-Type` of the entity `headers`, and `http.tls.cert` becomes the
+//! property `tls/cert` of the entity `http`. Scalars are converted through
+//! `StarValue`, or through your own `ValueMapping` with `insert_serialize_with`.
+//! A top level scalar like `port: 8080` becomes an entity `port` with a single
+//! property named `SCALAR_PROPERTY` (`.`), a name no nested field may take.
+//! An empty nested list, map or struct is stored as a null property at its
+//! path and read back empty.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # use serde::{Deserialize, Serialize};
+//! # use std::collections::BTreeMap;
+//! # #[derive(Serialize, Deserialize, Default)]
+//! # struct Http { port: u16, host: String }
+//! # #[derive(Serialize, Deserialize)]
+//! # struct Config { http: Http, headers: BTreeMap<String, String> }
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//!     let headers = BTreeMap::from([("Content-Type".to_string(), "application/json".to_string())]);
+//!     let config = Config { http: Http { port: 8080, ..Default::default() }, headers };
+//!     let being = starsystem.insert_serialize("config".to_string(), &config).await.unwrap();
+//!
+//!     // and back, errors name the property that failed, like `config/headers/Content-Type`
+//!     let config: Config = starsystem.extract(being).await.unwrap();
+//! # assert_eq!(config.http.port, 8080);
+//! # });
+//! ```
+//! 
+//! ### Layered configuration
+//! `resolve` merges beings into a single view, later layers override earlier
+//! ones property by property and every value records the layer it came from.
+//! An entity or property named with a leading `!` hides the one of the same
+//! name inherited from the layers below. An entity that holds nothing but such
+//! properties only hides them, it does not add an empty entity of its own.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let mut layers = Vec::new();
+//! # for (name, port) in [("base", 80), ("production", 443), ("local", 8080)] {
+//! #     let layer = starsystem.conceive_being(name.to_string()).await.unwrap();
+//! #     let server = starsystem.constitute_being(layer.clone(), "server".to_string()).await.unwrap();
+//! #     starsystem.add_property(layer.clone(), server, StarValue::Int(port), "port".to_string()).await.unwrap();
+//! #     layers.push(layer);
+//! # }
+//! # let (base, production, local) = (layers[0].clone(), layers[1].clone(), layers[2].clone());
+//!     let resolved = starsystem.resolve(&[base, production, local]).await.unwrap();
+//!     let port = resolved.value("server", "port");
+//!     let from = resolved.provenance("server", "port");
+//! # assert_eq!(port, Some(StarValue::Int(8080)));
+//! # });
+//! ```
+//! 
+//! ### Environment overlays
+//! `apply_env_overlay` sets properties from environment variables. With the
+//! prefix `APP`, `APP__HEADERS__CONTENT_TYPE` sets the property `Content-Type`
+//! of the entity `headers`, names match ignoring case and `-` versus `_`.
+//! `EnvOverlay` configures the separator, how new names are cased, the parser
+//! and a dry run that only lists the changes. Every change is checked before
+//! any is written, and numerals with a leading zero like `007` stay strings.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let being = starsystem.conceive_being("app".to_string()).await.unwrap();
+//!     let changes = starsystem.apply_env_overlay(being.clone(), "APP".to_string()).await.unwrap();
+//!
+//!     let overlay = EnvOverlay::new("APP".to_string()).separator("_".to_string()).dry_run();
+//!     let planned = starsystem.apply_env_overlay_with(being, &overlay, parse_star_value).await.unwrap();
+//! # });
+//! ```
+//! 
+//! ### Interpolation
+//! String properties can reference other properties, like
+//! `"${config/server/host}:${config/server/port}"`. References are only
+//! expanded when you ask for them, with `ascend_being_interpolated` or
+//! `get_property_interpolated`. `${entity/property}` names a property of the
+//! same being. Property names stored from nested fields hold slashes, so a
+//! reference like `${http/tls/cert}` is read as the property `tls/cert` of the
+//! entity `http` of the same being when there is one, and as
+//! being/entity/property otherwise. `$${` is a literal `${`, and cycles or
+//! references to missing properties are errors.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let being = starsystem.conceive_being("config".to_string()).await.unwrap();
+//! # let server = starsystem.constitute_being(being.clone(), "server".to_string()).await.unwrap();
+//! # starsystem.add_property(being.clone(), server.clone(), "localhost".into(), "host".to_string()).await.unwrap();
+//! # starsystem.add_property(being.clone(), server.clone(), StarValue::Int(8080), "port".to_string()).await.unwrap();
+//! # let url = starsystem.add_property(being.clone(), server, "${config/server/host}:${config/server/port}".into(), "url".to_string()).await.unwrap();
+//!     let url = starsystem.get_property_interpolated(being.clone(), url).await.unwrap();
+//!     let config = starsystem.ascend_being_interpolated(being).await.unwrap();
+//! # assert_eq!(url, StarValue::from("localhost:8080"));
+//! # });
+//! ```
+//! 
+//! ### Computed properties
+//! A computed property derives its value from other properties and is
+//! recomputed whenever one of them changes through `set_property` or
+//! `set_property_by_id`. Inputs that would make a property depend on itself
+//! are rejected. `ascend_being` marks computed properties with `computed`.
+//! Computed values are checked against constraints and schemas like any other
+//! write, so setting an input fails, before anything is written, when a
+//! property computed from it would break them.
+//! Other writes do not recompute: systems of a `Schedule`, `par_for_each_mut`
+//! and `add_properties_batch` leave computed properties at their last value
+//! until an input is set again.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let being = starsystem.conceive_being("order".to_string()).await.unwrap();
+//! # let entity = starsystem.constitute_being(being.clone(), "line".to_string()).await.unwrap();
+//! # let price = starsystem.add_property(being.clone(), entity.clone(), StarValue::Float(2.5), "price".to_string()).await.unwrap();
+//! # let quantity = starsystem.add_property(being.clone(), entity.clone(), StarValue::Int(4), "quantity".to_string()).await.unwrap();
+//!     let total = starsystem.add_computed_property(being, entity, "total".to_string(), vec![price, quantity], |v| {
+//!         StarValue::Float(v[0].as_float().unwrap_or(0.0) * v[1].as_float().unwrap_or(0.0))
+//!     }).await.unwrap();
+//! # });
+//! ```
+//! 
+//! ### Schemas
+//! A `BeingSchema` declares the entities and properties beings of a name must
+//! have, the variants each property may hold and the values new properties
+//! start with. Mutations that break it are rejected, or kept as warnings in
+//! `SchemaMode::Warn`, and `validate_all` lists every violation with its path.
+//! `spawn_batch`, `instantiate` and `insert_serialize` add the required entities
+//! and default properties a being is missing and check it before writing it.
+//! A `SharedStarSystem` keeps the schemas of the starsystem it was made from
+//! and checks its mutations against them.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//!     starsystem.set_schema("config".to_string(), BeingSchema::new()
+//!         .entity(EntitySchema::new("server".to_string()).required()
+//!             .property(PropertySchema::new("port".to_string()).required().variant(StarValue::Int(0)).default(StarValue::Int(80)))));
+//!
+//!     // the required entity is constituted with its default properties
+//!     let config = starsystem.conceive_being("config".to_string()).await.unwrap();
+//!     let violations = starsystem.validate_all().await.unwrap();
+//! # assert!(violations.is_empty());
+//! # });
+//! ```
+//! 
+//! ### Constraints
+//! Constraints check the values of properties in `add_property`,
+//! `set_property`, `set_property_by_id`, `spawn_batch` and
+//! `add_properties_batch`, on a `StarSystem` and on a `SharedStarSystem`, for
+//! every property of a name or for the property at a path. Ranges, glob
+//! patterns (`*`, `?`, `[a-z]`), maximum lengths, allowed values and closures
+//! are available, and a value that fails is rejected with every constraint it
+//! failed, as `PropertyError::Invalid` from the single property methods.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # let mut starsystem = StarSystem::<StarValue>::new_blocking();
+//!     starsystem.add_constraint("port".to_string(), Constraint::range(Some(1.0), Some(65535.0)))
+//!         .add_path_constraint("config/server/host".to_string(), Constraint::pattern("*.example.com").unwrap());
+//!
+//!     // the typed error lists every failure
+//!     let err = starsystem.validate_value("config", "server", "port", &StarValue::Int(0)).unwrap_err();
+//! ```
+//! 
+//! ### Templates
+//! A `Template` is a blueprint of entities and properties, written in code or
+//! converted from an `AscendedBeing` with `Template::from`. `instantiate`
+//! creates a being from it with fresh ids in one batch, overriding properties
+//! by path. Beings created with `instantiate_linked` pick up the changes of
+//! `update_template`, except for the properties they override. If a linked
+//! being cannot take an update, the beings go back to the old version and it
+//! stays registered. Instantiated beings get the defaults and checks of their
+//! schema and the constraints, like beings of `spawn_batch`.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # use std::collections::BTreeMap;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//!     let template = Template::new("server".to_string())
+//!         .entity(EntitySpec::new("http".to_string()).property("port".to_string(), StarValue::Int(80)));
+//!
+//!     let overrides = BTreeMap::from([("http/port".to_string(), StarValue::Int(8080))]);
+//!     let spawned = starsystem.instantiate_linked(&template, "api".to_string(), overrides).await.unwrap();
+//! # });
+//! ```
+//! 
+//! ### Cloning beings
+//! `clone_being` copies a being with all its entities and properties under
+//! fresh ids and returns the id every original maps to. The copies stay on
+//! the worlds of their originals, or are placed again with
+//! `ClonePlacement::Random`. The copy follows the schema and constraints of
+//! its new name like a being conceived under that name.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let being = starsystem.conceive_being("config".to_string()).await.unwrap();
+//! # let entity = starsystem.constitute_being(being.clone(), "server".to_string()).await.unwrap();
+//!     let cloned = starsystem.clone_being(being, "copy".to_string(), ClonePlacement::SameWorlds).await.unwrap();
+//!     let copied_entity = &cloned.ids[&entity];
+//! # });
+//! ```
+//! 
+//! ### Renaming and moving
+//! Beings, entities and properties can be renamed, entities moved to other
+//! beings and properties moved to other entities, without changing their ids.
+//! The names and locations on beings and in worlds are kept in sync.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let being = starsystem.conceive_being("config".to_string()).await.unwrap();
+//! # let other_being = starsystem.conceive_being("other".to_string()).await.unwrap();
+//! # let entity = starsystem.constitute_being(being.clone(), "server".to_string()).await.unwrap();
+//! # let other_entity = starsystem.constitute_being(other_being.clone(), "listener".to_string()).await.unwrap();
+//! # let property = starsystem.add_property(being.clone(), entity.clone(), StarValue::Int(80), "port".to_string()).await.unwrap();
+//!     starsystem.rename_entity(being.clone(), entity.clone(), "http".to_string()).await.unwrap();
+//!     starsystem.rename_property(property.clone(), "listen_port".to_string()).await.unwrap();
+//!     starsystem.move_entity(being, other_being, entity.clone()).await.unwrap();
+//!     starsystem.move_property(property, entity, other_entity).await.unwrap();
+//! # });
+//! ```
+//! 
+//! ### Name conflicts
+//! By default constituting an entity replaces the one of the same name on the
+//! being, while properties of the same name pile up on an entity. A
+//! `NameConflictPolicy` of `Replace`, `Reject`, `AllowDuplicates` or `Merge`
+//! changes that for the whole starsystem or for a single call, and
+//! `upsert_property` sets a property by name, creating it and its entity if
+//! they are missing. Replacing a property its schema requires is rejected
+//! like removing it, `Merge` sets it instead. A `SharedStarSystem` follows
+//! the policies of the starsystem it was made from.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let being = starsystem.conceive_being("config".to_string()).await.unwrap();
+//!     starsystem.set_property_conflict_policy(NameConflictPolicy::Reject);
+//!     let entity = starsystem.constitute_being_with_policy(being.clone(), "server".to_string(), NameConflictPolicy::Merge).await.unwrap();
+//!     let port = starsystem.upsert_property(being, "server".to_string(), "port".to_string(), StarValue::Int(8080)).await.unwrap();
+//! # });
+//! ```
+//! 
 //! ### Saving and loading the starsystem
 //! You can save the state of your starsystem to a file by calling the
 //! ascend_being method on the starsystem for each being. This will
@@ -137,18 +453,24 @@
 //! to a file using any serialization method that you want.
 //! 
 //! ```rust
-//! ...
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let dir = std::env::temp_dir().join(format!("starsystem-{}", std::process::id()));
+//! # std::fs::create_dir_all(&dir).unwrap();
+//! # let configuration = starsystem.conceive_being("config".to_string()).await.unwrap();
+//! # let ascended_configuration = starsystem.ascend_being(configuration.clone()).await.unwrap();
 //! // save the configuration to a file
 //! // consolidate all ascended beings
-//!     let mut beings: Vec<AscendedBeing<Edification>> = vec![];
+//!     let mut beings: Vec<AscendedBeing<StarValue>> = vec![];
 //!     for val in ascended_configuration {
 //!         beings.push(val);
 //!     }
-//! 
+//!
 //!     // write to file
-//!     let mut file = std::fs::File::create("./config.json").unwrap();
+//!     let mut file = std::fs::File::create(dir.join("config.json")).unwrap();
 //!     serde_json::to_writer_pretty(&mut file, &beings).unwrap();
-//! ...
+//! # });
 //! ```
 //! 
 //! You can load the state of your starsystem from a file by calling the
@@ -157,36 +479,45 @@
 //! the new starsystem.
 //! 
 //! ```rust
-//! ...
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//! # let dir = std::env::temp_dir().join(format!("starsystem-{}", std::process::id()));
+//! # std::fs::create_dir_all(&dir).unwrap();
+//! # let configuration = starsystem.conceive_being("config".to_string()).await.unwrap();
+//! # let headers = starsystem.constitute_being(configuration.clone(), "headers".to_string()).await.unwrap();
+//! # starsystem.add_property(configuration.clone(), headers, "application/json".into(), "Content-Type".to_string()).await.unwrap();
+//! # let saved = starsystem.ascend_being(configuration.clone()).await.unwrap();
+//! # serde_json::to_writer_pretty(std::fs::File::create(dir.join("config.json")).unwrap(), &saved).unwrap();
 //! // load the configuration from a file
-//!     let mut file = std::fs::File::open("./config.json").unwrap();
-//!     let beings: Vec<AscendedBeing<Edification>> = serde_json::from_reader(&mut file).unwrap();
-//! 
+//!     let mut file = std::fs::File::open(dir.join("config.json")).unwrap();
+//!     let beings: Vec<AscendedBeing<StarValue>> = serde_json::from_reader(&mut file).unwrap();
+//!
 //!     // recreate a new starsystem
-//!     let mut starsystem2 = StarSystem::<Edification>::new().await;
+//!     let mut starsystem2 = StarSystem::<StarValue>::new().await;
 //!     // recreate the beings
 //!     let configuration2 = starsystem2
 //!         .conceive_being("config".to_string())
 //!         .await
 //!         .unwrap();
-//! 
+//!
 //!     // devalop the being by recreating the saved entities and properties in starsystem2
 //!     starsystem2
 //!         .develop_being(configuration2.clone(), beings)
 //!         .await
 //!         .unwrap();
-//! 
+//!
 //!     // ascend the configuration2
-//!     let ascended_configuration = starsystem
-//!         .ascend_being(configuration.clone())
+//!     let ascended_configuration = starsystem2
+//!         .ascend_being(configuration2.clone())
 //!         .await
 //!         .unwrap();
-//! 
+//!
 //!     println!(
 //!         "{}",
 //!         serde_json::to_string_pretty(&ascended_configuration).unwrap()
 //!     );
-//! ...
+//! # });
 //! ```
 //! 
 //! The output of the above code will be:
@@ -214,12 +545,260 @@
 //! ]
 //! ```
 //! 
+//! ### Systems
+//! Systems are functions that run over every property matching a `Query`.
+//! A query selects variants of the enum for reading or writing and can be
+//! narrowed down to property names. Systems are added to named stages, the
+//! stages run in the order they were added and systems of a stage that do
+//! not write what another one reads or writes run in parallel.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//!     starsystem.add_system(
+//!         "update".to_string(),
+//!         Query::new().write(StarValue::Int(0)).named("retries".to_string()),
+//!         |context| {
+//!             for item in context.items.iter_mut() {
+//!                 if let StarValue::Int(n) = &mut item.component.data {
+//!                     *n += 1;
+//!                 }
+//!             }
+//!         },
+//!     );
+//!     starsystem.run_systems().await.unwrap();
+//! # });
+//! ```
+//! 
+//! Beings, entities and properties can not be created or removed while a
+//! system or a query is iterating. Record the changes in `Commands` instead.
+//! The commands recorded by the systems of a stage are applied in order once
+//! the stage is done, a buffer filled outside of a system is applied with
+//! `apply_commands`, which returns one result per command. A system that
+//! fails records no commands, the commands of the other systems of its stage
+//! are still applied and its error is returned once every stage has run.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//!     let mut commands = Commands::new();
+//!     for item in starsystem.query(&Query::new().read(StarValue::Bool(false))).await.unwrap() {
+//!         if item.component.data == StarValue::Bool(false) {
+//!             commands.dissolve_entity(item.being, item.entity);
+//!         }
+//!     }
+//!     for result in starsystem.apply_commands(&mut commands).await {
+//!         result.unwrap();
+//!     }
+//! # });
+//! ```
+//! 
+//! #### Change detection
+//! Every property remembers the tick it was added at and the tick its data
+//! last changed at, and worlds keep a log of removed properties. Queries can
+//! be narrowed to what was `added`, `changed` or `removed` since a system
+//! last ran. Outside of systems, remember the current tick, advance it and
+//! ask for what changed since. A world only keeps its latest `REMOVAL_LIMIT`
+//! removals, `World::set_removal_limit` changes that, and the log is not saved
+//! with the world.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//!     let since = starsystem.tick;
+//!     starsystem.advance_tick().await;
+//!     // ... mutate the starsystem ...
+//!     let moved = starsystem.query_since(&Query::new().read(StarValue::Int(0)).changed(), Some(since)).await.unwrap();
+//!     let gone = starsystem.removed_since(&Query::new().read(StarValue::Int(0)), Some(since)).await;
+//! # });
+//! ```
+//! 
+//! ### Resources
+//! Resources are values that are not tied to any being, like a clock, the
+//! configuration of a pipeline or counters. Named resources are values of
+//! the enum and are serialized with the starsystem, resources of any other
+//! type are keyed by their type. Those are only serialized when inserted with
+//! `insert_any_serialized_resource`, and come back after loading once
+//! `restore_any_resource` is called for their type. Every resource has its
+//! own lock, so holding one does not block the others. Systems reach them
+//! through their context.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # use serde::{Deserialize, Serialize};
+//! # #[derive(Serialize, Deserialize)]
+//! # struct Settings { retries: u32 }
+//! # let mut starsystem = StarSystem::<StarValue>::new_blocking();
+//!     starsystem.insert_resource("frames".to_string(), StarValue::Int(0));
+//!     if let Some(frames) = starsystem.get_resource_mut("frames".to_string()) {
+//!         *frames.write().unwrap() = StarValue::Int(1);
+//!     }
+//!     starsystem.insert_any_resource(std::time::Instant::now());
+//!     let started = *starsystem.get_any_resource::<std::time::Instant>().unwrap().read().unwrap();
+//!
+//!     starsystem.insert_any_serialized_resource(Settings { retries: 3 });
+//!     let json = serde_json::to_string(&starsystem).unwrap();
+//!     let mut loaded: StarSystem<StarValue> = serde_json::from_str(&json).unwrap();
+//!     loaded.restore_any_resource::<Settings>().unwrap();
+//! ```
+//! 
+//! ### Batch transforms
+//! `par_for_each` and `par_for_each_mut` visit every component of one variant
+//! across all worlds in parallel, without copying the components out. Every
+//! component `par_for_each_mut` visits is stamped as changed at the current
+//! tick, so change detection sees the transform.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # let starsystem = StarSystem::<StarValue>::new_blocking();
+//!     starsystem.par_for_each_mut(StarValue::Int(0), |_id, component| {
+//!         if let StarValue::Int(n) = &mut component.data {
+//!             *n += 1;
+//!         }
+//!     });
+//! ```
+//! 
+//! ### Dense storage
+//! By default a world keeps the components of every variant in a map keyed by
+//! their ids. For starsystems that mostly iterate over components, worlds can
+//! keep a packed column per variant instead, addressed by generational handles.
+//! The rest of the api does not change and the storage serializes the same way.
+//! Updating a property keeps its handle valid unless its value changes to
+//! another variant. `World::components` returns the components by variant
+//! whatever the storage.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//!     let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//!     starsystem.set_storage(StorageKind::Dense);
+//! # });
+//! ```
+//! 
+//! ### Events
+//! Systems can talk to each other through typed events instead of polling
+//! components. Events are double buffered: an event sent during a run can be
+//! read during that run and the next one, then it is dropped. A reader sees
+//! every event once, so keep it around and move it into the system that uses it.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let mut starsystem: StarSystem<StarValue> = StarSystem::new().await;
+//!     #[derive(Clone)]
+//!     struct Collision { a: Uid, b: Uid }
+//!
+//!     starsystem.add_event::<Collision>();
+//!     let reader = starsystem.event_reader::<Collision>();
+//!     starsystem.add_system("physics".to_string(), Query::new().read(StarValue::Int(0)), |ctx| {
+//!         let collisions = ctx.events.writer::<Collision>();
+//!         // ... collisions.send(Collision { a, b }) ...
+//!     });
+//!     starsystem.add_system("damage".to_string(), Query::new(), move |_ctx| {
+//!         for collision in reader.read() {
+//!             // ...
+//!         }
+//!     });
+//! # starsystem.run_systems().await.unwrap();
+//! # });
+//! ```
+//! 
+//! ### Running at a fixed timestep
+//! A `Runner` runs the schedule of a starsystem at a fixed timestep. Time
+//! passed on its clock is accumulated and the schedule runs once per timestep,
+//! at most `max_steps` times per frame. Systems can read the current
+//! `FixedTime` from their resources. Use a `FakeClock` or call `step` directly
+//! to run a simulation deterministically in tests.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # use std::time::Duration;
+//! # let mut starsystem = StarSystem::<StarValue>::new_blocking();
+//!     let clock = FakeClock::new();
+//!     let mut runner = Runner::with_clock(Duration::from_millis(10), clock.clone()).max_steps(4);
+//!     runner.frame(&mut starsystem).unwrap();
+//!     clock.advance(Duration::from_millis(25));
+//!     let steps = runner.frame(&mut starsystem).unwrap(); // 2 steps, 5ms left in the accumulator
+//! # assert_eq!(steps, 2);
+//!
+//!     // or drive it with the wall clock
+//!     let mut runner = Runner::new(Duration::from_millis(16));
+//!     runner.run_until(&mut starsystem, |s| s.tick > 5).unwrap();
+//! ```
+//! 
+//! ### Using the blocking API
+//! None of the async methods actually wait on anything, they only take
+//! locks. Every async method on `StarSystem`, `SharedStarSystem` and `World`
+//! has a synchronous twin with a `_blocking` suffix that does the work, the
+//! async methods are thin wrappers around them. CLI tools and tests can call the
+//! blocking methods directly without pulling in an executor.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//!     let mut starsystem = StarSystem::<StarValue>::new_blocking();
+//!     let configuration = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+//!     let headers = starsystem.constitute_being_blocking(configuration.clone(), "headers".to_string()).unwrap();
+//! ```
+//! 
+//! ### Sharing a starsystem across threads
+//! A `StarSystem` can be turned into a `SharedStarSystem`. The shared handle
+//! can be cloned into as many threads as needed and every method takes
+//! `&self`. Reads only take shared locks and writes only lock the being and
+//! the world they touch. The schemas, constraints and computed properties of
+//! the starsystem come along, so writes through the shared handle are checked
+//! and recomputed like those of the starsystem, and `to_star_system` gives
+//! back its schedule, events and templates too.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # let starsystem = StarSystem::<StarValue>::new_blocking();
+//!     let shared = SharedStarSystem::from(starsystem);
+//!     let handles: Vec<_> = (0..4)
+//!         .map(|i| {
+//!             let shared = shared.clone();
+//!             std::thread::spawn(move || {
+//!                 shared.conceive_being_blocking(format!("worker-{}", i)).unwrap()
+//!             })
+//!         })
+//!         .collect();
+//! # for handle in handles {
+//! #     handle.join().unwrap();
+//! # }
+//! ```
+//! 
+//! ### Running a starsystem on its own thread
+//! `StarSystemActor::spawn` moves a starsystem onto a dedicated thread and
+//! returns a cloneable handle. Every call is sent to that thread as a
+//! command, so mutations are applied one after another without any locks.
+//! The returned `Reply` can be awaited from any executor or waited on from a
+//! plain thread.
+//! 
+//! ```rust
+//! # use starsystem::*;
+//! # futures::executor::block_on(async {
+//! # let starsystem = StarSystem::<StarValue>::new_blocking();
+//!     let actor = StarSystemActor::spawn(starsystem);
+//!     let configuration = actor.conceive_being("config".to_string()).await.unwrap();
+//!     let headers = actor.constitute_being(configuration.clone(), "headers".to_string()).wait().unwrap();
+//!
+//!     // stop the thread and take the starsystem back
+//!     let starsystem = actor.stop().wait().unwrap();
+//! # });
+//! ```
+//! 
 
+pub use schedule::*;
 pub use starsystem::*;
 pub use uid::*;
+pub use value::*;
 pub use world::*;
 
+mod schedule;
 mod starsystem;
 mod uid;
+mod value;
 mod world;
 
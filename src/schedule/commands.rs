@@ -0,0 +1,145 @@
+use super::{EnumIndex, StarSystem, Uid};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use strum::IntoEnumIterator;
+
+/// A structural change recorded by `Commands`.
+/// Ids of new beings, entities and properties are reserved when the command is recorded,
+/// so later commands in the same buffer can refer to them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command<T> {
+	ConceiveBeing { id: Uid, name: String },
+	KillBeing { being: Uid },
+	ConstituteBeing { being: Uid, id: Uid, entity_name: String },
+	DissolveEntity { being: Uid, entity: Uid },
+	AddProperty { being: Uid, entity: Uid, id: Uid, property: T, property_name: String },
+	SetProperty { being: Uid, entity: Uid, property: Uid, value: T, name: String },
+	SetPropertyById { property: Uid, value: T },
+	RemoveProperty { property: Uid },
+}
+
+/// A buffer of structural changes.
+/// Record changes while iterating a query or inside a system and apply them at a sync point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commands<T> {
+	pub queue: Vec<Command<T>>,
+}
+
+impl<T> Default for Commands<T> {
+	fn default() -> Self {
+		Self { queue: Vec::new() }
+	}
+}
+
+impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> Commands<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn len(&self) -> usize {
+		self.queue.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.queue.is_empty()
+	}
+
+	pub fn push(&mut self, command: Command<T>) {
+		self.queue.push(command);
+	}
+
+	// move the commands of another buffer to the end of this one
+	pub fn append(&mut self, other: &mut Commands<T>) {
+		self.queue.append(&mut other.queue);
+	}
+
+	// returns the id the being will have
+	pub fn conceive_being(&mut self, name: String) -> Uid {
+		let id = Uid::new();
+		self.push(Command::ConceiveBeing { id: id.clone(), name });
+		id
+	}
+
+	pub fn kill_being(&mut self, being: Uid) {
+		self.push(Command::KillBeing { being });
+	}
+
+	// returns the id the entity will have
+	pub fn constitute_being(&mut self, being: Uid, entity_name: String) -> Uid {
+		let id = Uid::new();
+		self.push(Command::ConstituteBeing { being, id: id.clone(), entity_name });
+		id
+	}
+
+	pub fn dissolve_entity(&mut self, being: Uid, entity: Uid) {
+		self.push(Command::DissolveEntity { being, entity });
+	}
+
+	// returns the id the property will have
+	pub fn add_property(&mut self, being: Uid, entity: Uid, property: T, property_name: String) -> Uid {
+		let id = Uid::new();
+		self.push(Command::AddProperty { being, entity, id: id.clone(), property, property_name });
+		id
+	}
+
+	pub fn set_property(&mut self, being: Uid, entity: Uid, property: Uid, value: T, name: String) {
+		self.push(Command::SetProperty { being, entity, property, value, name });
+	}
+
+	pub fn set_property_by_id(&mut self, property: Uid, value: T) {
+		self.push(Command::SetPropertyById { property, value });
+	}
+
+	pub fn remove_property(&mut self, property: Uid) {
+		self.push(Command::RemoveProperty { property });
+	}
+
+	// apply the commands in the order they were recorded and empty the buffer
+	// a failing command does not stop the ones after it
+	// returns one result per command
+	pub fn apply(&mut self, starsystem: &mut StarSystem<T>) -> Vec<Result<(), String>> {
+		self.queue.drain(..).map(|command| command.apply(starsystem)).collect()
+	}
+}
+
+impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> Command<T> {
+	pub fn apply(self, starsystem: &mut StarSystem<T>) -> Result<(), String> {
+		match self {
+			Command::ConceiveBeing { id, name } => {
+				if starsystem.beings.iter().any(|b| b.id == id) {
+					return Err(format!("Being with id {} already exists", id));
+				}
+				starsystem.set_being_blocking(id, name).map(|_| ())
+			}
+			Command::KillBeing { being } => starsystem.kill_being_blocking(being),
+			Command::ConstituteBeing { being, id, entity_name } => starsystem.constitute_being_with_id_blocking(being, id, entity_name).map(|_| ()),
+			Command::DissolveEntity { being, entity } => {
+				let b = starsystem.get_being_blocking(being.clone())?;
+				if !b.entities.iter().any(|e| e.id == entity) {
+					return Err(format!("Entity with id {} does not exist on being {}", entity, being));
+				}
+				starsystem.dissolve_entity_blocking(being, entity)
+			}
+			Command::AddProperty { being, entity, id, property, property_name } => starsystem.add_property_with_id_blocking(being, entity, id, property, property_name).map(|_| ()).map_err(String::from),
+			Command::SetProperty { being, entity, property, value, name } => {
+				let b = starsystem.get_being_blocking(being.clone())?;
+				if !b.entities.iter().any(|e| e.id == entity) {
+					return Err(format!("Entity with id {} does not exist on being {}", entity, being));
+				}
+				starsystem.set_property_blocking(being, entity, property, value, name).map(|_| ()).map_err(String::from)
+			}
+			Command::SetPropertyById { property, value } => {
+				if !starsystem.worlds.values().any(|w| w.has_component(property.clone())) {
+					return Err(format!("Property with id {} does not exist", property));
+				}
+				starsystem.set_property_by_id_blocking(property, value).map(|_| ()).map_err(String::from)
+			}
+			Command::RemoveProperty { property } => {
+				if !starsystem.beings.iter().any(|b| b.entities.iter().any(|e| e.properties.iter().any(|p| p.id == property))) {
+					return Err(format!("Property with id {} does not exist", property));
+				}
+				starsystem.remove_property_blocking(property)
+			}
+		}
+	}
+}
@@ -0,0 +1,128 @@
+use super::{Component, EnumIndex, EventBus, Removal, Resources, StarSystem, Uid};
+pub use commands::{Command, Commands};
+pub use query::{ChangeFilter, Query, QueryItem};
+pub use runner::{Clock, FakeClock, FixedTime, Runner, SystemClock};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+pub use system::{System, SystemContext, SystemFn};
+use strum::IntoEnumIterator;
+
+mod commands;
+mod query;
+mod runner;
+mod system;
+
+/// A named group of systems.
+/// Systems of a stage that do not conflict run in parallel.
+#[derive(Debug, Clone)]
+pub struct Stage<T> {
+	pub name: String,
+	pub systems: Vec<System<T>>,
+}
+
+/// Runs stages of systems in order.
+#[derive(Debug, Clone)]
+pub struct Schedule<T> {
+	pub stages: Vec<Stage<T>>,
+}
+
+impl<T> Default for Schedule<T> {
+	fn default() -> Self {
+		Self { stages: Vec::new() }
+	}
+}
+
+impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> Stage<T> {
+	pub fn new(name: String) -> Self {
+		Self { name, systems: Vec::new() }
+	}
+
+	// split the systems into batches that can run in parallel
+	// returns the positions of the systems in each batch
+	// a system is placed after the last batch holding a system it conflicts with
+	// so conflicting systems keep the order they were added in
+	pub fn batches(&self) -> Vec<Vec<usize>> {
+		let mut batches: Vec<Vec<usize>> = Vec::new();
+		for (i, system) in self.systems.iter().enumerate() {
+			let after = batches.iter().rposition(|b| b.iter().any(|s| self.systems[*s].query.conflicts_with(&system.query))).map(|i| i + 1).unwrap_or(0);
+			match batches.get_mut(after) {
+				Some(batch) => batch.push(i),
+				None => batches.push(vec![i]),
+			}
+		}
+		batches
+	}
+}
+
+impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> Schedule<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	// add an empty stage at the end of the schedule
+	pub fn add_stage(&mut self, name: String) -> &mut Self {
+		if !self.stages.iter().any(|s| s.name == name) {
+			self.stages.push(Stage::new(name));
+		}
+		self
+	}
+
+	// add a system to a stage
+	// if the stage does not exist, it is added at the end of the schedule
+	pub fn add_system(&mut self, stage: String, query: Query, system: impl Fn(&mut SystemContext<T>) + Send + Sync + 'static) -> &mut Self {
+		self.add_stage(stage.clone());
+		self.stages.iter_mut().find(|s| s.name == stage).unwrap().systems.push(System::new(query, system));
+		self
+	}
+
+	// run every stage in order
+	// every system runs at its own tick, so it sees what the systems before it changed the next time it runs
+	// the commands recorded by the systems of a stage are applied once the stage is done
+	// failing systems and commands do not stop the schedule, their errors are returned together at the end
+	// a failing system stores none of its changes, records no commands and keeps its last run
+	pub fn run(&mut self, starsystem: &mut StarSystem<T>) -> Result<(), String> {
+		let mut errors = Vec::new();
+		for stage in self.stages.iter_mut() {
+			// keep the commands in the order the systems were added, not the order of the batches
+			let mut recorded: Vec<Commands<T>> = stage.systems.iter().map(|_| Commands::new()).collect();
+			for batch in stage.batches() {
+				let ticks: Vec<u64> = batch.iter().map(|_| starsystem.next_tick()).collect();
+				let starsystem: &StarSystem<T> = starsystem;
+				let systems = &stage.systems;
+				let results: Vec<Result<Commands<T>, String>> = batch.par_iter().zip(ticks.par_iter()).map(|(i, tick)| systems[*i].run_on(starsystem, *tick)).collect();
+				for ((i, tick), res) in batch.iter().zip(ticks).zip(results) {
+					match res {
+						Ok(c) => {
+							stage.systems[*i].last_run = Some(tick);
+							recorded[*i] = c;
+						}
+						Err(e) => errors.push(format!("stage {}: system {}: {}", stage.name, i, e)),
+					}
+				}
+			}
+			starsystem.advance_tick_blocking();
+			let mut commands = Commands::new();
+			for c in recorded.iter_mut() {
+				commands.append(c);
+			}
+			for (i, res) in commands.apply(starsystem).into_iter().enumerate() {
+				if let Err(e) = res {
+					errors.push(format!("stage {}: command {}: {}", stage.name, i, e));
+				}
+			}
+		}
+		// every system has seen the removals up to the oldest last run
+		if let Some(oldest) = self.stages.iter().flat_map(|s| s.systems.iter()).map(|s| s.last_run.unwrap_or(0)).min() {
+			starsystem.clear_removals_blocking(oldest);
+		}
+		starsystem.advance_tick_blocking();
+		starsystem.update_events();
+
+		if errors.is_empty() {
+			Ok(())
+		} else {
+			Err(errors.join("\n"))
+		}
+	}
+}
@@ -0,0 +1,103 @@
+use super::{Component, EnumIndex, Uid};
+use std::collections::BTreeSet;
+
+/// Describes the components a system receives and how it accesses them.
+/// Variants are selected with a value of the enum, the same way `World::get_components_of_type` does.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+	pub reads: BTreeSet<usize>,
+	pub writes: BTreeSet<usize>,
+	pub names: BTreeSet<String>,
+	pub filter: Option<ChangeFilter>,
+}
+
+/// Narrows a query down to what happened since a given tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeFilter {
+	// components added since the tick
+	Added,
+	// components added or changed since the tick
+	Changed,
+	// components removed since the tick, these are not returned as items
+	Removed,
+}
+
+impl Query {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	// read components of the variant of the given value
+	pub fn read(mut self, variant: impl EnumIndex) -> Self {
+		self.reads.insert(variant.index());
+		self
+	}
+
+	// read and write components of the variant of the given value
+	pub fn write(mut self, variant: impl EnumIndex) -> Self {
+		self.writes.insert(variant.index());
+		self
+	}
+
+	// only match properties with the given name
+	// if no names are given, every property of the variants matches
+	pub fn named(mut self, name: String) -> Self {
+		self.names.insert(name);
+		self
+	}
+
+	// only match components added since the last run
+	pub fn added(mut self) -> Self {
+		self.filter = Some(ChangeFilter::Added);
+		self
+	}
+
+	// only match components added or changed since the last run
+	pub fn changed(mut self) -> Self {
+		self.filter = Some(ChangeFilter::Changed);
+		self
+	}
+
+	// match components removed since the last run
+	pub fn removed(mut self) -> Self {
+		self.filter = Some(ChangeFilter::Removed);
+		self
+	}
+
+	// does the query read or write the given index
+	pub fn includes(&self, index: usize) -> bool {
+		self.reads.contains(&index) || self.writes.contains(&index)
+	}
+
+	// does a property of the given index and name match the query
+	pub fn matches(&self, index: usize, name: &str) -> bool {
+		self.includes(index) && (self.names.is_empty() || self.names.contains(name))
+	}
+
+	// does a component pass the change filter
+	// if there is no tick to compare against, everything counts as added and changed
+	pub fn passes<T>(&self, component: &Component<T>, since: Option<u64>) -> bool {
+		match (self.filter, since) {
+			(None, _) => true,
+			(Some(ChangeFilter::Removed), _) => false,
+			(Some(_), None) => true,
+			(Some(ChangeFilter::Added), Some(since)) => component.added > since,
+			(Some(ChangeFilter::Changed), Some(since)) => component.changed > since,
+		}
+	}
+
+	// two queries conflict if one writes a variant the other reads or writes
+	pub fn conflicts_with(&self, other: &Query) -> bool {
+		self.writes.iter().any(|i| other.includes(*i)) || other.writes.iter().any(|i| self.includes(*i))
+	}
+}
+
+/// A component matched by a query, along with where it lives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryItem<T> {
+	pub being: Uid,
+	pub entity: Uid,
+	pub world: Uid,
+	pub id: Uid,
+	pub component: Component<T>,
+}
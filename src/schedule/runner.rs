@@ -0,0 +1,167 @@
+use super::{EnumIndex, StarSystem};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use strum::IntoEnumIterator;
+
+/// A source of time for a `Runner`.
+pub trait Clock {
+	// the time elapsed since the clock started
+	fn now(&self) -> Duration;
+
+	// wait for the given duration
+	fn sleep(&self, duration: Duration);
+}
+
+/// A clock that follows the wall clock.
+#[derive(Debug, Clone)]
+pub struct SystemClock {
+	start: Instant,
+}
+
+impl Default for SystemClock {
+	fn default() -> Self {
+		Self { start: Instant::now() }
+	}
+}
+
+impl SystemClock {
+	pub fn new() -> Self {
+		Self::default()
+	}
+}
+
+impl Clock for SystemClock {
+	fn now(&self) -> Duration {
+		self.start.elapsed()
+	}
+
+	fn sleep(&self, duration: Duration) {
+		thread::sleep(duration);
+	}
+}
+
+/// A clock that only moves when it is told to.
+/// Clones share the same time, so a test can keep one and hand the other to a runner.
+/// Sleeping advances the clock instead of waiting.
+#[derive(Debug, Clone, Default)]
+pub struct FakeClock {
+	now: Arc<Mutex<Duration>>,
+}
+
+impl FakeClock {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn advance(&self, duration: Duration) {
+		*self.now.lock().unwrap() += duration;
+	}
+
+	pub fn set(&self, now: Duration) {
+		*self.now.lock().unwrap() = now;
+	}
+}
+
+impl Clock for FakeClock {
+	fn now(&self) -> Duration {
+		*self.now.lock().unwrap()
+	}
+
+	fn sleep(&self, duration: Duration) {
+		self.advance(duration);
+	}
+}
+
+/// The fixed timestep of the running step.
+/// The runner inserts it as a resource before every step, so systems can read it from their context.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTime {
+	pub delta: Duration,
+	pub step: u64,
+}
+
+/// Runs the schedule of a starsystem at a fixed timestep.
+/// Time passed on the clock is added to an accumulator and the schedule runs once per timestep in it.
+/// At most `max_steps` run per frame, the time left over after that is dropped so a slow frame does not snowball.
+#[derive(Debug, Clone)]
+pub struct Runner<C> {
+	pub timestep: Duration,
+	pub max_steps: usize,
+	pub clock: C,
+	pub accumulator: Duration,
+	pub steps: u64,
+	last: Option<Duration>,
+}
+
+impl Runner<SystemClock> {
+	pub fn new(timestep: Duration) -> Self {
+		Self::with_clock(timestep, SystemClock::new())
+	}
+}
+
+impl<C: Clock> Runner<C> {
+	pub fn with_clock(timestep: Duration, clock: C) -> Self {
+		Self { timestep, max_steps: 5, clock, accumulator: Duration::ZERO, steps: 0, last: None }
+	}
+
+	// set the maximum number of steps a frame can run
+	pub fn max_steps(mut self, max_steps: usize) -> Self {
+		self.max_steps = max_steps;
+		self
+	}
+
+	// how far the accumulator is into the next step, from 0 to 1
+	// useful to interpolate between two steps when rendering
+	pub fn alpha(&self) -> f64 {
+		if self.timestep.is_zero() {
+			return 0.0;
+		}
+		self.accumulator.as_secs_f64() / self.timestep.as_secs_f64()
+	}
+
+	// run the schedule once, without looking at the clock
+	pub fn step<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, starsystem: &mut StarSystem<T>) -> Result<(), String> {
+		starsystem.insert_any_resource(FixedTime { delta: self.timestep, step: self.steps });
+		self.steps += 1;
+		starsystem.run_systems_blocking()
+	}
+
+	// add the time passed since the last frame to the accumulator and run the steps it covers
+	// the first frame only starts the clock
+	// returns the number of steps that ran
+	pub fn frame<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, starsystem: &mut StarSystem<T>) -> Result<usize, String> {
+		let now = self.clock.now();
+		if let Some(last) = self.last {
+			self.accumulator += now.saturating_sub(last);
+		}
+		self.last = Some(now);
+		if self.timestep.is_zero() {
+			return Err("Timestep must be greater than zero".to_string());
+		}
+
+		let mut ran = 0;
+		while self.accumulator >= self.timestep {
+			if ran == self.max_steps {
+				// drop the time that could not be caught up
+				self.accumulator = Duration::from_nanos((self.accumulator.as_nanos() % self.timestep.as_nanos()) as u64);
+				break;
+			}
+			self.accumulator -= self.timestep;
+			self.step(starsystem)?;
+			ran += 1;
+		}
+		Ok(ran)
+	}
+
+	// run frames until stop returns true, sleeping on the clock until the next step is due
+	pub fn run_until<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, starsystem: &mut StarSystem<T>, mut stop: impl FnMut(&StarSystem<T>) -> bool) -> Result<(), String> {
+		while !stop(starsystem) {
+			self.frame(starsystem)?;
+			self.clock.sleep(self.timestep.saturating_sub(self.accumulator));
+		}
+		Ok(())
+	}
+}
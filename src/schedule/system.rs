@@ -0,0 +1,90 @@
+use super::{ChangeFilter, Commands, EnumIndex, EventBus, Query, QueryItem, Removal, Resources, StarSystem, Uid};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use strum::IntoEnumIterator;
+
+/// The function of a system.
+pub type SystemFn<T> = Arc<dyn Fn(&mut SystemContext<T>) + Send + Sync>;
+
+/// A function that runs over the components matching its query.
+#[derive(Clone)]
+pub struct System<T> {
+	pub query: Query,
+	pub run: SystemFn<T>,
+	// the tick the system last ran at
+	pub last_run: Option<u64>,
+}
+
+/// What a system receives when it runs.
+/// Changes to the data of items of written variants are stored back into the worlds once the system returns.
+/// If an item is changed to a variant the query does not write, none of the changes are stored.
+/// Structural changes are recorded in `commands` and applied once every system of the stage has run.
+#[derive(Debug)]
+pub struct SystemContext<T> {
+	pub items: Vec<QueryItem<T>>,
+	pub commands: Commands<T>,
+	// components removed since the last run, only filled for queries filtering on removals
+	pub removed: Vec<Removal<T>>,
+	// the tick the system runs at
+	pub tick: u64,
+	// the tick the system last ran at
+	pub last_run: Option<u64>,
+	// the resources of the starsystem
+	// systems of a batch share them, every resource has its own lock
+	pub resources: Resources<T>,
+	// the event channels of the starsystem, to create writers
+	pub events: EventBus,
+}
+
+impl<T> Debug for System<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("System").field("query", &self.query).field("last_run", &self.last_run).finish()
+	}
+}
+
+impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> System<T> {
+	pub fn new(query: Query, run: impl Fn(&mut SystemContext<T>) + Send + Sync + 'static) -> Self {
+		Self { query, run: Arc::new(run), last_run: None }
+	}
+
+	// collect the matching components, run the system and store back what it changed
+	// changes are stamped with the given tick
+	// returns the commands recorded by the system
+	pub fn run_on(&self, starsystem: &StarSystem<T>, tick: u64) -> Result<Commands<T>, String> {
+		let items = starsystem.query_since_blocking(&self.query, self.last_run)?;
+		let removed = match self.query.filter {
+			Some(ChangeFilter::Removed) => starsystem.removed_since_blocking(&self.query, self.last_run),
+			_ => Vec::new(),
+		};
+		let originals: BTreeMap<Uid, T> = items.iter().filter(|i| self.query.writes.contains(&i.component.data.index())).map(|i| (i.id.clone(), i.component.data.clone())).collect();
+		let mut context = SystemContext { items, commands: Commands::new(), removed, tick, last_run: self.last_run, resources: starsystem.resources.clone(), events: starsystem.events.clone() };
+		(self.run)(&mut context);
+
+		// group the changed components by world
+		// a component can only be changed to a variant the query writes
+		let mut updates: BTreeMap<Uid, Vec<(Uid, Uid, T)>> = BTreeMap::new();
+		for item in context.items {
+			if let Some(original) = originals.get(&item.id) {
+				if *original != item.component.data {
+					let index = item.component.data.index();
+					if !self.query.writes.contains(&index) {
+						return Err(format!("component: {} was changed to variant {} which the query does not write", item.id, index));
+					}
+					updates.entry(item.world).or_default().push((item.entity, item.id, item.component.data));
+				}
+			}
+		}
+
+		// check every update before storing any of them
+		for (world, updates) in updates.iter() {
+			let w = starsystem.worlds.get(world).ok_or(format!("world: {} not found", world))?;
+			w.check_updates_blocking(updates)?;
+		}
+		for (world, updates) in updates {
+			starsystem.worlds[&world].update_components_blocking(updates, tick)?;
+		}
+		Ok(context.commands)
+	}
+}
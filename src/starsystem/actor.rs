@@ -0,0 +1,237 @@
+use super::{AscendedBeing, Being, BeingSpec, EnumIndex, SpawnedBeing, StarSystem, Uid};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::any::Any;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use strum::IntoEnumIterator;
+
+type Job<T> = Box<dyn FnOnce(&mut StarSystem<T>) + Send>;
+
+enum Message<T> {
+	Job(Job<T>),
+	Stop(Responder<StarSystem<T>>),
+}
+
+/// A cloneable handle to a starsystem owned by a dedicated thread.
+/// Every call is sent to the thread as a command, so mutations are serialized without any locks.
+/// Calls return a `Reply` that can be awaited or waited on.
+/// A call that panics resolves to an error with the panic message and the thread keeps running,
+/// the starsystem is left as the call left it when it panicked.
+#[derive(Debug, Clone)]
+pub struct StarSystemActor<T> {
+	sender: Sender<Message<T>>,
+}
+
+impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> StarSystemActor<T> {
+	// spawn a thread that owns the starsystem
+	// the thread runs until stop is called or every handle is dropped
+	pub fn spawn(starsystem: StarSystem<T>) -> Self {
+		let (sender, receiver) = channel::<Message<T>>();
+		thread::spawn(move || {
+			let mut starsystem = starsystem;
+			for message in receiver {
+				match message {
+					Message::Job(job) => job(&mut starsystem),
+					Message::Stop(responder) => {
+						responder.send(Ok(starsystem));
+						return;
+					}
+				}
+			}
+		});
+		Self { sender }
+	}
+
+	// run a closure on the actor thread
+	pub fn call<R: 'static + Send>(&self, f: impl FnOnce(&mut StarSystem<T>) -> R + Send + 'static) -> Reply<R> {
+		self.try_call(move |s| Ok(f(s)))
+	}
+
+	// run a fallible closure on the actor thread
+	// the error of the closure is returned as the error of the reply
+	// a panic of the closure is caught and returned as the error of the reply
+	pub fn try_call<R: 'static + Send>(&self, f: impl FnOnce(&mut StarSystem<T>) -> Result<R, String> + Send + 'static) -> Reply<R> {
+		let (responder, reply) = Reply::pair();
+		let job: Job<T> = Box::new(move |s| {
+			let res = catch_unwind(AssertUnwindSafe(|| f(s))).unwrap_or_else(|panic| Err(panic_message(panic)));
+			responder.send(res);
+		});
+		// if the actor is gone the responder is dropped with the message and the reply resolves to an error
+		let _ = self.sender.send(Message::Job(job));
+		reply
+	}
+
+	// stop the actor thread and return the starsystem
+	// calls made after this resolve to an error
+	pub fn stop(&self) -> Reply<StarSystem<T>> {
+		let (responder, reply) = Reply::pair();
+		let _ = self.sender.send(Message::Stop(responder));
+		reply
+	}
+
+	/*
+			StarSystem actions
+			mirrors the starsystem api, each call is executed on the actor thread
+	*/
+
+	pub fn conceive_being(&self, name: String) -> Reply<Uid> {
+		self.try_call(move |s| s.conceive_being_blocking(name))
+	}
+
+	pub fn set_being(&self, id: Uid, name: String) -> Reply<Uid> {
+		self.try_call(move |s| s.set_being_blocking(id, name))
+	}
+
+	pub fn kill_being(&self, id: Uid) -> Reply<()> {
+		self.try_call(move |s| s.kill_being_blocking(id))
+	}
+
+	pub fn get_being(&self, id: Uid) -> Reply<Being> {
+		self.try_call(move |s| s.get_being_blocking(id))
+	}
+
+	pub fn constitute_being(&self, being: Uid, entity_name: String) -> Reply<Uid> {
+		self.try_call(move |s| s.constitute_being_blocking(being, entity_name))
+	}
+
+	pub fn dissolve_entity(&self, being: Uid, entity: Uid) -> Reply<()> {
+		self.try_call(move |s| s.dissolve_entity_blocking(being, entity))
+	}
+
+	pub fn add_property(&self, being: Uid, entity: Uid, property: T, property_name: String) -> Reply<Uid> {
+		self.try_call(move |s| s.add_property_blocking(being, entity, property, property_name).map_err(String::from))
+	}
+
+	pub fn upsert_property(&self, being: Uid, entity_name: String, property_name: String, value: T) -> Reply<Uid> {
+		self.try_call(move |s| s.upsert_property_blocking(being, entity_name, property_name, value))
+	}
+
+	pub fn set_property(&self, being: Uid, entity: Uid, property: Uid, value: T, name: String) -> Reply<Uid> {
+		self.try_call(move |s| s.set_property_blocking(being, entity, property, value, name).map_err(String::from))
+	}
+
+	pub fn remove_property(&self, property: Uid) -> Reply<()> {
+		self.try_call(move |s| s.remove_property_blocking(property))
+	}
+
+	pub fn set_property_by_id(&self, property_id: Uid, property_value: T) -> Reply<Uid> {
+		self.try_call(move |s| s.set_property_by_id_blocking(property_id, property_value).map_err(String::from))
+	}
+
+	pub fn spawn_batch(&self, specs: Vec<BeingSpec<T>>) -> Reply<Vec<SpawnedBeing>> {
+		self.try_call(move |s| s.spawn_batch_blocking(specs))
+	}
+
+	pub fn add_properties_batch(&self, properties: Vec<(Uid, Uid, T, String)>) -> Reply<Vec<Uid>> {
+		self.try_call(move |s| s.add_properties_batch_blocking(properties))
+	}
+
+	pub fn develop_being(&self, being: Uid, ascended_beings: Vec<AscendedBeing<T>>) -> Reply<Vec<Uid>> {
+		self.try_call(move |s| s.develop_being_blocking(being, ascended_beings))
+	}
+
+	pub fn ascend_being(&self, being: Uid) -> Reply<Vec<AscendedBeing<T>>> {
+		self.try_call(move |s| s.ascend_being_blocking(being))
+	}
+}
+
+// the message of a caught panic
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+	let message = match panic.downcast::<String>() {
+		Ok(message) => *message,
+		Err(panic) => panic.downcast::<&str>().map(|m| m.to_string()).unwrap_or_else(|_| "unknown panic".to_string()),
+	};
+	format!("StarSystem call panicked: {}", message)
+}
+
+struct ReplyState<R> {
+	value: Option<Result<R, String>>,
+	waker: Option<Waker>,
+}
+
+type ReplySlot<R> = Arc<(Mutex<ReplyState<R>>, Condvar)>;
+
+/// The pending result of a call to a `StarSystemActor`.
+/// It can be awaited from any executor or waited on from a plain thread.
+#[derive(Debug)]
+pub struct Reply<R> {
+	slot: ReplySlot<R>,
+}
+
+impl<R> Debug for ReplyState<R> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ReplyState").field("ready", &self.value.is_some()).finish()
+	}
+}
+
+impl<R> Reply<R> {
+	fn pair() -> (Responder<R>, Self) {
+		let slot: ReplySlot<R> = Arc::new((Mutex::new(ReplyState { value: None, waker: None }), Condvar::new()));
+		(Responder { slot: Some(slot.clone()) }, Self { slot })
+	}
+
+	// block the current thread until the actor has answered
+	pub fn wait(self) -> Result<R, String> {
+		let (state, condvar) = &*self.slot;
+		let mut state = state.lock().unwrap();
+		loop {
+			if let Some(value) = state.value.take() {
+				return value;
+			}
+			state = condvar.wait(state).unwrap();
+		}
+	}
+}
+
+impl<R> Future for Reply<R> {
+	type Output = Result<R, String>;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let mut state = self.slot.0.lock().unwrap();
+		match state.value.take() {
+			Some(value) => Poll::Ready(value),
+			None => {
+				state.waker = Some(cx.waker().clone());
+				Poll::Pending
+			}
+		}
+	}
+}
+
+// the sending half of a reply
+// if it is dropped without answering, the reply resolves to an error
+struct Responder<R> {
+	slot: Option<ReplySlot<R>>,
+}
+
+impl<R> Responder<R> {
+	fn send(mut self, value: Result<R, String>) {
+		if let Some(slot) = self.slot.take() {
+			Self::fulfill(&slot, value);
+		}
+	}
+
+	fn fulfill(slot: &ReplySlot<R>, value: Result<R, String>) {
+		let (state, condvar) = &**slot;
+		let mut state = state.lock().unwrap();
+		state.value = Some(value);
+		if let Some(waker) = state.waker.take() {
+			waker.wake();
+		}
+		condvar.notify_all();
+	}
+}
+
+impl<R> Drop for Responder<R> {
+	fn drop(&mut self) {
+		if let Some(slot) = self.slot.take() {
+			Self::fulfill(&slot, Err("StarSystemActor is no longer running".to_string()));
+		}
+	}
+}
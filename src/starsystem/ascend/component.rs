@@ -6,4 +6,7 @@ pub struct AscendedComponent<T> {
 	pub name: String,
 	pub id: Uid,
 	pub data: T,
+	// true if the value is derived from other properties
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub computed: bool,
 }
@@ -1,6 +1,10 @@
-use super::StarEntity;
+use super::{AscendedBeing, AscendedComponent, AscendedEntity, EnumIndex, World};
+use super::{StarEntity, StarEntityLocation, StarEntityProperty};
 use super::Uid;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use strum::IntoEnumIterator;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Being {
@@ -13,4 +17,146 @@ impl Being {
 	pub fn new(name: String) -> Self {
 		Self { id: Uid::new(), entities: Vec::new(), name }
 	}
+
+	// the ids of every property of the being
+	pub fn property_ids(&self) -> Vec<Uid> {
+		self.entities.iter().flat_map(|e| e.properties.iter().map(|p| p.id.clone())).collect()
+	}
+
+	/*
+			Being actions
+			shared by StarSystem and SharedStarSystem, these only lock the worlds they touch
+	*/
+
+	// constitute
+	// if an entity with the same name exists on the being, remove it
+	// create a new entity with the given id on the given world
+	pub(crate) fn constitute<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, worlds: &BTreeMap<Uid, World<T>>, world: Uid, id: Uid, entity_name: String) -> Result<Uid, String> {
+		if let Some(e) = self.entities.iter().find(|e| e.name == entity_name) {
+			if let Some(w) = worlds.get(&e.location.world) {
+				w.remove_entity_blocking(e.id.clone()).unwrap();
+			}
+		}
+		self.entities.retain(|e| e.name != entity_name);
+		self.insert_entity(worlds, world, id, entity_name)
+	}
+
+	// insert entity
+	// create a new entity with the given id on the given world, next to any entity of the same name
+	pub(crate) fn insert_entity<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, worlds: &BTreeMap<Uid, World<T>>, world: Uid, id: Uid, entity_name: String) -> Result<Uid, String> {
+		let ent = match worlds.get(&world) {
+			Some(w) => w.insert_entity_blocking(id, entity_name.clone())?,
+			None => return Err(format!("World with id {} does not exist", world)),
+		};
+		let entity = StarEntity { location: StarEntityLocation { world, entity: ent.clone() }, id: ent, name: entity_name, properties: Vec::new() };
+		self.entities.push(entity.clone());
+		Ok(entity.id)
+	}
+
+	// dissolve
+	// remove entity from its world
+	// remove entity from being
+	pub(crate) fn dissolve<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, worlds: &BTreeMap<Uid, World<T>>, entity: Uid) {
+		if let Some(e) = self.entities.iter().find(|e| e.id == entity) {
+			if let Some(world) = worlds.get(&e.location.world) {
+				world.remove_entity_blocking(e.id.clone()).unwrap();
+			}
+		}
+		self.entities.retain(|e| e.id != entity);
+	}
+
+	// dissolve all
+	// remove every entity of the being from its world
+	pub(crate) fn dissolve_all<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, worlds: &BTreeMap<Uid, World<T>>) {
+		for entity in self.entities.iter() {
+			if let Some(world) = worlds.get(&entity.location.world) {
+				world.remove_entity_blocking(entity.id.clone()).unwrap();
+			}
+		}
+		self.entities.clear();
+	}
+
+	// add property with the given id to entity
+	pub(crate) fn add_property<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, worlds: &BTreeMap<Uid, World<T>>, entity: Uid, id: Uid, property: T, property_name: String) -> Result<Uid, String> {
+		if let Some(e) = self.entities.iter_mut().find(|e| e.id == entity) {
+			if let Some(world) = worlds.get(&e.location.world) {
+				let id = world.set_component_to_entity_blocking(entity.clone(), property, property_name.clone(), id).unwrap();
+				let location: StarEntityLocation = StarEntityLocation { world: e.location.world.clone(), entity: id.clone() };
+				e.properties.push(StarEntityProperty { location, id: id.clone(), name: property_name });
+				return Ok(id);
+			}
+		}
+		Err("Could not add property to entity".to_string())
+	}
+
+	// set property
+	pub(crate) fn set_property<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, worlds: &BTreeMap<Uid, World<T>>, entity: Uid, property: Uid, value: T, name: String) -> Option<Uid> {
+		let e = self.entities.iter_mut().find(|e| e.id == entity)?;
+		let world = worlds.get(&e.location.world)?;
+		let id = world.set_component_to_entity_blocking(entity.clone(), value, name.clone(), property.clone()).unwrap();
+
+		// update property
+		if let Some(p) = e.properties.iter_mut().find(|p| p.id == property) {
+			p.name = name;
+		}
+		Some(id)
+	}
+
+	// remove property by id
+	// returns true if the property was found on the being
+	pub(crate) fn remove_property<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, worlds: &BTreeMap<Uid, World<T>>, property: Uid) -> bool {
+		for e in self.entities.iter_mut() {
+			if let Some(p) = e.properties.iter().find(|p| p.id == property) {
+				if let Some(world) = worlds.get(&e.location.world) {
+					world.remove_component_from_entity_blocking(e.id.clone(), p.id.clone()).unwrap();
+				}
+				e.properties.retain(|p| p.id != property);
+				return true;
+			}
+		}
+		false
+	}
+
+	// develop
+	// recreate the entities of an ascended being on the given world
+	pub(crate) fn develop<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&mut self, worlds: &BTreeMap<Uid, World<T>>, world: Uid, ascended_being: &AscendedBeing<T>) -> Result<Vec<Uid>, String> {
+		let w = match worlds.get(&world) {
+			Some(w) => w,
+			None => return Err(format!("World with id {} does not exist", world)),
+		};
+
+		let mut entities = Vec::new();
+		for e in ascended_being.entities.iter() {
+			let ent = w.set_entity_blocking(e.id.clone(), e.name.clone()).unwrap();
+			let mut entity = StarEntity { location: StarEntityLocation { world: world.clone(), entity: ent.clone() }, id: ent.clone(), name: e.name.clone(), properties: Vec::new() };
+
+			// add properties
+			for c in e.components.iter() {
+				entity.properties.push(StarEntityProperty { location: StarEntityLocation { world: world.clone(), entity: ent.clone() }, id: c.id.clone(), name: c.name.clone() });
+			}
+
+			entities.push(entity.id.clone());
+			self.entities.push(entity);
+		}
+		Ok(entities)
+	}
+
+	// ascend
+	// collect the entities and components of the being from the worlds
+	pub(crate) fn ascend<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(&self, worlds: &BTreeMap<Uid, World<T>>) -> Result<AscendedBeing<T>, String> {
+		let mut entities: Vec<AscendedEntity<T>> = vec![];
+		for entity in self.entities.iter() {
+			let world = match worlds.get(&entity.location.world) {
+				Some(w) => w,
+				None => return Err(format!("World with id {} does not exist", entity.location.world)),
+			};
+			let components = world.get_entity_components_blocking(entity.id.clone())?;
+			let mut new_component: Vec<AscendedComponent<T>> = vec![];
+			for (id, component) in components.iter() {
+				new_component.push(AscendedComponent { id: id.clone(), name: component.name.clone(), data: component.data.clone(), computed: false });
+			}
+			entities.push(AscendedEntity { id: entity.id.clone(), name: entity.name.clone(), components: new_component });
+		}
+		Ok(AscendedBeing { name: self.name.clone(), id: self.id.clone(), entities })
+	}
 }
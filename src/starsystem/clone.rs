@@ -0,0 +1,61 @@
+use super::{Being, EntityBatch, EnumIndex, StarEntity, StarEntityLocation, StarEntityProperty, Uid, World};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use strum::IntoEnumIterator;
+
+/// Where `clone_being` puts the copies of entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ClonePlacement {
+	// every copy goes to the world of its original
+	#[default]
+	SameWorlds,
+	// every copy goes to a random world
+	Random,
+}
+
+/// The copy of a being and the id every original maps to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClonedBeing {
+	pub id: Uid,
+	// the new id of the being, of every entity and of every property, by original id
+	pub ids: BTreeMap<Uid, Uid>,
+}
+
+// copy a being and its components into worlds under fresh ids
+// each world is written to once
+// returns the copy to add to the starsystem and the mapping of ids
+pub(crate) fn clone_being<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(worlds: &BTreeMap<Uid, World<T>>, being: &Being, name: String, placement: ClonePlacement) -> Result<(Being, ClonedBeing), String> {
+	let world_ids: Vec<Uid> = worlds.keys().cloned().collect();
+	let mut batches: BTreeMap<Uid, EntityBatch<T>> = BTreeMap::new();
+	let mut copy = Being::new(name);
+	let mut ids = BTreeMap::new();
+	ids.insert(being.id.clone(), copy.id.clone());
+
+	for entity in being.entities.iter() {
+		let source = worlds.get(&entity.location.world).ok_or(format!("World with id {} does not exist", entity.location.world))?;
+		let world = match placement {
+			ClonePlacement::SameWorlds => entity.location.world.clone(),
+			ClonePlacement::Random => world_ids[rand::random::<usize>() % world_ids.len()].clone(),
+		};
+		let id = Uid::new();
+		ids.insert(entity.id.clone(), id.clone());
+
+		let mut components = Vec::with_capacity(entity.properties.len());
+		let mut properties = Vec::with_capacity(entity.properties.len());
+		for (component_id, component) in source.get_entity_components_blocking(entity.id.clone())? {
+			let new_id = Uid::new();
+			properties.push(StarEntityProperty { location: StarEntityLocation { world: world.clone(), entity: new_id.clone() }, id: new_id.clone(), name: component.name.clone() });
+			components.push((new_id.clone(), component.data, component.name));
+			ids.insert(component_id, new_id);
+		}
+		copy.entities.push(StarEntity { location: StarEntityLocation { world: world.clone(), entity: id.clone() }, id: id.clone(), name: entity.name.clone(), properties });
+		batches.entry(world).or_default().push((id, entity.name.clone(), components));
+	}
+
+	for (world, batch) in batches {
+		worlds.get(&world).ok_or(format!("World with id {} does not exist", world))?.insert_entities_blocking(batch)?;
+	}
+	let cloned = ClonedBeing { id: copy.id.clone(), ids };
+	Ok((copy, cloned))
+}
@@ -0,0 +1,127 @@
+use super::Uid;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Derives the value of a computed property from the values of its inputs, in the order they were registered.
+pub type ComputeFn<T> = Arc<dyn Fn(&[T]) -> T + Send + Sync>;
+
+/// A property whose value is derived from other properties.
+#[derive(Clone)]
+pub struct ComputedProperty<T> {
+	pub inputs: Vec<Uid>,
+	pub compute: ComputeFn<T>,
+}
+
+/// The computed properties of a starsystem and the graph of the properties they read.
+/// Closures are not serialized, computed properties have to be registered again after loading.
+#[derive(Clone)]
+pub struct ComputedProperties<T> {
+	properties: BTreeMap<Uid, ComputedProperty<T>>,
+	// the computed properties that read each property
+	dependents: BTreeMap<Uid, BTreeSet<Uid>>,
+}
+
+impl<T> Default for ComputedProperties<T> {
+	fn default() -> Self {
+		Self { properties: BTreeMap::new(), dependents: BTreeMap::new() }
+	}
+}
+
+impl<T> Debug for ComputedProperties<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_map().entries(self.properties.iter().map(|(id, p)| (id, &p.inputs))).finish()
+	}
+}
+
+impl<T> ComputedProperties<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn is_computed(&self, id: &Uid) -> bool {
+		self.properties.contains_key(id)
+	}
+
+	pub fn get(&self, id: &Uid) -> Option<&ComputedProperty<T>> {
+		self.properties.get(id)
+	}
+
+	// register a computed property, replacing an earlier registration of the same property
+	// rejects inputs that would make the property depend on itself
+	pub(crate) fn insert(&mut self, id: Uid, inputs: Vec<Uid>, compute: ComputeFn<T>) -> Result<(), String> {
+		if let Some(path) = self.path_to(&inputs, &id) {
+			let cycle: Vec<String> = std::iter::once(&id).chain(path.iter()).map(|id| id.to_string()).collect();
+			return Err(format!("Computed property would depend on itself: {}", cycle.join(" <- ")));
+		}
+		self.remove(&id);
+		for input in inputs.iter() {
+			self.dependents.entry(input.clone()).or_default().insert(id.clone());
+		}
+		self.properties.insert(id, ComputedProperty { inputs, compute });
+		Ok(())
+	}
+
+	// the chain of inputs that leads from the given inputs back to the property, if any
+	fn path_to(&self, inputs: &[Uid], id: &Uid) -> Option<Vec<Uid>> {
+		for input in inputs {
+			if input == id {
+				return Some(vec![input.clone()]);
+			}
+			if let Some(p) = self.properties.get(input) {
+				if let Some(mut path) = self.path_to(&p.inputs, id) {
+					path.insert(0, input.clone());
+					return Some(path);
+				}
+			}
+		}
+		None
+	}
+
+	// stop computing a property, it keeps its last value
+	pub(crate) fn remove(&mut self, id: &Uid) -> Option<ComputedProperty<T>> {
+		let property = self.properties.remove(id)?;
+		for input in property.inputs.iter() {
+			if let Some(d) = self.dependents.get_mut(input) {
+				d.remove(id);
+				if d.is_empty() {
+					self.dependents.remove(input);
+				}
+			}
+		}
+		Some(property)
+	}
+
+	// forget properties that were removed
+	// computed properties that read them stop being computed and keep their last value
+	pub(crate) fn forget(&mut self, ids: &[Uid]) {
+		for id in ids {
+			self.remove(id);
+			for dependent in self.dependents.get(id).cloned().unwrap_or_default() {
+				self.remove(&dependent);
+			}
+		}
+	}
+
+	// the computed properties affected by a change of a property, inputs before the properties that read them
+	pub(crate) fn affected(&self, id: &Uid) -> Vec<Uid> {
+		let mut visited = BTreeSet::new();
+		let mut order = Vec::new();
+		self.visit(id, &mut visited, &mut order);
+		order.pop();
+		order.reverse();
+		order
+	}
+
+	fn visit(&self, id: &Uid, visited: &mut BTreeSet<Uid>, order: &mut Vec<Uid>) {
+		visited.insert(id.clone());
+		if let Some(dependents) = self.dependents.get(id) {
+			for d in dependents {
+				if !visited.contains(d) {
+					self.visit(d, visited, order);
+				}
+			}
+		}
+		order.push(id.clone());
+	}
+}
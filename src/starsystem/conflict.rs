@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// What happens when an entity or property is added under a name its being or entity already uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NameConflictPolicy {
+	// the existing one is removed and the new one takes its place
+	Replace,
+	// the addition fails
+	Reject,
+	// both are kept under the same name
+	AllowDuplicates,
+	// the existing one is kept, a property gets the new value
+	Merge,
+}
+
+/// The name conflict policies of a starsystem.
+/// Entities are replaced and properties duplicated by default, as they always were.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameConflicts {
+	pub entities: NameConflictPolicy,
+	pub properties: NameConflictPolicy,
+}
+
+impl Default for NameConflicts {
+	fn default() -> Self {
+		Self { entities: NameConflictPolicy::Replace, properties: NameConflictPolicy::AllowDuplicates }
+	}
+}
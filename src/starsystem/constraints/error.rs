@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// What a constraint checks, as reported when a value fails it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConstraintKind {
+	Range { min: Option<f64>, max: Option<f64> },
+	Pattern(String),
+	MaxLength(usize),
+	// the allowed values, formatted with Debug
+	OneOf(Vec<String>),
+	// the name of a custom constraint
+	Custom(String),
+}
+
+impl Display for ConstraintKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ConstraintKind::Range { min, max } => {
+				let bound = |b: &Option<f64>| b.map(|b| b.to_string()).unwrap_or_default();
+				write!(f, "range {}..={}", bound(min), bound(max))
+			}
+			ConstraintKind::Pattern(p) => write!(f, "pattern {:?}", p),
+			ConstraintKind::MaxLength(n) => write!(f, "max length {}", n),
+			ConstraintKind::OneOf(values) => write!(f, "one of [{}]", values.join(", ")),
+			ConstraintKind::Custom(name) => write!(f, "{}", name),
+		}
+	}
+}
+
+/// A constraint a value failed and why.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintFailure {
+	pub constraint: ConstraintKind,
+	pub message: String,
+}
+
+/// Every constraint a value failed, at the path of its property like `config/server/port`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationError {
+	pub path: String,
+	pub failures: Vec<ConstraintFailure>,
+}
+
+impl Display for ValidationError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let failures: Vec<String> = self.failures.iter().map(|c| format!("{} ({})", c.message, c.constraint)).collect();
+		write!(f, "{}: {}", self.path, failures.join(", "))
+	}
+}
+
+impl std::error::Error for ValidationError {}
+
+impl From<ValidationError> for String {
+	fn from(error: ValidationError) -> Self {
+		error.to_string()
+	}
+}
@@ -0,0 +1,139 @@
+use super::{BeingSpec, StarValue};
+pub use error::{ConstraintFailure, ConstraintKind, ValidationError};
+pub use pattern::Pattern;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+mod error;
+mod pattern;
+
+/// Checks a value, returning why it fails.
+pub type CheckFn<T> = Arc<dyn Fn(&T) -> Result<(), String> + Send + Sync>;
+
+/// A rule the values of a property must follow.
+/// Ranges, patterns and lengths read values through their conversion to `StarValue`.
+#[derive(Clone)]
+pub struct Constraint<T> {
+	pub kind: ConstraintKind,
+	check: CheckFn<T>,
+}
+
+impl<T> Debug for Constraint<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Constraint({})", self.kind)
+	}
+}
+
+impl<T: 'static> Constraint<T> {
+	// a constraint checked by a closure
+	pub fn custom(name: String, check: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static) -> Self {
+		Self { kind: ConstraintKind::Custom(name), check: Arc::new(check) }
+	}
+
+	// the value must equal one of the given values
+	pub fn one_of(values: Vec<T>) -> Self
+	where
+		T: PartialEq + Debug + Send + Sync,
+	{
+		let kind = ConstraintKind::OneOf(values.iter().map(|v| format!("{:?}", v)).collect());
+		let check = move |value: &T| if values.contains(value) { Ok(()) } else { Err(format!("{:?} is not an allowed value", value)) };
+		Self { kind, check: Arc::new(check) }
+	}
+
+	pub fn check(&self, value: &T) -> Result<(), ConstraintFailure> {
+		(self.check)(value).map_err(|message| ConstraintFailure { constraint: self.kind.clone(), message })
+	}
+}
+
+impl<T: 'static + Clone + Into<StarValue>> Constraint<T> {
+	// the value must be a number within the given bounds, inclusive
+	pub fn range(min: Option<f64>, max: Option<f64>) -> Self {
+		let check = move |value: &T| {
+			let scalar: StarValue = value.clone().into();
+			let n = scalar.as_float().ok_or(format!("expected a number, found {}", scalar.type_name()))?;
+			if min.is_some_and(|min| n < min) || max.is_some_and(|max| n > max) {
+				return Err(format!("{} is out of range", n));
+			}
+			Ok(())
+		};
+		Self { kind: ConstraintKind::Range { min, max }, check: Arc::new(check) }
+	}
+
+	// the value must be a string matching a glob pattern
+	pub fn pattern(pattern: &str) -> Result<Self, String> {
+		let compiled = Pattern::new(pattern)?;
+		let check = move |value: &T| match value.clone().into() {
+			StarValue::String(s) if compiled.matches(&s) => Ok(()),
+			StarValue::String(s) => Err(format!("{:?} does not match {:?}", s, compiled.as_str())),
+			other => Err(format!("expected a string, found {}", other.type_name())),
+		};
+		Ok(Self { kind: ConstraintKind::Pattern(pattern.to_string()), check: Arc::new(check) })
+	}
+
+	// strings, bytes, lists and maps can hold at most the given number of characters or items
+	pub fn max_length(max: usize) -> Self {
+		let check = move |value: &T| {
+			let scalar: StarValue = value.clone().into();
+			let len = match &scalar {
+				StarValue::String(s) => s.chars().count(),
+				StarValue::Bytes(b) => b.len(),
+				StarValue::List(l) => l.len(),
+				StarValue::Map(m) => m.len(),
+				other => return Err(format!("{} has no length", other.type_name())),
+			};
+			if len > max {
+				return Err(format!("length {} is longer than {}", len, max));
+			}
+			Ok(())
+		};
+		Self { kind: ConstraintKind::MaxLength(max), check: Arc::new(check) }
+	}
+}
+
+/// The constraints of a starsystem, registered for every property of a name or for the property at a path.
+/// Closures are not serialized, constraints have to be registered again after loading.
+#[derive(Debug, Clone)]
+pub struct Constraints<T> {
+	pub by_name: BTreeMap<String, Vec<Constraint<T>>>,
+	// keyed by paths like `config/server/port`
+	pub by_path: BTreeMap<String, Vec<Constraint<T>>>,
+}
+
+impl<T> Default for Constraints<T> {
+	fn default() -> Self {
+		Self { by_name: BTreeMap::new(), by_path: BTreeMap::new() }
+	}
+}
+
+impl<T: 'static> Constraints<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	// check a value against the constraints of its property name and path
+	// lists every constraint it fails
+	pub fn validate(&self, being: &str, entity: &str, property: &str, value: &T) -> Result<(), ValidationError> {
+		let path = format!("{}/{}/{}", being, entity, property);
+		let constraints = self.by_name.get(property).into_iter().chain(self.by_path.get(&path)).flatten();
+		let failures: Vec<ConstraintFailure> = constraints.filter_map(|c| c.check(value).err()).collect();
+		if failures.is_empty() {
+			return Ok(());
+		}
+		Err(ValidationError { path, failures })
+	}
+
+	// check every property of a being to spawn, up to the first value that fails
+	pub fn validate_spec(&self, spec: &BeingSpec<T>) -> Result<(), ValidationError> {
+		for e in spec.entities.iter() {
+			for p in e.properties.iter() {
+				self.validate(&spec.name, &e.name, &p.name, &p.value)?;
+			}
+		}
+		Ok(())
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.by_name.is_empty() && self.by_path.is_empty()
+	}
+}
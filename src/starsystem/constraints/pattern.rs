@@ -0,0 +1,107 @@
+// a small glob matcher for string constraints
+// `*` matches any run of characters, `?` any single character, `[a-z0-9_]` a character of a class,
+// `[!...]` a character outside it and `\` escapes the next character
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+	Literal(char),
+	Any,
+	One,
+	Class { ranges: Vec<(char, char)>, negated: bool },
+}
+
+impl Token {
+	fn matches(&self, c: char) -> bool {
+		match self {
+			Token::Literal(l) => *l == c,
+			Token::One => true,
+			Token::Class { ranges, negated } => ranges.iter().any(|(a, b)| *a <= c && c <= *b) != *negated,
+			Token::Any => false,
+		}
+	}
+}
+
+/// A compiled glob pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+	source: String,
+	tokens: Vec<Token>,
+}
+
+impl Pattern {
+	pub fn new(source: &str) -> Result<Self, String> {
+		let mut tokens = Vec::new();
+		let mut chars = source.chars();
+		while let Some(c) = chars.next() {
+			match c {
+				'*' => tokens.push(Token::Any),
+				'?' => tokens.push(Token::One),
+				'\\' => tokens.push(Token::Literal(chars.next().ok_or(format!("pattern {:?} ends with an escape", source))?)),
+				'[' => {
+					let mut ranges = Vec::new();
+					let mut negated = false;
+					let mut closed = false;
+					let mut first = true;
+					while let Some(c) = chars.next() {
+						match c {
+							'!' if first => negated = true,
+							']' if !first || !ranges.is_empty() => {
+								closed = true;
+								break;
+							}
+							_ => {
+								let c = if c == '\\' { chars.next().ok_or(format!("pattern {:?} ends with an escape", source))? } else { c };
+								// a range like a-z, a trailing - is a literal
+								let mut lookahead = chars.clone();
+								match (lookahead.next(), lookahead.next()) {
+									(Some('-'), Some(end)) if end != ']' => {
+										chars = lookahead;
+										ranges.push((c, end));
+									}
+									_ => ranges.push((c, c)),
+								}
+							}
+						}
+						first = false;
+					}
+					if !closed {
+						return Err(format!("pattern {:?} has an unclosed class", source));
+					}
+					tokens.push(Token::Class { ranges, negated });
+				}
+				c => tokens.push(Token::Literal(c)),
+			}
+		}
+		Ok(Self { source: source.to_string(), tokens })
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.source
+	}
+
+	pub fn matches(&self, text: &str) -> bool {
+		let text: Vec<char> = text.chars().collect();
+		// matched[j] is true if the tokens so far match the first j characters
+		let mut matched = vec![false; text.len() + 1];
+		matched[0] = true;
+		for token in self.tokens.iter() {
+			let mut next = vec![false; text.len() + 1];
+			match token {
+				Token::Any => {
+					let mut any = false;
+					for j in 0..=text.len() {
+						any |= matched[j];
+						next[j] = any;
+					}
+				}
+				_ => {
+					for j in 1..=text.len() {
+						next[j] = matched[j - 1] && token.matches(text[j - 1]);
+					}
+				}
+			}
+			matched = next;
+		}
+		matched[text.len()]
+	}
+}
@@ -0,0 +1,129 @@
+use super::StarValue;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// How names taken from environment variables are written when no entity or property matches them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CaseFolding {
+	#[default]
+	Lower,
+	Upper,
+	Preserve,
+}
+
+impl CaseFolding {
+	pub fn apply(&self, name: &str) -> String {
+		match self {
+			CaseFolding::Lower => name.to_lowercase(),
+			CaseFolding::Upper => name.to_uppercase(),
+			CaseFolding::Preserve => name.to_string(),
+		}
+	}
+}
+
+/// Options of an environment overlay.
+/// `APP__HEADERS__CONTENT_TYPE` with the prefix `APP` maps to the property `Content-Type` of the entity `headers`.
+/// Names match existing entities and properties ignoring case and treating `-` and `_` alike,
+/// further segments become the path of a nested property like `tls/cert`.
+/// A single segment like `APP__PORT` sets the scalar property `SCALAR_PROPERTY` of the entity `port`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvOverlay {
+	pub prefix: String,
+	pub separator: String,
+	pub case: CaseFolding,
+	pub dry_run: bool,
+	// the variables to read instead of the environment of the process
+	pub vars: Option<Vec<(String, String)>>,
+}
+
+impl EnvOverlay {
+	pub fn new(prefix: String) -> Self {
+		Self { prefix, separator: "__".to_string(), case: CaseFolding::Lower, dry_run: false, vars: None }
+	}
+
+	pub fn separator(mut self, separator: String) -> Self {
+		self.separator = separator;
+		self
+	}
+
+	pub fn case(mut self, case: CaseFolding) -> Self {
+		self.case = case;
+		self
+	}
+
+	// list the changes without applying them
+	pub fn dry_run(mut self) -> Self {
+		self.dry_run = true;
+		self
+	}
+
+	pub fn vars(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+		self.vars = Some(vars.into_iter().collect());
+		self
+	}
+
+	// the variables of the overlay, sorted by name so they are applied in a stable order
+	pub(crate) fn read_vars(&self) -> Vec<(String, String)> {
+		let mut vars = match &self.vars {
+			Some(vars) => vars.clone(),
+			None => std::env::vars().collect(),
+		};
+		vars.sort();
+		vars
+	}
+
+	// split a variable name into the segments after the prefix
+	// returns none if the variable does not start with the prefix
+	pub(crate) fn segments(&self, var: &str) -> Option<Vec<String>> {
+		let rest = var.strip_prefix(&self.prefix)?.strip_prefix(&self.separator)?;
+		let segments: Vec<String> = rest.split(&self.separator).map(|s| s.to_string()).collect();
+		if segments.iter().any(|s| s.is_empty()) {
+			return None;
+		}
+		Some(segments)
+	}
+}
+
+// compare names ignoring case and treating - and _ alike
+pub(crate) fn same_name(a: &str, b: &str) -> bool {
+	let fold = |s: &str| s.to_lowercase().replace('-', "_");
+	fold(a) == fold(b)
+}
+
+/// A change made or, in a dry run, planned by an environment overlay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvChange<T> {
+	pub var: String,
+	pub entity: String,
+	pub property: String,
+	// none if the property does not exist yet
+	pub old: Option<T>,
+	pub new: T,
+}
+
+// parse the value of a variable through StarValue
+// numerals with a leading zero like `007` are kept as strings, so codes and modes keep their digits
+// if the component type has no variant for the parsed value, the raw text is tried as a string
+pub fn parse_star_value<T>(raw: &str) -> Result<T, String>
+where
+	T: TryFrom<StarValue>,
+	<T as TryFrom<StarValue>>::Error: Display,
+{
+	let value: StarValue = match leading_zero(raw) {
+		true => StarValue::String(raw.to_string()),
+		false => raw.parse()?,
+	};
+	let parsed = matches!(value, StarValue::String(_));
+	match T::try_from(value) {
+		Ok(t) => Ok(t),
+		Err(_) if !parsed => T::try_from(StarValue::String(raw.to_string())).map_err(|err| err.to_string()),
+		Err(err) => Err(err.to_string()),
+	}
+}
+
+// true for numerals like `007` or `-01.5`, but not for `0` or `0.5`
+fn leading_zero(raw: &str) -> bool {
+	let digits = raw.strip_prefix(['-', '+']).unwrap_or(raw);
+	let mut chars = digits.chars();
+	chars.next() == Some('0') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
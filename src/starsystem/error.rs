@@ -0,0 +1,43 @@
+use super::{SchemaViolation, ValidationError};
+use std::fmt::Display;
+
+/// Why a property could not be added or set.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyError {
+	// the value failed constraints of its property
+	Invalid(ValidationError),
+	// the value breaks the schema of its being
+	Schema(Vec<SchemaViolation>),
+	// anything else, like a being, entity or property that does not exist
+	Other(String),
+}
+
+impl Display for PropertyError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			PropertyError::Invalid(error) => write!(f, "{}", error),
+			PropertyError::Schema(violations) => write!(f, "{}", violations.iter().map(|v| v.to_string()).collect::<Vec<String>>().join("; ")),
+			PropertyError::Other(message) => write!(f, "{}", message),
+		}
+	}
+}
+
+impl std::error::Error for PropertyError {}
+
+impl From<ValidationError> for PropertyError {
+	fn from(error: ValidationError) -> Self {
+		PropertyError::Invalid(error)
+	}
+}
+
+impl From<String> for PropertyError {
+	fn from(message: String) -> Self {
+		PropertyError::Other(message)
+	}
+}
+
+impl From<PropertyError> for String {
+	fn from(error: PropertyError) -> Self {
+		error.to_string()
+	}
+}
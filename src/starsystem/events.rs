@@ -0,0 +1,174 @@
+use std::any::{Any, TypeId};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// A double buffered queue of events of one type.
+/// Events sent during a tick can be read during that tick and the next one, then they are dropped.
+#[derive(Debug)]
+pub struct Events<E> {
+	previous: Vec<(usize, E)>,
+	current: Vec<(usize, E)>,
+	count: usize,
+}
+
+impl<E> Default for Events<E> {
+	fn default() -> Self {
+		Self { previous: Vec::new(), current: Vec::new(), count: 0 }
+	}
+}
+
+impl<E: Clone> Events<E> {
+	pub fn send(&mut self, event: E) {
+		self.current.push((self.count, event));
+		self.count += 1;
+	}
+
+	// drop the events of the previous tick and keep the ones of this tick for one more tick
+	pub fn update(&mut self) {
+		self.previous = std::mem::take(&mut self.current);
+	}
+
+	// the id of the oldest event still buffered
+	pub fn oldest(&self) -> usize {
+		self.previous.first().or(self.current.first()).map(|e| e.0).unwrap_or(self.count)
+	}
+
+	// every buffered event with an id of at least the cursor
+	// returns the events and the new cursor
+	pub fn read_from(&self, cursor: usize) -> (Vec<E>, usize) {
+		let events = self.previous.iter().chain(self.current.iter()).filter(|e| e.0 >= cursor).map(|e| e.1.clone()).collect();
+		(events, self.count)
+	}
+
+	pub fn len(&self) -> usize {
+		self.previous.len() + self.current.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+// a channel of any event type, so the bus can update all of them at once
+trait Channel: Send + Sync {
+	fn update(&self);
+}
+
+struct EventChannel<E> {
+	events: RwLock<Events<E>>,
+}
+
+impl<E: 'static + Clone + Send + Sync> Channel for EventChannel<E> {
+	fn update(&self) {
+		self.events.write().unwrap().update();
+	}
+}
+
+// every channel is kept twice, once to be updated and once to be downcast to its event type
+type Channels = BTreeMap<TypeId, (Arc<dyn Channel>, Arc<dyn Any + Send + Sync>)>;
+
+/// The typed event channels of a starsystem.
+/// Clones share the same channels.
+#[derive(Clone, Default)]
+pub struct EventBus {
+	channels: Arc<RwLock<Channels>>,
+}
+
+impl Debug for EventBus {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("EventBus").field("channels", &self.channels.read().unwrap().len()).finish()
+	}
+}
+
+impl EventBus {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	// get the channel of an event type
+	// if it does not exist, it is created
+	fn channel<E: 'static + Clone + Send + Sync>(&self) -> Arc<EventChannel<E>> {
+		let id = TypeId::of::<E>();
+		let existing = self.channels.read().unwrap().get(&id).map(|c| c.1.clone());
+		let channel = match existing {
+			Some(channel) => channel,
+			None => {
+				let channel = Arc::new(EventChannel::<E> { events: RwLock::new(Events::default()) });
+				self.channels.write().unwrap().entry(id).or_insert_with(|| (channel.clone(), channel)).1.clone()
+			}
+		};
+		// channels are keyed by the type id of their events
+		channel.downcast::<EventChannel<E>>().unwrap()
+	}
+
+	// register an event type
+	pub fn add<E: 'static + Clone + Send + Sync>(&self) {
+		self.channel::<E>();
+	}
+
+	pub fn writer<E: 'static + Clone + Send + Sync>(&self) -> EventWriter<E> {
+		EventWriter { channel: self.channel::<E>() }
+	}
+
+	// a reader sees every event still buffered and every event sent after it was created once
+	pub fn reader<E: 'static + Clone + Send + Sync>(&self) -> EventReader<E> {
+		let channel = self.channel::<E>();
+		let cursor = channel.events.read().unwrap().oldest();
+		EventReader { channel, cursor: AtomicUsize::new(cursor) }
+	}
+
+	// advance every channel by one tick
+	pub fn update(&self) {
+		for channel in self.channels.read().unwrap().values() {
+			channel.0.update();
+		}
+	}
+}
+
+/// Sends events of one type.
+#[derive(Clone)]
+pub struct EventWriter<E> {
+	channel: Arc<EventChannel<E>>,
+}
+
+impl<E: 'static + Clone + Send + Sync> EventWriter<E> {
+	pub fn send(&self, event: E) {
+		self.channel.events.write().unwrap().send(event);
+	}
+}
+
+/// Reads events of one type, each event is seen once.
+/// The cursor is kept inside the reader, so it can be moved into a system and read from every run.
+pub struct EventReader<E> {
+	channel: Arc<EventChannel<E>>,
+	cursor: AtomicUsize,
+}
+
+impl<E> Clone for EventReader<E> {
+	fn clone(&self) -> Self {
+		Self { channel: self.channel.clone(), cursor: AtomicUsize::new(self.cursor.load(Ordering::SeqCst)) }
+	}
+}
+
+impl<E: 'static + Clone + Send + Sync> EventReader<E> {
+	// the events sent since the last read
+	pub fn read(&self) -> Vec<E> {
+		let events = self.channel.events.read().unwrap();
+		let (res, cursor) = events.read_from(self.cursor.load(Ordering::SeqCst));
+		self.cursor.store(cursor, Ordering::SeqCst);
+		res
+	}
+
+	// the number of events that have not been read yet
+	pub fn len(&self) -> usize {
+		let cursor = self.cursor.load(Ordering::SeqCst);
+		let events = self.channel.events.read().unwrap();
+		events.previous.iter().chain(events.current.iter()).filter(|e| e.0 >= cursor).count()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
@@ -0,0 +1,131 @@
+use super::{AscendedBeing, Being, EnumIndex, StarValue, Uid, ValueMapping, World};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use strum::IntoEnumIterator;
+
+/// Opens a reference in a string property, like `${config/server/host}`.
+/// `$${` is an escaped `${` and is kept as text.
+pub const REFERENCE_OPEN: &str = "${";
+
+// expands references in the string properties of beings
+// referenced beings are ascended once and every expanded property is cached by its path
+pub(crate) struct Interpolator<'a, T, M> {
+	beings: &'a [Being],
+	worlds: &'a BTreeMap<Uid, World<T>>,
+	mapping: &'a M,
+	ascended: BTreeMap<String, AscendedBeing<T>>,
+	expanded: BTreeMap<String, String>,
+	// the paths being expanded, to detect cycles
+	stack: Vec<String>,
+}
+
+impl<'a, T: 'static + Sync + Send + Serialize + for<'b> Deserialize<'b> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug, M: ValueMapping<T>> Interpolator<'a, T, M> {
+	pub fn new(beings: &'a [Being], worlds: &'a BTreeMap<Uid, World<T>>, mapping: &'a M) -> Self {
+		Self { beings, worlds, mapping, ascended: BTreeMap::new(), expanded: BTreeMap::new(), stack: Vec::new() }
+	}
+
+	// expand the references of every string property of an ascended being
+	pub fn being(&mut self, mut being: AscendedBeing<T>) -> Result<AscendedBeing<T>, String> {
+		for entity in being.entities.iter_mut() {
+			for component in entity.components.iter_mut() {
+				component.data = self.component(&being.name, &entity.name, &component.name, &component.data)?;
+			}
+		}
+		Ok(being)
+	}
+
+	// expand the references of a component if it is a string
+	// other components are returned as they are
+	pub fn component(&mut self, being: &str, entity: &str, property: &str, data: &T) -> Result<T, String> {
+		let path = format!("{}/{}/{}", being, entity, property);
+		match self.mapping.to_scalar(data).map_err(|err| format!("{}: {}", path, err))? {
+			StarValue::String(text) if text.contains(REFERENCE_OPEN) => {
+				let text = self.expand_path(&path, being, &text)?;
+				self.mapping.to_component(StarValue::String(text)).map_err(|err| format!("{}: {}", path, err))
+			}
+			_ => Ok(data.clone()),
+		}
+	}
+
+	fn expand_path(&mut self, path: &str, being: &str, text: &str) -> Result<String, String> {
+		if let Some(start) = self.stack.iter().position(|p| p == path) {
+			let mut cycle = self.stack[start..].to_vec();
+			cycle.push(path.to_string());
+			return Err(format!("cycle in references: {}", cycle.join(" -> ")));
+		}
+		self.stack.push(path.to_string());
+		let expanded = self.expand(path, being, text);
+		self.stack.pop();
+		expanded
+	}
+
+	// replace every reference in a text by the text of the property it names
+	// `${entity/property}` names a property of the same being, `${being/entity/property}` one of any being
+	// a reference that names a property of the same being is read that way, even if it would name one of another being too
+	fn expand(&mut self, path: &str, being: &str, text: &str) -> Result<String, String> {
+		let mut out = String::with_capacity(text.len());
+		let mut rest = text;
+		while let Some(i) = rest.find(REFERENCE_OPEN) {
+			// an escaped reference
+			if rest[..i].ends_with('$') {
+				out.push_str(&rest[..i - 1]);
+				out.push_str(REFERENCE_OPEN);
+				rest = &rest[i + REFERENCE_OPEN.len()..];
+				continue;
+			}
+			out.push_str(&rest[..i]);
+			rest = &rest[i + REFERENCE_OPEN.len()..];
+			let end = rest.find('}').ok_or(format!("{}: unterminated reference in {:?}", path, text))?;
+			let reference = &rest[..end];
+			rest = &rest[end + 1..];
+
+			// property names may hold slashes, so a reference is read as entity/property of the same being first
+			let short = format!("{}/{}", being, reference);
+			let target = match reference.matches('/').count() {
+				0 => return Err(format!("{}: invalid reference ${{{}}}, expected being/entity/property or entity/property", path, reference)),
+				1 => short,
+				_ if self.data(&short)?.is_some() => short,
+				_ => reference.to_string(),
+			};
+			out.push_str(&self.lookup(path, &target)?);
+		}
+		out.push_str(rest);
+		Ok(out)
+	}
+
+	// the data of the property at a being/entity/property path, if there is one
+	fn data(&mut self, target: &str) -> Result<Option<T>, String> {
+		let mut segments = target.splitn(3, '/');
+		let (being, entity, property) = match (segments.next(), segments.next(), segments.next()) {
+			(Some(b), Some(e), Some(p)) => (b, e, p),
+			_ => return Ok(None),
+		};
+		if !self.ascended.contains_key(being) {
+			if let Some(b) = self.beings.iter().find(|b| b.name == being) {
+				self.ascended.insert(being.to_string(), b.ascend(self.worlds)?);
+			}
+		}
+		Ok(self.ascended.get(being).and_then(|b| b.entities.iter().find(|e| e.name == entity)).and_then(|e| e.components.iter().find(|c| c.name == property)).map(|c| c.data.clone()))
+	}
+
+	// the expanded text of the property at a path
+	fn lookup(&mut self, from: &str, target: &str) -> Result<String, String> {
+		if let Some(text) = self.expanded.get(target) {
+			return Ok(text.clone());
+		}
+		let data = self.data(target)?.ok_or(format!("{}: unresolved reference ${{{}}}", from, target))?;
+		let being = target.split('/').next().unwrap_or_default();
+
+		let text = match self.mapping.to_scalar(&data).map_err(|err| format!("{}: {}", target, err))? {
+			StarValue::String(text) if text.contains(REFERENCE_OPEN) => self.expand_path(target, being, &text)?,
+			StarValue::String(text) => text,
+			StarValue::Bool(b) => b.to_string(),
+			StarValue::Int(i) => i.to_string(),
+			StarValue::Float(f) => f.to_string(),
+			other => return Err(format!("{}: cannot interpolate the {} value of ${{{}}}", from, other.type_name(), target)),
+		};
+		self.expanded.insert(target.to_string(), text.clone());
+		Ok(text)
+	}
+}
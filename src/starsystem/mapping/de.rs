@@ -0,0 +1,322 @@
+use super::{AscendedBeing, MappingError, StarValue, ValueMapping, SCALAR_PROPERTY};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+// the entities and properties of a being, rebuilt into the shape they were serialized from
+enum Tree {
+	Scalar(StarValue),
+	Branch(Vec<(String, Tree)>),
+}
+
+impl Tree {
+	// insert a scalar at a path of keys below this branch
+	fn insert(&mut self, keys: &[&str], scalar: StarValue) -> Result<(), String> {
+		let branch = match self {
+			Tree::Branch(branch) => branch,
+			Tree::Scalar(_) => return Err("a property is stored both as a value and below it".to_string()),
+		};
+		let (key, rest) = match keys.split_first() {
+			Some(k) => k,
+			None => return Err("empty property name".to_string()),
+		};
+		if rest.is_empty() {
+			match branch.iter_mut().find(|(k, _)| k == key) {
+				Some(existing) => existing.1 = Tree::Scalar(scalar),
+				None => branch.push((key.to_string(), Tree::Scalar(scalar))),
+			}
+			return Ok(());
+		}
+		if !branch.iter().any(|(k, _)| k == key) {
+			branch.push((key.to_string(), Tree::Branch(Vec::new())));
+		}
+		branch.iter_mut().find(|(k, _)| k == key).unwrap().1.insert(rest, scalar)
+	}
+}
+
+// read an ascended being into any deserializable type
+// the reverse of `entities`, errors carry the path of the property that failed like `config/headers/Content-Type`
+pub(crate) fn extract<S: DeserializeOwned, T>(being: &AscendedBeing<T>, mapping: &impl ValueMapping<T>) -> Result<S, String> {
+	let mut entities = Vec::with_capacity(being.entities.len());
+	for entity in being.entities.iter() {
+		let path = format!("{}/{}", being.name, entity.name);
+		// a top level scalar is stored as an entity with a single property named `SCALAR_PROPERTY`
+		let tree = match entity.components.as_slice() {
+			[c] if c.name == SCALAR_PROPERTY => Tree::Scalar(mapping.to_scalar(&c.data).map_err(|e| format!("{}: {}", path, e))?),
+			components => {
+				let mut tree = Tree::Branch(Vec::new());
+				for c in components {
+					let scalar = mapping.to_scalar(&c.data).map_err(|e| format!("{}/{}: {}", path, c.name, e))?;
+					let keys: Vec<&str> = c.name.split('/').collect();
+					tree.insert(&keys, scalar).map_err(|e| format!("{}/{}: {}", path, c.name, e))?;
+				}
+				tree
+			}
+		};
+		entities.push((entity.name.clone(), tree));
+	}
+	S::deserialize(TreeDeserializer { tree: Tree::Branch(entities), path: being.name.clone() }).map_err(|e| e.to_string())
+}
+
+// read a single value into any deserializable type, the reverse of `value`
+// errors carry the given path
+pub(crate) fn from_value<S: DeserializeOwned>(value: StarValue, path: &str) -> Result<S, String> {
+	S::deserialize(TreeDeserializer { tree: Tree::Scalar(value), path: path.to_string() }).map_err(|e| e.to_string())
+}
+
+struct TreeDeserializer {
+	tree: Tree,
+	path: String,
+}
+
+impl<'de> de::Deserializer<'de> for TreeDeserializer {
+	type Error = MappingError;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MappingError> {
+		let path = self.path.clone();
+		let res = match self.tree {
+			Tree::Scalar(StarValue::Null) => visitor.visit_unit(),
+			Tree::Scalar(StarValue::Bool(b)) => visitor.visit_bool(b),
+			Tree::Scalar(StarValue::Int(i)) => visitor.visit_i64(i),
+			Tree::Scalar(StarValue::Float(f)) => visitor.visit_f64(f),
+			Tree::Scalar(StarValue::String(s)) => visitor.visit_string(s),
+			Tree::Scalar(StarValue::Bytes(b)) => visitor.visit_byte_buf(b),
+			Tree::Scalar(StarValue::List(items)) => {
+				let items = items.into_iter().enumerate().map(|(i, item)| (i.to_string(), Tree::Scalar(item))).collect();
+				visitor.visit_seq(SeqAccess { items: Vec::into_iter(items), path: path.clone() })
+			}
+			Tree::Scalar(StarValue::Map(fields)) => {
+				let fields = fields.into_iter().map(|(k, v)| (k, Tree::Scalar(v))).collect();
+				visitor.visit_map(MapAccess { fields: Vec::into_iter(fields), value: None, path: path.clone() })
+			}
+			Tree::Branch(fields) => visitor.visit_map(MapAccess { fields: fields.into_iter(), value: None, path: path.clone() }),
+		};
+		res.map_err(|e| e.at(&path))
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MappingError> {
+		match self.tree {
+			Tree::Scalar(StarValue::Null) => visitor.visit_none(),
+			_ => visitor.visit_some(self),
+		}
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, MappingError> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	// sequences are stored as branches keyed by their position
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MappingError> {
+		let path = self.path.clone();
+		match self.tree {
+			Tree::Branch(fields) => {
+				let mut items = Vec::with_capacity(fields.len());
+				for (key, tree) in fields {
+					match key.parse::<usize>() {
+						Ok(i) => items.push((i, key, tree)),
+						Err(_) => return Err(MappingError::new(format!("expected a sequence, found the key {}", key)).at(&path)),
+					}
+				}
+				items.sort_by_key(|i| i.0);
+				let items: Vec<(String, Tree)> = items.into_iter().map(|i| (i.1, i.2)).collect();
+				visitor.visit_seq(SeqAccess { items: items.into_iter(), path: path.clone() }).map_err(|e| e.at(&path))
+			}
+			// an empty nested sequence is stored as null
+			Tree::Scalar(StarValue::Null) => visitor.visit_seq(SeqAccess { items: Vec::new().into_iter(), path: path.clone() }).map_err(|e| e.at(&path)),
+			tree => TreeDeserializer { tree, path }.deserialize_any(visitor),
+		}
+	}
+
+	// an empty nested map or struct is stored as null
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MappingError> {
+		match self.tree {
+			Tree::Scalar(StarValue::Null) => visitor.visit_map(MapAccess { fields: Vec::new().into_iter(), value: None, path: self.path.clone() }).map_err(|e| e.at(&self.path)),
+			_ => self.deserialize_any(visitor),
+		}
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, MappingError> {
+		self.deserialize_map(visitor)
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, MappingError> {
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, MappingError> {
+		self.deserialize_seq(visitor)
+	}
+
+	// unit variants are stored as their name, other variants as a branch with the variant as its only key
+	fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, MappingError> {
+		let path = self.path.clone();
+		let (variant, content) = match self.tree {
+			Tree::Scalar(StarValue::String(variant)) => (variant, None),
+			Tree::Branch(mut fields) if fields.len() == 1 => {
+				let (variant, tree) = fields.remove(0);
+				let content = TreeDeserializer { tree, path: format!("{}/{}", path, variant) };
+				(variant, Some(content))
+			}
+			_ => return Err(MappingError::new("expected an enum variant".to_string()).at(&path)),
+		};
+		visitor.visit_enum(EnumAccess { variant, content, path: path.clone() }).map_err(|e| e.at(&path))
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct identifier ignored_any
+	}
+}
+
+struct SeqAccess {
+	items: std::vec::IntoIter<(String, Tree)>,
+	path: String,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+	type Error = MappingError;
+
+	fn next_element_seed<E: DeserializeSeed<'de>>(&mut self, seed: E) -> Result<Option<E::Value>, MappingError> {
+		match self.items.next() {
+			Some((key, tree)) => seed.deserialize(TreeDeserializer { tree, path: format!("{}/{}", self.path, key) }).map(Some),
+			None => Ok(None),
+		}
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.items.len())
+	}
+}
+
+struct MapAccess {
+	fields: std::vec::IntoIter<(String, Tree)>,
+	value: Option<TreeDeserializer>,
+	path: String,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+	type Error = MappingError;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, MappingError> {
+		match self.fields.next() {
+			Some((key, tree)) => {
+				self.value = Some(TreeDeserializer { tree, path: format!("{}/{}", self.path, key) });
+				seed.deserialize(KeyDeserializer { key }).map(Some)
+			}
+			None => Ok(None),
+		}
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, MappingError> {
+		match self.value.take() {
+			Some(value) => seed.deserialize(value),
+			None => Err(MappingError::new("map value without a key".to_string())),
+		}
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.fields.len())
+	}
+}
+
+// property names are strings, numbers and booleans are parsed back when they are asked for
+struct KeyDeserializer {
+	key: String,
+}
+
+macro_rules! parse_key {
+	($($method:ident $visit:ident $t:ty),*) => {
+		$(
+			fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MappingError> {
+				match self.key.parse::<$t>() {
+					Ok(v) => visitor.$visit(v),
+					Err(_) => visitor.visit_string(self.key),
+				}
+			}
+		)*
+	};
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer {
+	type Error = MappingError;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MappingError> {
+		visitor.visit_string(self.key)
+	}
+
+	parse_key! {
+		deserialize_bool visit_bool bool,
+		deserialize_i8 visit_i8 i8,
+		deserialize_i16 visit_i16 i16,
+		deserialize_i32 visit_i32 i32,
+		deserialize_i64 visit_i64 i64,
+		deserialize_u8 visit_u8 u8,
+		deserialize_u16 visit_u16 u16,
+		deserialize_u32 visit_u32 u32,
+		deserialize_u64 visit_u64 u64,
+		deserialize_f32 visit_f32 f32,
+		deserialize_f64 visit_f64 f64
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, MappingError> {
+		visitor.visit_enum(EnumAccess { variant: self.key, content: None, path: String::new() })
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, MappingError> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	forward_to_deserialize_any! {
+		i128 u128 char str string bytes byte_buf option unit unit_struct
+		seq tuple tuple_struct map struct identifier ignored_any
+	}
+}
+
+struct EnumAccess {
+	variant: String,
+	content: Option<TreeDeserializer>,
+	path: String,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+	type Error = MappingError;
+	type Variant = VariantAccess;
+
+	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, VariantAccess), MappingError> {
+		let variant = seed.deserialize(self.variant.into_deserializer())?;
+		Ok((variant, VariantAccess { content: self.content, path: self.path }))
+	}
+}
+
+struct VariantAccess {
+	content: Option<TreeDeserializer>,
+	path: String,
+}
+
+impl VariantAccess {
+	fn content(self) -> Result<TreeDeserializer, MappingError> {
+		self.content.ok_or_else(|| MappingError::new("expected an enum variant with data".to_string()).at(&self.path))
+	}
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccess {
+	type Error = MappingError;
+
+	fn unit_variant(self) -> Result<(), MappingError> {
+		match self.content {
+			None => Ok(()),
+			Some(content) => Err(MappingError::new("expected a unit variant".to_string()).at(&content.path)),
+		}
+	}
+
+	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, MappingError> {
+		seed.deserialize(self.content()?)
+	}
+
+	fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, MappingError> {
+		de::Deserializer::deserialize_seq(self.content()?, visitor)
+	}
+
+	fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, MappingError> {
+		de::Deserializer::deserialize_any(self.content()?, visitor)
+	}
+}
@@ -0,0 +1,86 @@
+use super::{AscendedBeing, StarValue};
+use std::fmt::Display;
+
+mod de;
+mod ser;
+
+pub(crate) use de::{extract, from_value};
+pub(crate) use ser::{entities, value};
+
+/// The name of the only property of an entity that holds a top level scalar of a stored struct.
+/// Reserved, no nested field may be stored under this name.
+pub const SCALAR_PROPERTY: &str = ".";
+
+/// Converts between the scalars of a serde data model and the component type of a starsystem.
+/// Used by `insert_serialize_with` to store any `Serialize` type in a being.
+pub trait ValueMapping<T> {
+	// convert a scalar to a component
+	// scalars are null, bool, int, float, string or bytes
+	fn to_component(&self, scalar: StarValue) -> Result<T, String>;
+
+	// convert a component back to a scalar
+	fn to_scalar(&self, value: &T) -> Result<StarValue, String>;
+}
+
+/// Maps scalars through the conversions of `StarValue`.
+/// Works for `StarValue` itself and for any type that converts from and to it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StarValueMapping;
+
+impl<T: TryFrom<StarValue> + Into<StarValue> + Clone> ValueMapping<T> for StarValueMapping
+where
+	T::Error: Display,
+{
+	fn to_component(&self, scalar: StarValue) -> Result<T, String> {
+		T::try_from(scalar).map_err(|e| e.to_string())
+	}
+
+	fn to_scalar(&self, value: &T) -> Result<StarValue, String> {
+		Ok(value.clone().into())
+	}
+}
+
+// the error of the serializer and deserializer of beings
+// the path is the being, entity and property the error happened at
+#[derive(Debug)]
+pub(crate) struct MappingError {
+	pub message: String,
+	pub path: Option<String>,
+}
+
+impl MappingError {
+	pub fn new(message: String) -> Self {
+		Self { message, path: None }
+	}
+
+	// set the path of the error, unless a deeper one was already set
+	pub fn at(mut self, path: &str) -> Self {
+		if self.path.is_none() {
+			self.path = Some(path.to_string());
+		}
+		self
+	}
+}
+
+impl Display for MappingError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.path {
+			Some(path) => write!(f, "{}: {}", path, self.message),
+			None => write!(f, "{}", self.message),
+		}
+	}
+}
+
+impl std::error::Error for MappingError {}
+
+impl serde::ser::Error for MappingError {
+	fn custom<M: Display>(msg: M) -> Self {
+		MappingError::new(msg.to_string())
+	}
+}
+
+impl serde::de::Error for MappingError {
+	fn custom<M: Display>(msg: M) -> Self {
+		MappingError::new(msg.to_string())
+	}
+}
@@ -0,0 +1,461 @@
+use super::{MappingError, StarValue, SCALAR_PROPERTY};
+use serde::ser::{self, Serialize};
+
+// a serialized value before it is split into entities and properties
+enum Node {
+	Scalar(StarValue),
+	Seq(Vec<Node>),
+	Map(Vec<(String, Node)>),
+	// none, skipped when flattening
+	Absent,
+}
+
+// (entity name, [(property name, scalar)])
+pub(crate) type Flattened = Vec<(String, Vec<(String, StarValue)>)>;
+
+// the entities of a serialized value
+// top level fields become entities and nested fields become properties named by their path below the entity
+// a top level scalar becomes an entity with a single property named `SCALAR_PROPERTY`
+pub(crate) fn entities<S: Serialize + ?Sized>(value: &S) -> Result<Flattened, String> {
+	let fields = match value.serialize(NodeSerializer).map_err(|e| e.to_string())? {
+		Node::Map(fields) => fields,
+		_ => return Err("Only structs and maps can be stored in a being".to_string()),
+	};
+	let mut entities = Vec::with_capacity(fields.len());
+	for (name, node) in fields {
+		let mut properties = Vec::new();
+		match node {
+			Node::Scalar(scalar) => properties.push((SCALAR_PROPERTY.to_string(), scalar)),
+			Node::Absent => continue,
+			node => {
+				flatten(node, String::new(), &mut properties);
+				if properties.iter().any(|(property, _)| property == SCALAR_PROPERTY) {
+					return Err(format!("{}/{}: the property name {} is reserved", name, SCALAR_PROPERTY, SCALAR_PROPERTY));
+				}
+			}
+		}
+		entities.push((name, properties));
+	}
+	Ok(entities)
+}
+
+// a serialized value as a single value, sequences become lists and structs and maps become maps
+pub(crate) fn value<S: Serialize + ?Sized>(value: &S) -> Result<StarValue, String> {
+	value.serialize(NodeSerializer).map(Node::into_value).map_err(|e| e.to_string())
+}
+
+impl Node {
+	fn into_value(self) -> StarValue {
+		match self {
+			Node::Scalar(scalar) => scalar,
+			Node::Seq(items) => StarValue::List(items.into_iter().map(Node::into_value).collect()),
+			Node::Map(fields) => StarValue::Map(fields.into_iter().filter(|f| !matches!(f.1, Node::Absent)).map(|(k, v)| (k, v.into_value())).collect()),
+			Node::Absent => StarValue::Null,
+		}
+	}
+}
+
+// a nested sequence or map that leaves no properties is stored as null at its path, so it is read back as empty
+fn flatten(node: Node, path: String, properties: &mut Vec<(String, StarValue)>) {
+	let join = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}/{}", path, key) };
+	let before = properties.len();
+	match node {
+		Node::Scalar(scalar) => properties.push((path, scalar)),
+		Node::Seq(items) => {
+			for (i, item) in items.into_iter().enumerate() {
+				flatten(item, join(&i.to_string()), properties);
+			}
+			if properties.len() == before && !path.is_empty() {
+				properties.push((path, StarValue::Null));
+			}
+		}
+		Node::Map(fields) => {
+			for (key, item) in fields {
+				flatten(item, join(&key), properties);
+			}
+			if properties.len() == before && !path.is_empty() {
+				properties.push((path, StarValue::Null));
+			}
+		}
+		Node::Absent => {}
+	}
+}
+
+struct NodeSerializer;
+
+impl ser::Serializer for NodeSerializer {
+	type Ok = Node;
+	type Error = MappingError;
+	type SerializeSeq = SeqSerializer;
+	type SerializeTuple = SeqSerializer;
+	type SerializeTupleStruct = SeqSerializer;
+	type SerializeTupleVariant = VariantSerializer<SeqSerializer>;
+	type SerializeMap = MapSerializer;
+	type SerializeStruct = MapSerializer;
+	type SerializeStructVariant = VariantSerializer<MapSerializer>;
+
+	fn serialize_bool(self, v: bool) -> Result<Node, MappingError> {
+		Ok(Node::Scalar(StarValue::Bool(v)))
+	}
+
+	fn serialize_i8(self, v: i8) -> Result<Node, MappingError> {
+		self.serialize_i64(v as i64)
+	}
+
+	fn serialize_i16(self, v: i16) -> Result<Node, MappingError> {
+		self.serialize_i64(v as i64)
+	}
+
+	fn serialize_i32(self, v: i32) -> Result<Node, MappingError> {
+		self.serialize_i64(v as i64)
+	}
+
+	fn serialize_i64(self, v: i64) -> Result<Node, MappingError> {
+		Ok(Node::Scalar(StarValue::Int(v)))
+	}
+
+	fn serialize_u8(self, v: u8) -> Result<Node, MappingError> {
+		self.serialize_i64(v as i64)
+	}
+
+	fn serialize_u16(self, v: u16) -> Result<Node, MappingError> {
+		self.serialize_i64(v as i64)
+	}
+
+	fn serialize_u32(self, v: u32) -> Result<Node, MappingError> {
+		self.serialize_i64(v as i64)
+	}
+
+	fn serialize_u64(self, v: u64) -> Result<Node, MappingError> {
+		StarValue::try_from(v).map(Node::Scalar).map_err(MappingError::new)
+	}
+
+	fn serialize_f32(self, v: f32) -> Result<Node, MappingError> {
+		self.serialize_f64(v as f64)
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<Node, MappingError> {
+		Ok(Node::Scalar(StarValue::Float(v)))
+	}
+
+	fn serialize_char(self, v: char) -> Result<Node, MappingError> {
+		self.serialize_str(&v.to_string())
+	}
+
+	fn serialize_str(self, v: &str) -> Result<Node, MappingError> {
+		Ok(Node::Scalar(StarValue::String(v.to_string())))
+	}
+
+	fn serialize_bytes(self, v: &[u8]) -> Result<Node, MappingError> {
+		Ok(Node::Scalar(StarValue::Bytes(v.to_vec())))
+	}
+
+	fn serialize_none(self) -> Result<Node, MappingError> {
+		Ok(Node::Absent)
+	}
+
+	fn serialize_some<V: Serialize + ?Sized>(self, value: &V) -> Result<Node, MappingError> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<Node, MappingError> {
+		Ok(Node::Scalar(StarValue::Null))
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Node, MappingError> {
+		self.serialize_unit()
+	}
+
+	fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Node, MappingError> {
+		self.serialize_str(variant)
+	}
+
+	fn serialize_newtype_struct<V: Serialize + ?Sized>(self, _name: &'static str, value: &V) -> Result<Node, MappingError> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<V: Serialize + ?Sized>(self, _name: &'static str, _index: u32, variant: &'static str, value: &V) -> Result<Node, MappingError> {
+		Ok(Node::Map(vec![(variant.to_string(), value.serialize(self)?)]))
+	}
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, MappingError> {
+		Ok(SeqSerializer { items: Vec::with_capacity(len.unwrap_or(0)) })
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, MappingError> {
+		self.serialize_seq(Some(len))
+	}
+
+	fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer, MappingError> {
+		self.serialize_seq(Some(len))
+	}
+
+	fn serialize_tuple_variant(self, _name: &'static str, _index: u32, variant: &'static str, len: usize) -> Result<VariantSerializer<SeqSerializer>, MappingError> {
+		Ok(VariantSerializer { variant, inner: SeqSerializer { items: Vec::with_capacity(len) } })
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer, MappingError> {
+		Ok(MapSerializer { fields: Vec::with_capacity(len.unwrap_or(0)), key: None })
+	}
+
+	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer, MappingError> {
+		self.serialize_map(Some(len))
+	}
+
+	fn serialize_struct_variant(self, _name: &'static str, _index: u32, variant: &'static str, len: usize) -> Result<VariantSerializer<MapSerializer>, MappingError> {
+		Ok(VariantSerializer { variant, inner: MapSerializer { fields: Vec::with_capacity(len), key: None } })
+	}
+}
+
+struct SeqSerializer {
+	items: Vec<Node>,
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+	type Ok = Node;
+	type Error = MappingError;
+
+	fn serialize_element<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), MappingError> {
+		self.items.push(value.serialize(NodeSerializer)?);
+		Ok(())
+	}
+
+	fn end(self) -> Result<Node, MappingError> {
+		Ok(Node::Seq(self.items))
+	}
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+	type Ok = Node;
+	type Error = MappingError;
+
+	fn serialize_element<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), MappingError> {
+		ser::SerializeSeq::serialize_element(self, value)
+	}
+
+	fn end(self) -> Result<Node, MappingError> {
+		ser::SerializeSeq::end(self)
+	}
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+	type Ok = Node;
+	type Error = MappingError;
+
+	fn serialize_field<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), MappingError> {
+		ser::SerializeSeq::serialize_element(self, value)
+	}
+
+	fn end(self) -> Result<Node, MappingError> {
+		ser::SerializeSeq::end(self)
+	}
+}
+
+struct MapSerializer {
+	fields: Vec<(String, Node)>,
+	key: Option<String>,
+}
+
+impl ser::SerializeMap for MapSerializer {
+	type Ok = Node;
+	type Error = MappingError;
+
+	fn serialize_key<K: Serialize + ?Sized>(&mut self, key: &K) -> Result<(), MappingError> {
+		let key = key.serialize(KeySerializer)?;
+		// keys are joined into paths with slashes
+		if key.contains('/') {
+			return Err(MappingError::new(format!("map key {} contains a slash", key)));
+		}
+		self.key = Some(key);
+		Ok(())
+	}
+
+	fn serialize_value<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), MappingError> {
+		let key = self.key.take().ok_or(MappingError::new("map value without a key".to_string()))?;
+		self.fields.push((key, value.serialize(NodeSerializer)?));
+		Ok(())
+	}
+
+	fn end(self) -> Result<Node, MappingError> {
+		Ok(Node::Map(self.fields))
+	}
+}
+
+impl ser::SerializeStruct for MapSerializer {
+	type Ok = Node;
+	type Error = MappingError;
+
+	fn serialize_field<V: Serialize + ?Sized>(&mut self, key: &'static str, value: &V) -> Result<(), MappingError> {
+		self.fields.push((key.to_string(), value.serialize(NodeSerializer)?));
+		Ok(())
+	}
+
+	fn end(self) -> Result<Node, MappingError> {
+		Ok(Node::Map(self.fields))
+	}
+}
+
+// an enum variant with fields is stored as a map with the variant as its only key
+struct VariantSerializer<S> {
+	variant: &'static str,
+	inner: S,
+}
+
+impl ser::SerializeTupleVariant for VariantSerializer<SeqSerializer> {
+	type Ok = Node;
+	type Error = MappingError;
+
+	fn serialize_field<V: Serialize + ?Sized>(&mut self, value: &V) -> Result<(), MappingError> {
+		ser::SerializeSeq::serialize_element(&mut self.inner, value)
+	}
+
+	fn end(self) -> Result<Node, MappingError> {
+		Ok(Node::Map(vec![(self.variant.to_string(), ser::SerializeSeq::end(self.inner)?)]))
+	}
+}
+
+impl ser::SerializeStructVariant for VariantSerializer<MapSerializer> {
+	type Ok = Node;
+	type Error = MappingError;
+
+	fn serialize_field<V: Serialize + ?Sized>(&mut self, key: &'static str, value: &V) -> Result<(), MappingError> {
+		ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+	}
+
+	fn end(self) -> Result<Node, MappingError> {
+		Ok(Node::Map(vec![(self.variant.to_string(), ser::SerializeStruct::end(self.inner)?)]))
+	}
+}
+
+// map keys become part of property names, so they have to be strings or simple scalars
+struct KeySerializer;
+
+impl KeySerializer {
+	fn unsupported(kind: &str) -> MappingError {
+		MappingError::new(format!("map keys must be strings, numbers or booleans, found {}", kind))
+	}
+}
+
+impl ser::Serializer for KeySerializer {
+	type Ok = String;
+	type Error = MappingError;
+	type SerializeSeq = ser::Impossible<String, MappingError>;
+	type SerializeTuple = ser::Impossible<String, MappingError>;
+	type SerializeTupleStruct = ser::Impossible<String, MappingError>;
+	type SerializeTupleVariant = ser::Impossible<String, MappingError>;
+	type SerializeMap = ser::Impossible<String, MappingError>;
+	type SerializeStruct = ser::Impossible<String, MappingError>;
+	type SerializeStructVariant = ser::Impossible<String, MappingError>;
+
+	fn serialize_bool(self, v: bool) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_i8(self, v: i8) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_i16(self, v: i16) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_i32(self, v: i32) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_i64(self, v: i64) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_u8(self, v: u8) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_u16(self, v: u16) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_u32(self, v: u32) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_u64(self, v: u64) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_f32(self, v: f32) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_char(self, v: char) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_str(self, v: &str) -> Result<String, MappingError> {
+		Ok(v.to_string())
+	}
+
+	fn serialize_bytes(self, _v: &[u8]) -> Result<String, MappingError> {
+		Err(Self::unsupported("bytes"))
+	}
+
+	fn serialize_none(self) -> Result<String, MappingError> {
+		Err(Self::unsupported("none"))
+	}
+
+	fn serialize_some<V: Serialize + ?Sized>(self, value: &V) -> Result<String, MappingError> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<String, MappingError> {
+		Err(Self::unsupported("unit"))
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<String, MappingError> {
+		Err(Self::unsupported("unit struct"))
+	}
+
+	fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<String, MappingError> {
+		Ok(variant.to_string())
+	}
+
+	fn serialize_newtype_struct<V: Serialize + ?Sized>(self, _name: &'static str, value: &V) -> Result<String, MappingError> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<V: Serialize + ?Sized>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &V) -> Result<String, MappingError> {
+		Err(Self::unsupported("enum variant with data"))
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, MappingError> {
+		Err(Self::unsupported("sequence"))
+	}
+
+	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, MappingError> {
+		Err(Self::unsupported("tuple"))
+	}
+
+	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, MappingError> {
+		Err(Self::unsupported("tuple struct"))
+	}
+
+	fn serialize_tuple_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, MappingError> {
+		Err(Self::unsupported("enum variant with data"))
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, MappingError> {
+		Err(Self::unsupported("map"))
+	}
+
+	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, MappingError> {
+		Err(Self::unsupported("struct"))
+	}
+
+	fn serialize_struct_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, MappingError> {
+		Err(Self::unsupported("enum variant with data"))
+	}
+}
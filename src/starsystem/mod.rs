@@ -1,45 +1,149 @@
+pub use actor::{Reply, StarSystemActor};
 use super::EnumIndex;
 pub use super::Uid;
-use super::World;
+use super::{Component, EntityBatch, StarValue, StorageKind, World};
+use rayon::prelude::*;
+use super::{Commands, Query, QueryItem, Removal, Schedule, SystemContext};
 pub use ascend::{AscendedBeing, AscendedComponent, AscendedEntity};
 pub use being::Being;
+pub use clone::{ClonePlacement, ClonedBeing};
+pub use conflict::{NameConflictPolicy, NameConflicts};
+pub use computed::{ComputeFn, ComputedProperties, ComputedProperty};
+pub use env::{parse_star_value, CaseFolding, EnvChange, EnvOverlay};
+pub use error::PropertyError;
+pub use events::{EventBus, EventReader, EventWriter, Events};
+pub use interpolate::REFERENCE_OPEN;
+use interpolate::Interpolator;
+pub use resolve::{Resolved, ResolvedEntity, ResolvedProperty, REMOVAL_MARKER};
+pub use constraints::{Constraint, ConstraintFailure, ConstraintKind, Constraints, Pattern, ValidationError};
+pub use schema::{BeingSchema, EntitySchema, PropertySchema, SchemaMode, SchemaViolation, ViolationKind};
+pub use resources::{Resource, Resources};
+pub use mapping::{StarValueMapping, ValueMapping, SCALAR_PROPERTY};
+pub use shared::SharedStarSystem;
+pub use spawn::{BeingSpec, EntitySpec, PropertySpec, SpawnedBeing, SpawnedEntity};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+pub use template::{Template, TemplateLink};
 pub use starentity::{StarEntity, StarEntityLocation, StarEntityProperty};
+use std::any::Any;
 use std::collections::BTreeMap;
-use std::fmt::Debug;
+use std::sync::Arc;
+use std::fmt::{Debug, Display};
 use strum::IntoEnumIterator;
 
+mod actor;
 mod ascend;
 mod being;
+mod clone;
+mod computed;
+mod conflict;
+mod constraints;
+mod env;
+mod error;
+mod events;
+mod interpolate;
+mod mapping;
+mod resolve;
+mod resources;
+mod schema;
+mod shared;
+mod spawn;
 mod starentity;
+mod template;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StarSystem<T> {
 	pub worlds: BTreeMap<Uid, World<T>>,
 	pub beings: Vec<Being>,
+	#[serde(skip)]
+	pub schedule: Schedule<T>,
+	// the current tick, used to stamp added, changed and removed properties
+	#[serde(default)]
+	pub tick: u64,
+	// values that are not tied to any being
+	#[serde(default)]
+	pub resources: Resources<T>,
+	// typed event channels between systems
+	#[serde(skip)]
+	pub events: EventBus,
+	// the kind of storage new worlds keep their components in
+	#[serde(default)]
+	pub storage: StorageKind,
+	// properties derived from other properties
+	#[serde(skip)]
+	pub computed: ComputedProperties<T>,
+	// the schemas beings are held to, by being name
+	#[serde(default)]
+	pub schemas: BTreeMap<String, BeingSchema<T>>,
+	#[serde(default)]
+	pub schema_mode: SchemaMode,
+	// violations let through in warn mode
+	#[serde(skip)]
+	pub schema_warnings: Vec<SchemaViolation>,
+	// rules the values of properties must follow
+	#[serde(skip)]
+	pub constraints: Constraints<T>,
+	// templates beings stay linked to, by template name
+	#[serde(default)]
+	pub templates: BTreeMap<String, Template<T>>,
+	#[serde(default)]
+	pub template_links: BTreeMap<Uid, TemplateLink<T>>,
+	// what adding an entity or property under a name already in use does
+	#[serde(default)]
+	pub name_conflicts: NameConflicts,
 }
 
 impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> StarSystem<T> {
 	/*
 			StarSystem actions
+			every action has a synchronous `_blocking` twin, the async methods are thin wrappers around them
 	*/
 
 	// Create a new starsystem
 	pub async fn new() -> Self {
-		Self { worlds: BTreeMap::new(), beings: Vec::new() }
+		Self::new_blocking()
+	}
+
+	pub fn new_blocking() -> Self {
+		Self { worlds: BTreeMap::new(), beings: Vec::new(), schedule: Schedule::new(), tick: 0, resources: Resources::new(), events: EventBus::new(), storage: StorageKind::Sparse, computed: ComputedProperties::new(), schemas: BTreeMap::new(), schema_mode: SchemaMode::Reject, schema_warnings: Vec::new(), constraints: Constraints::new(), templates: BTreeMap::new(), template_links: BTreeMap::new(), name_conflicts: NameConflicts::default() }
 	}
 
 	// Create a new world
-	async fn create_world(&mut self) -> Result<Uid, String> {
-		let world = World::<T>::new().await;
+	fn create_world(&mut self) -> Result<Uid, String> {
+		let world = World::<T>::with_storage_blocking(self.storage);
+		world.set_tick(self.tick);
 		self.worlds.insert(world.id.clone(), world.clone());
 		Ok(world.id)
 	}
 
+	// choose the kind of storage worlds keep their components in
+	// existing worlds are converted
+	pub fn set_storage(&mut self, kind: StorageKind) -> &mut Self {
+		self.storage = kind;
+		for world in self.worlds.values() {
+			world.set_storage(kind);
+		}
+		self
+	}
+
 	// Create a new being
 	pub async fn conceive_being(&mut self, name: String) -> Result<Uid, String> {
+		self.conceive_being_blocking(name)
+	}
+
+	pub fn conceive_being_blocking(&mut self, name: String) -> Result<Uid, String> {
 		let being = Being::new(name);
 		self.beings.push(being.clone());
+
+		// constitute the entities the schema of the being requires
+		// if one fails the being is killed again, so no half made being is left behind
+		let required: Vec<String> = self.schemas.get(&being.name).map(|s| s.entities.values().filter(|e| e.required).map(|e| e.name.clone()).collect()).unwrap_or_default();
+		for entity in required {
+			if let Err(err) = self.constitute_being_blocking(being.id.clone(), entity) {
+				self.kill_being_blocking(being.id)?;
+				return Err(err);
+			}
+		}
 		Ok(being.id)
 	}
 
@@ -47,6 +151,10 @@ impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIt
 	// create a new being with provided id and name
 	// if the being already exists, it will be overwritten
 	pub async fn set_being(&mut self, id: Uid, name: String) -> Result<Uid, String> {
+		self.set_being_blocking(id, name)
+	}
+
+	pub fn set_being_blocking(&mut self, id: Uid, name: String) -> Result<Uid, String> {
 		let being = Being { id, entities: Vec::new(), name };
 		self.beings.push(being.clone());
 		Ok(being.id)
@@ -56,23 +164,16 @@ impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIt
 	// remove all entities from each world that the being owns
 	// remove being from beings
 	pub async fn kill_being(&mut self, id: Uid) -> Result<(), String> {
-		let mut being = None;
-		let mut being_index = None;
-		for (i, b) in self.beings.iter().enumerate() {
-			if b.id == id {
-				being = Some(b);
-				being_index = Some(i);
-				break;
-			}
-		}
+		self.kill_being_blocking(id)
+	}
 
-		if let Some(being) = being {
-			for entity in being.entities.iter() {
-				if let Some(world) = self.worlds.get_mut(&entity.location.world) {
-					world.remove_entity(entity.id.clone()).await.unwrap();
-				}
-			}
-			self.beings.remove(being_index.unwrap());
+	pub fn kill_being_blocking(&mut self, id: Uid) -> Result<(), String> {
+		if let Some(being_index) = self.beings.iter().position(|b| b.id == id) {
+			let properties = self.beings[being_index].property_ids();
+			self.beings[being_index].dissolve_all(&self.worlds);
+			self.computed.forget(&properties);
+			self.beings.remove(being_index);
+			self.template_links.remove(&id);
 			Ok(())
 		} else {
 			Err(format!("Being with id {} does not exist", id))
@@ -81,6 +182,10 @@ impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIt
 
 	// get being by id
 	pub async fn get_being(&self, id: Uid) -> Result<Being, String> {
+		self.get_being_blocking(id)
+	}
+
+	pub fn get_being_blocking(&self, id: Uid) -> Result<Being, String> {
 		for being in self.beings.iter() {
 			if being.id == id {
 				return Ok(being.clone());
@@ -94,137 +199,991 @@ impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIt
 	// create a new entity on a random world
 	// add entity to being
 	pub async fn constitute_being(&mut self, being: Uid, entity_name: String) -> Result<Uid, String> {
-		if self.worlds.is_empty() {
-			self.create_world().await.unwrap();
-		}
+		self.constitute_being_blocking(being, entity_name)
+	}
 
-		// if enitity exist on being with the same name, remove it
-		if let Some(b) = self.beings.iter_mut().find(|b| b.id == being) {
-			if let Some(e) = b.entities.iter_mut().find(|e| e.name == entity_name) {
-				// get world
-				if let Some(world) = self.worlds.get_mut(&e.location.world) {
-					// remove entity
-					world.remove_entity(e.id.clone()).await.unwrap();
-				}
+	pub fn constitute_being_blocking(&mut self, being: Uid, entity_name: String) -> Result<Uid, String> {
+		self.constitute_being_with_id_blocking(being, Uid::new(), entity_name)
+	}
+
+	pub(crate) fn constitute_being_with_id_blocking(&mut self, being: Uid, id: Uid, entity_name: String) -> Result<Uid, String> {
+		self.constitute_being_with_id_and_policy_blocking(being, id, entity_name, self.name_conflicts.entities)
+	}
+
+	// constitute being, resolving a name already in use with the given policy instead of the one of the starsystem
+	// with Merge the existing entity is returned
+	pub async fn constitute_being_with_policy(&mut self, being: Uid, entity_name: String, policy: NameConflictPolicy) -> Result<Uid, String> {
+		self.constitute_being_with_policy_blocking(being, entity_name, policy)
+	}
+
+	pub fn constitute_being_with_policy_blocking(&mut self, being: Uid, entity_name: String, policy: NameConflictPolicy) -> Result<Uid, String> {
+		self.constitute_being_with_id_and_policy_blocking(being, Uid::new(), entity_name, policy)
+	}
+
+	pub(crate) fn constitute_being_with_id_and_policy_blocking(&mut self, being: Uid, id: Uid, entity_name: String, policy: NameConflictPolicy) -> Result<Uid, String> {
+		if let Some(existing) = self.entity_id(&being, &entity_name) {
+			match policy {
+				NameConflictPolicy::Reject => return Err(format!("Being with id {} already has an entity named {}", being, entity_name)),
+				NameConflictPolicy::Merge => return Ok(existing),
+				NameConflictPolicy::Replace | NameConflictPolicy::AllowDuplicates => {}
 			}
+		}
+		let being_name = self.beings.iter().find(|b| b.id == being).map(|b| b.name.clone()).ok_or(format!("Being with id {} does not exist", being))?;
 
-			// remove entity from being
-			b.entities.retain(|e| e.name != entity_name);
+		// the properties the schema gives a default value are checked before anything is written,
+		// so a default that breaks a constraint or the schema leaves a replaced entity in place
+		let defaults: Vec<(String, T)> = self.schemas.get(&being_name).and_then(|s| s.entities.get(&entity_name)).map(|e| e.properties.values().filter_map(|p| p.default.clone().map(|d| (p.name.clone(), d))).collect()).unwrap_or_default();
+		for (name, value) in defaults.iter() {
+			self.precheck_value(&being_name, &entity_name, name, value)?;
 		}
 
+		if self.worlds.is_empty() {
+			self.create_world().unwrap();
+		}
 		let world = self.worlds.iter().nth(rand::random::<usize>() % self.worlds.len()).unwrap().0.clone();
-		let ent = self.worlds.get_mut(&world).unwrap().create_entity(entity_name.clone()).await.unwrap();
-		let entity = StarEntity { location: StarEntityLocation { world, entity: ent.clone() }, id: ent, name: entity_name, properties: Vec::new() };
-		for b in self.beings.iter_mut() {
-			if b.id == being {
-				b.entities.push(entity.clone());
-				break;
+		let b = self.beings.iter_mut().find(|b| b.id == being).ok_or(format!("Being with id {} does not exist", being))?;
+		let (entity, replaced) = match policy {
+			NameConflictPolicy::Replace => {
+				// the entity it replaces takes its properties with it
+				let replaced: Vec<Uid> = b.entities.iter().filter(|e| e.name == entity_name).flat_map(|e| e.properties.iter().map(|p| p.id.clone())).collect();
+				(b.constitute(&self.worlds, world, id, entity_name.clone())?, replaced)
 			}
+			_ => (b.insert_entity(&self.worlds, world, id, entity_name.clone())?, Vec::new()),
+		};
+		for (name, value) in defaults {
+			self.add_property_blocking(being.clone(), entity.clone(), value, name)?;
 		}
-		Ok(entity.id)
+		self.computed.forget(&replaced);
+		Ok(entity)
 	}
 
 	// dissolve entity
 	// remove entity from being
 	// remove entity from world
 	pub async fn dissolve_entity(&mut self, being: Uid, entity: Uid) -> Result<(), String> {
+		self.dissolve_entity_blocking(being, entity)
+	}
+
+	pub fn dissolve_entity_blocking(&mut self, being: Uid, entity: Uid) -> Result<(), String> {
+		if let Some((b, e)) = self.entity_path(&being, &entity) {
+			let violation = self.schemas.get(&b).and_then(|s| s.check_entity_removal(&b, &e));
+			self.enforce_schema(violation.into_iter().collect())?;
+		}
 		if let Some(b) = self.beings.iter_mut().find(|b| b.id == being) {
-			if let Some(e) = b.entities.iter_mut().find(|e| e.id == entity) {
-				// get world
-				if let Some(world) = self.worlds.get_mut(&e.location.world) {
-					// remove entity
-					world.remove_entity(e.id.clone()).await.unwrap();
+			let properties: Vec<Uid> = b.entities.iter().filter(|e| e.id == entity).flat_map(|e| e.properties.iter().map(|p| p.id.clone())).collect();
+			b.dissolve(&self.worlds, entity);
+			self.computed.forget(&properties);
+		}
+		Ok(())
+	}
+
+	// add property to entity
+	pub async fn add_property(&mut self, being: Uid, entity: Uid, property: T, property_name: String) -> Result<Uid, PropertyError> {
+		self.add_property_blocking(being, entity, property, property_name)
+	}
+
+	pub fn add_property_blocking(&mut self, being: Uid, entity: Uid, property: T, property_name: String) -> Result<Uid, PropertyError> {
+		self.add_property_with_id_blocking(being, entity, Uid::new(), property, property_name)
+	}
+
+	pub(crate) fn add_property_with_id_blocking(&mut self, being: Uid, entity: Uid, id: Uid, property: T, property_name: String) -> Result<Uid, PropertyError> {
+		self.add_property_with_id_and_policy_blocking(being, entity, id, property, property_name, self.name_conflicts.properties)
+	}
+
+	// add property to entity, resolving a name already in use with the given policy instead of the one of the starsystem
+	// with Merge the existing property is set and its id returned
+	pub async fn add_property_with_policy(&mut self, being: Uid, entity: Uid, property: T, property_name: String, policy: NameConflictPolicy) -> Result<Uid, PropertyError> {
+		self.add_property_with_policy_blocking(being, entity, property, property_name, policy)
+	}
+
+	pub fn add_property_with_policy_blocking(&mut self, being: Uid, entity: Uid, property: T, property_name: String, policy: NameConflictPolicy) -> Result<Uid, PropertyError> {
+		self.add_property_with_id_and_policy_blocking(being, entity, Uid::new(), property, property_name, policy)
+	}
+
+	pub(crate) fn add_property_with_id_and_policy_blocking(&mut self, being: Uid, entity: Uid, id: Uid, property: T, property_name: String, policy: NameConflictPolicy) -> Result<Uid, PropertyError> {
+		let existing: Vec<Uid> = self.beings.iter().find(|b| b.id == being).and_then(|b| b.entities.iter().find(|e| e.id == entity)).map(|e| e.properties.iter().filter(|p| p.name == property_name).map(|p| p.id.clone()).collect()).unwrap_or_default();
+		if !existing.is_empty() {
+			match policy {
+				NameConflictPolicy::Reject => return Err(format!("Entity with id {} already has a property named {}", entity, property_name).into()),
+				NameConflictPolicy::Merge => return self.set_property_blocking(being, entity, existing[0].clone(), property, property_name),
+				NameConflictPolicy::Replace | NameConflictPolicy::AllowDuplicates => {}
+			}
+		}
+		if let Some((b, e)) = self.entity_path(&being, &entity) {
+			self.check_value(&b, &e, &property_name, &property)?;
+			// replacing removes the existing properties first, which a schema may not allow
+			if policy == NameConflictPolicy::Replace && !existing.is_empty() {
+				let violation = self.schemas.get(&b).and_then(|s| s.check_property_removal(&b, &e, &property_name));
+				self.enforce_schema(violation.into_iter().collect())?;
+			}
+		}
+		if policy == NameConflictPolicy::Replace {
+			if let Some(b) = self.beings.iter_mut().find(|b| b.id == being) {
+				for p in existing.iter() {
+					b.remove_property(&self.worlds, p.clone());
 				}
 			}
+			self.computed.forget(&existing);
+		}
+		match self.beings.iter_mut().find(|b| b.id == being) {
+			Some(b) => Ok(b.add_property(&self.worlds, entity, id, property, property_name)?),
+			None => Err("Could not add property to entity".to_string().into()),
+		}
+	}
+
+	// set the first property of an entity with the given name, or add it if it is missing
+	// the entity is constituted if the being has none of that name
+	// returns the id of the property
+	pub async fn upsert_property(&mut self, being: Uid, entity_name: String, property_name: String, value: T) -> Result<Uid, String> {
+		self.upsert_property_blocking(being, entity_name, property_name, value)
+	}
+
+	pub fn upsert_property_blocking(&mut self, being: Uid, entity_name: String, property_name: String, value: T) -> Result<Uid, String> {
+		let entity = self.constitute_being_with_policy_blocking(being.clone(), entity_name, NameConflictPolicy::Merge)?;
+		Ok(self.add_property_with_policy_blocking(being, entity, value, property_name, NameConflictPolicy::Merge)?)
+	}
+
+	// choose what adding an entity under a name its being already uses does
+	pub fn set_entity_conflict_policy(&mut self, policy: NameConflictPolicy) -> &mut Self {
+		self.name_conflicts.entities = policy;
+		self
+	}
+
+	// choose what adding a property under a name its entity already uses does
+	pub fn set_property_conflict_policy(&mut self, policy: NameConflictPolicy) -> &mut Self {
+		self.name_conflicts.properties = policy;
+		self
+	}
+
+	// set property
+	pub async fn set_property(&mut self, being: Uid, entity: Uid, property: Uid, value: T, name: String) -> Result<Uid, PropertyError> {
+		self.set_property_blocking(being, entity, property, value, name)
+	}
+
+	pub fn set_property_blocking(&mut self, being: Uid, entity: Uid, property: Uid, value: T, name: String) -> Result<Uid, PropertyError> {
+		if self.computed.is_computed(&property) {
+			return Err(format!("Property {} is computed", property).into());
+		}
+		if let Some((b, e)) = self.entity_path(&being, &entity) {
+			self.check_value(&b, &e, &name, &value)?;
+		}
+		let planned = self.plan_recompute(&property, value.clone())?;
+		let b = self.beings.iter_mut().find(|b| b.id == being).ok_or(format!("Being with id {} does not exist", being))?;
+		let id = b.set_property(&self.worlds, entity.clone(), property, value, name).ok_or(format!("Entity with id {} does not exist", entity))?;
+		self.write_computed(planned)?;
+		Ok(id)
+	}
 
-			// remove entity from being
-			b.entities.retain(|e| e.id != entity);
+	// remove property by id
+	pub async fn remove_property(&mut self, property: Uid) -> Result<(), String> {
+		self.remove_property_blocking(property)
+	}
+
+	pub fn remove_property_blocking(&mut self, property: Uid) -> Result<(), String> {
+		if let Some((b, e, p)) = self.property_path(&property) {
+			let violation = self.schemas.get(&b).and_then(|s| s.check_property_removal(&b, &e, &p));
+			self.enforce_schema(violation.into_iter().collect())?;
+		}
+		for b in self.beings.iter_mut() {
+			if b.remove_property(&self.worlds, property.clone()) {
+				break;
+			}
 		}
+		self.computed.forget(&[property]);
 		Ok(())
 	}
 
-	// add property to entity
-	pub async fn add_property(&mut self, being: Uid, entity: Uid, property: T, property_name: String) -> Result<Uid, String> {
-		let mut id: Option<Uid> = None;
-		if let Some(b) = self.beings.iter_mut().find(|b| b.id == being) {
-			if let Some(e) = b.entities.iter_mut().find(|e| e.id == entity) {
-				// get world
-				if let Some(world) = self.worlds.get_mut(&e.location.world) {
-					// add property
-					id = Some(world.add_component_to_entity(entity.clone(), property, property_name.clone()).await.unwrap());
+	// set property given property id
+	pub async fn set_property_by_id(&mut self, property_id: Uid, property_value: T) -> Result<Uid, PropertyError> {
+		self.set_property_by_id_blocking(property_id, property_value)
+	}
+
+	pub fn set_property_by_id_blocking(&mut self, property_id: Uid, property_value: T) -> Result<Uid, PropertyError> {
+		if self.computed.is_computed(&property_id) {
+			return Err(format!("Property {} is computed", property_id).into());
+		}
+		if let Some((b, e, p)) = self.property_path(&property_id) {
+			self.check_value(&b, &e, &p, &property_value)?;
+		}
+		let planned = self.plan_recompute(&property_id, property_value.clone())?;
+		if let Some(world) = self.worlds.values().find(|w| w.has_component(property_id.clone())) {
+			world.set_component_blocking(property_id.clone(), property_value)?;
+		}
+		self.write_computed(planned)?;
+		Ok(property_id)
+	}
+
+	// get the value of a property by id
+	fn property_value(&self, property: &Uid) -> Result<T, String> {
+		match self.worlds.values().find(|w| w.has_component(property.clone())) {
+			Some(world) => world.get_component_blocking(property.clone()).map(|c| c.data),
+			None => Err(format!("Property with id {} does not exist", property)),
+		}
+	}
+
+	// the names of a being and one of its entities
+	fn entity_path(&self, being: &Uid, entity: &Uid) -> Option<(String, String)> {
+		let b = self.beings.iter().find(|b| b.id == *being)?;
+		let e = b.entities.iter().find(|e| e.id == *entity)?;
+		Some((b.name.clone(), e.name.clone()))
+	}
+
+	// the id of the first entity of a being with the given name
+	fn entity_id(&self, being: &Uid, entity: &str) -> Option<Uid> {
+		let b = self.beings.iter().find(|b| b.id == *being)?;
+		b.entities.iter().find(|e| e.name == entity).map(|e| e.id.clone())
+	}
+
+	// the id of the first property of an entity with the given name
+	fn property_id(&self, being: &Uid, entity: &str, property: &str) -> Option<Uid> {
+		let b = self.beings.iter().find(|b| b.id == *being)?;
+		let e = b.entities.iter().find(|e| e.name == entity)?;
+		e.properties.iter().find(|p| p.name == property).map(|p| p.id.clone())
+	}
+
+	// the names of the being, entity and property a property belongs to
+	fn property_path(&self, property: &Uid) -> Option<(String, String, String)> {
+		for b in self.beings.iter() {
+			for e in b.entities.iter() {
+				if let Some(p) = e.properties.iter().find(|p| p.id == *property) {
+					return Some((b.name.clone(), e.name.clone(), p.name.clone()));
 				}
 			}
 		}
+		None
+	}
 
-		// add property to entity
-		if let Some(id) = id {
-			for b in self.beings.iter_mut() {
-				if b.id == being {
-					for e in b.entities.iter_mut() {
-						if e.id == entity {
-							let location: StarEntityLocation = StarEntityLocation { world: e.location.world.clone(), entity: id.clone() };
-							let prop: StarEntityProperty = StarEntityProperty { location, id: id.clone(), name: property_name };
-							e.properties.push(prop);
-							break;
-						}
+	/*
+			Schemas
+	*/
+
+	// hold every being of the given name to a schema
+	pub fn set_schema(&mut self, being_name: String, schema: BeingSchema<T>) -> &mut Self {
+		self.schemas.insert(being_name, schema);
+		self
+	}
+
+	pub fn remove_schema(&mut self, being_name: String) -> Option<BeingSchema<T>> {
+		self.schemas.remove(&being_name)
+	}
+
+	// choose whether mutations that break a schema are rejected or kept as warnings
+	pub fn set_schema_mode(&mut self, mode: SchemaMode) -> &mut Self {
+		self.schema_mode = mode;
+		self
+	}
+
+	// the violations let through in warn mode since the last call
+	pub fn take_schema_warnings(&mut self) -> Vec<SchemaViolation> {
+		std::mem::take(&mut self.schema_warnings)
+	}
+
+	// reject or keep the violations of a mutation
+	fn enforce_schema(&mut self, violations: Vec<SchemaViolation>) -> Result<(), PropertyError> {
+		if violations.is_empty() {
+			return Ok(());
+		}
+		match self.schema_mode {
+			SchemaMode::Reject => Err(PropertyError::Schema(violations)),
+			SchemaMode::Warn => {
+				self.schema_warnings.extend(violations);
+				Ok(())
+			}
+		}
+	}
+
+	// copy a being with every entity and property under fresh ids
+	// the copy is checked against the schema and the constraints of its new name before anything is written,
+	// and gets the entities and default properties that schema asks for like conceive_being and constitute_being give them
+	// computed properties of the being are computed on the copy too, reading the copies of their inputs,
+	// and a template link is carried over
+	// returns the id of the copy and the id every original maps to
+	pub async fn clone_being(&mut self, being: Uid, name: String, placement: ClonePlacement) -> Result<ClonedBeing, String> {
+		self.clone_being_blocking(being, name, placement)
+	}
+
+	pub fn clone_being_blocking(&mut self, being: Uid, name: String, placement: ClonePlacement) -> Result<ClonedBeing, String> {
+		let b = self.beings.iter().find(|b| b.id == being).ok_or(format!("Being with id {} does not exist", being))?;
+		let ascended = b.ascend(&self.worlds)?;
+		let mut spec = BeingSpec::from_ascended(name.clone(), &ascended);
+		let mut violations = Vec::new();
+		if let Some(schema) = self.schemas.get(&name) {
+			schema.complete(&mut spec);
+			violations = schema.validate(&spec.ascend());
+		}
+		self.constraints.validate_spec(&spec)?;
+		self.enforce_schema(violations)?;
+
+		let b = self.beings.iter().find(|b| b.id == being).ok_or(format!("Being with id {} does not exist", being))?;
+		let (mut copy, cloned) = clone::clone_being(&self.worlds, b, name, placement)?;
+		let properties = b.property_ids();
+
+		// every computed property needs a copy, otherwise the copy is taken out of the worlds again
+		let mut computed = Vec::new();
+		for property in properties {
+			if let Some(c) = self.computed.get(&property).cloned() {
+				let id = match cloned.ids.get(&property) {
+					Some(id) => id.clone(),
+					None => {
+						copy.dissolve_all(&self.worlds);
+						return Err(format!("Property with id {} was not copied", property));
 					}
-					break;
-				}
+				};
+				let inputs = c.inputs.iter().map(|i| cloned.ids.get(i).unwrap_or(i).clone()).collect();
+				computed.push((id, inputs, c.compute));
+			}
+		}
+		self.beings.push(copy);
+		for (id, inputs, compute) in computed {
+			self.computed.insert(id, inputs, compute)?;
+		}
+
+		// the schema completed the spec after the copied entities and properties
+		// if adding one fails the copy is killed again, so no half made being is left behind
+		for (i, e) in spec.entities.into_iter().enumerate() {
+			let res = match ascended.entities.get(i) {
+				Some(original) => e.properties.into_iter().skip(original.components.len()).try_for_each(|p| self.add_property_blocking(cloned.id.clone(), cloned.ids[&original.id].clone(), p.value, p.name).map(|_| ()).map_err(String::from)),
+				None => self.constitute_being_blocking(cloned.id.clone(), e.name).map(|_| ()),
+			};
+			if let Err(err) = res {
+				self.kill_being_blocking(cloned.id)?;
+				return Err(err);
 			}
-			Ok(id)
+		}
+		if let Some(link) = self.template_links.get(&being).cloned() {
+			self.template_links.insert(cloned.id.clone(), link);
+		}
+		Ok(cloned)
+	}
+
+	/*
+			Renaming and moving
+			ids stay the same, so references to beings, entities and properties keep working
+	*/
+
+	pub async fn rename_being(&mut self, being: Uid, name: String) -> Result<(), String> {
+		self.rename_being_blocking(being, name)
+	}
+
+	pub fn rename_being_blocking(&mut self, being: Uid, name: String) -> Result<(), String> {
+		let b = self.beings.iter_mut().find(|b| b.id == being).ok_or(format!("Being with id {} does not exist", being))?;
+		b.name = name;
+		Ok(())
+	}
+
+	// rename an entity on its being and in its world
+	// fails if the being already has an entity of that name
+	pub async fn rename_entity(&mut self, being: Uid, entity: Uid, name: String) -> Result<(), String> {
+		self.rename_entity_blocking(being, entity, name)
+	}
+
+	pub fn rename_entity_blocking(&mut self, being: Uid, entity: Uid, name: String) -> Result<(), String> {
+		let (b, e) = self.entity_path(&being, &entity).ok_or(format!("Entity with id {} does not exist on being {}", entity, being))?;
+		if e == name {
+			return Ok(());
+		}
+		if self.entity_id(&being, &name).is_some() {
+			return Err(format!("Being {} already has an entity named {}", b, name));
+		}
+		let violation = self.schemas.get(&b).and_then(|s| s.check_entity_removal(&b, &e));
+		self.enforce_schema(violation.into_iter().collect())?;
+
+		let star_entity = self.beings.iter_mut().find(|b| b.id == being).unwrap().entities.iter_mut().find(|e| e.id == entity).unwrap();
+		let world = self.worlds.get(&star_entity.location.world).ok_or(format!("World with id {} does not exist", star_entity.location.world))?;
+		world.rename_entity_blocking(entity, name.clone())?;
+		star_entity.name = name;
+		Ok(())
+	}
+
+	// rename a property on its entity and in its world
+	pub async fn rename_property(&mut self, property: Uid, name: String) -> Result<(), String> {
+		self.rename_property_blocking(property, name)
+	}
+
+	pub fn rename_property_blocking(&mut self, property: Uid, name: String) -> Result<(), String> {
+		let (b, e, p) = self.property_path(&property).ok_or(format!("Property with id {} does not exist", property))?;
+		if p == name {
+			return Ok(());
+		}
+		let value = self.property_value(&property)?;
+		let violation = self.schemas.get(&b).and_then(|s| s.check_property_removal(&b, &e, &p));
+		self.enforce_schema(violation.into_iter().collect())?;
+		self.check_value(&b, &e, &name, &value)?;
+
+		let star_property = self.beings.iter_mut().flat_map(|b| b.entities.iter_mut()).flat_map(|e| e.properties.iter_mut()).find(|p| p.id == property).unwrap();
+		let world = self.worlds.get(&star_property.location.world).ok_or(format!("World with id {} does not exist", star_property.location.world))?;
+		world.rename_component_blocking(property, name.clone())?;
+		star_property.name = name;
+		Ok(())
+	}
+
+	// move an entity to another being, it stays in its world
+	// fails if the other being already has an entity of that name
+	pub async fn move_entity(&mut self, from_being: Uid, to_being: Uid, entity: Uid) -> Result<(), String> {
+		self.move_entity_blocking(from_being, to_being, entity)
+	}
+
+	pub fn move_entity_blocking(&mut self, from_being: Uid, to_being: Uid, entity: Uid) -> Result<(), String> {
+		if from_being == to_being {
+			return Ok(());
+		}
+		let (b, e) = self.entity_path(&from_being, &entity).ok_or(format!("Entity with id {} does not exist on being {}", entity, from_being))?;
+		let target = self.beings.iter().find(|b| b.id == to_being).ok_or(format!("Being with id {} does not exist", to_being))?;
+		if target.entities.iter().any(|t| t.name == e) {
+			return Err(format!("Being {} already has an entity named {}", target.name, e));
+		}
+		let violation = self.schemas.get(&b).and_then(|s| s.check_entity_removal(&b, &e));
+		self.enforce_schema(violation.into_iter().collect())?;
+
+		let source = self.beings.iter_mut().find(|b| b.id == from_being).unwrap();
+		let position = source.entities.iter().position(|e| e.id == entity).unwrap();
+		let star_entity = source.entities.remove(position);
+		self.beings.iter_mut().find(|b| b.id == to_being).unwrap().entities.push(star_entity);
+		Ok(())
+	}
+
+	// move a property to another entity, of the same or another being
+	// if the other entity lives in another world, the component moves along with its ticks
+	pub async fn move_property(&mut self, property: Uid, from_entity: Uid, to_entity: Uid) -> Result<(), String> {
+		self.move_property_blocking(property, from_entity, to_entity)
+	}
+
+	pub fn move_property_blocking(&mut self, property: Uid, from_entity: Uid, to_entity: Uid) -> Result<(), String> {
+		if from_entity == to_entity {
+			return Ok(());
+		}
+		let (b, e, p) = self.property_path(&property).ok_or(format!("Property with id {} does not exist", property))?;
+		let entities = || self.beings.iter().flat_map(|b| b.entities.iter().map(move |e| (b, e)));
+		let (_, source) = entities().find(|(_, e)| e.id == from_entity).ok_or(format!("Entity with id {} does not exist", from_entity))?;
+		if !source.properties.iter().any(|p| p.id == property) {
+			return Err(format!("Property with id {} does not exist on entity {}", property, from_entity));
+		}
+		let (target_being, target) = entities().find(|(_, e)| e.id == to_entity).ok_or(format!("Entity with id {} does not exist", to_entity))?;
+		let (from_world, to_world) = (source.location.world.clone(), target.location.world.clone());
+		let (to_b, to_e) = (target_being.name.clone(), target.name.clone());
+		let value = self.property_value(&property)?;
+		let violation = self.schemas.get(&b).and_then(|s| s.check_property_removal(&b, &e, &p));
+		self.enforce_schema(violation.into_iter().collect())?;
+		self.check_value(&to_b, &to_e, &p, &value)?;
+
+		// move the component between the entities of the worlds
+		let from = self.worlds.get(&from_world).ok_or(format!("World with id {} does not exist", from_world))?;
+		if from_world == to_world {
+			from.move_component_blocking(property.clone(), from_entity.clone(), to_entity.clone())?;
 		} else {
-			Err("Could not add property to entity".to_string())
+			let to = self.worlds.get(&to_world).ok_or(format!("World with id {} does not exist", to_world))?;
+			if !to.has_entity(to_entity.clone()) {
+				return Err(format!("Entity with id {} does not exist in world {}", to_entity, to_world));
+			}
+			let component = from.take_component_blocking(from_entity.clone(), property.clone())?;
+			// the component goes back to where it came from if the target does not take it
+			if let Err(err) = to.put_component_blocking(to_entity.clone(), property.clone(), component.clone()) {
+				from.put_component_blocking(from_entity.clone(), property.clone(), component)?;
+				return Err(err);
+			}
 		}
+
+		// and the property between the entities of the beings
+		let source = self.beings.iter_mut().flat_map(|b| b.entities.iter_mut()).find(|e| e.id == from_entity).unwrap();
+		let position = source.properties.iter().position(|p| p.id == property).unwrap();
+		let mut star_property = source.properties.remove(position);
+		star_property.location.world = to_world;
+		self.beings.iter_mut().flat_map(|b| b.entities.iter_mut()).find(|e| e.id == to_entity).unwrap().properties.push(star_property);
+		Ok(())
 	}
 
-	// set property
-	pub async fn set_property(&mut self, being: Uid, entity: Uid, property: Uid, value: T, name: String) -> Result<Uid, String> {
-		let mut id: Option<Uid> = None;
-		if let Some(b) = self.beings.iter_mut().find(|b| b.id == being) {
-			if let Some(e) = b.entities.iter_mut().find(|e| e.id == entity) {
-				// get world
-				if let Some(world) = self.worlds.get_mut(&e.location.world) {
-					// add property
-					id = Some(world.set_component_to_entity(entity.clone(), value.clone(), name.clone(), property.clone()).await.unwrap());
-
-					// update property
-					for p in e.properties.iter_mut() {
-						if p.id == property {
-							p.name = name;
-							break;
+	/*
+			Templates
+	*/
+
+	// create a being from a template with fresh ids, in one batch
+	// overrides replace the values of properties named by their path, like `server/port`
+	// like spawn_batch, the schema adds its defaults and the being is checked against the schema and the constraints
+	pub async fn instantiate(&mut self, template: &Template<T>, name: String, overrides: BTreeMap<String, T>) -> Result<SpawnedBeing, String> {
+		self.instantiate_blocking(template, name, overrides)
+	}
+
+	pub fn instantiate_blocking(&mut self, template: &Template<T>, name: String, overrides: BTreeMap<String, T>) -> Result<SpawnedBeing, String> {
+		let spec = template.spec(name, &overrides)?;
+		Ok(self.spawn_batch_blocking(vec![spec])?.remove(0))
+	}
+
+	// create a being from a template and keep it linked to the template
+	// the template is registered under its name, update_template passes later changes on to the being
+	pub async fn instantiate_linked(&mut self, template: &Template<T>, name: String, overrides: BTreeMap<String, T>) -> Result<SpawnedBeing, String> {
+		self.instantiate_linked_blocking(template, name, overrides)
+	}
+
+	pub fn instantiate_linked_blocking(&mut self, template: &Template<T>, name: String, overrides: BTreeMap<String, T>) -> Result<SpawnedBeing, String> {
+		if self.templates.get(&template.name).is_some_and(|t| t != template) {
+			return Err(format!("Template {} is registered with other content, update it with update_template first", template.name));
+		}
+		let spawned = self.instantiate_blocking(template, name, overrides.clone())?;
+		self.templates.insert(template.name.clone(), template.clone());
+		self.template_links.insert(spawned.id.clone(), TemplateLink { template: template.name.clone(), overrides });
+		Ok(spawned)
+	}
+
+	// stop passing template changes on to a being
+	pub fn unlink_being(&mut self, being: Uid) -> Option<TemplateLink<T>> {
+		self.template_links.remove(&being)
+	}
+
+	// register a new version of a template and pass its changes on to the linked beings
+	// properties whose template value changed are set, unless the being overrides them,
+	// and entities and properties the template dropped are removed
+	// if a being fails to take the changes, the linked beings are taken back to the old version and it stays registered
+	// returns the ids of the linked beings
+	pub async fn update_template(&mut self, template: Template<T>) -> Result<Vec<Uid>, String> {
+		self.update_template_blocking(template)
+	}
+
+	pub fn update_template_blocking(&mut self, template: Template<T>) -> Result<Vec<Uid>, String> {
+		let old = self.templates.get(&template.name).cloned().unwrap_or_else(|| Template::new(template.name.clone()));
+		let linked: Vec<(Uid, TemplateLink<T>)> = self.template_links.iter().filter(|l| l.1.template == template.name).map(|l| (l.0.clone(), l.1.clone())).collect();
+		for (i, (being, link)) in linked.iter().enumerate() {
+			if let Err(err) = self.propagate_template(being, &old, &template, &link.overrides) {
+				// the beings changed so far go back to the old version, which stays registered
+				for (being, link) in linked[..=i].iter().rev() {
+					let _ = self.propagate_template(being, &template, &old, &link.overrides);
+				}
+				return Err(err);
+			}
+		}
+		self.templates.insert(template.name.clone(), template);
+		Ok(linked.into_iter().map(|l| l.0).collect())
+	}
+
+	fn propagate_template(&mut self, being: &Uid, old: &Template<T>, new: &Template<T>, overrides: &BTreeMap<String, T>) -> Result<(), String> {
+		// what the template dropped
+		for e in old.entities.iter() {
+			match new.entities.iter().find(|n| n.name == e.name) {
+				None => {
+					if let Some(entity) = self.entity_id(being, &e.name) {
+						self.dissolve_entity_blocking(being.clone(), entity)?;
+					}
+				}
+				Some(n) => {
+					for p in e.properties.iter().filter(|p| !n.properties.iter().any(|np| np.name == p.name)) {
+						if let Some(property) = self.property_id(being, &e.name, &p.name) {
+							self.remove_property_blocking(property)?;
 						}
 					}
 				}
 			}
 		}
-		Ok(id.unwrap())
-	}
 
-	// remove property by id
-	pub async fn remove_property(&mut self, property: Uid) -> Result<(), String> {
-		for b in self.beings.iter_mut() {
-			for e in b.entities.iter_mut() {
-				if let Some(p) = e.properties.iter_mut().find(|p| p.id == property) {
-					// get world
-					if let Some(world) = self.worlds.get_mut(&e.location.world) {
-						// remove property
-						world.remove_component_from_entity(e.id.clone(), p.id.clone()).await.unwrap();
+		// what the template added or changed
+		for e in new.entities.iter() {
+			let entity = match self.entity_id(being, &e.name) {
+				Some(entity) => entity,
+				None => self.constitute_being_blocking(being.clone(), e.name.clone())?,
+			};
+			for p in e.properties.iter() {
+				if overrides.contains_key(&format!("{}/{}", e.name, p.name)) {
+					continue;
+				}
+				match self.property_id(being, &e.name, &p.name) {
+					Some(property) => {
+						if old.value(&e.name, &p.name) != Some(&p.value) {
+							self.set_property_blocking(being.clone(), entity.clone(), property, p.value.clone(), p.name.clone())?;
+						}
+					}
+					None => {
+						self.add_property_blocking(being.clone(), entity.clone(), p.value.clone(), p.name.clone())?;
 					}
-					// remove property from entity
-					e.properties.retain(|p| p.id != property);
-					break;
 				}
 			}
 		}
 		Ok(())
 	}
 
-	// set property given property id
-	pub async fn set_property_by_id(&mut self, property_id: Uid, property_value: T) -> Result<Uid, String> {
-		if let Some(world) = self.worlds.iter_mut().find(|w| w.1.has_component(property_id.clone())) {
-			world.1.set_component(property_id.clone(), property_value.clone()).await.unwrap();
+	/*
+			Constraints
+	*/
+
+	// constrain the values of every property of a name
+	pub fn add_constraint(&mut self, property_name: String, constraint: Constraint<T>) -> &mut Self {
+		self.constraints.by_name.entry(property_name).or_default().push(constraint);
+		self
+	}
+
+	// constrain the values of the property at a path like `config/server/port`
+	pub fn add_path_constraint(&mut self, path: String, constraint: Constraint<T>) -> &mut Self {
+		self.constraints.by_path.entry(path).or_default().push(constraint);
+		self
+	}
+
+	// check a value against the constraints of a property without storing it
+	// lists every constraint the value fails
+	pub fn validate_value(&self, being_name: &str, entity_name: &str, property_name: &str, value: &T) -> Result<(), ValidationError> {
+		self.constraints.validate(being_name, entity_name, property_name, value)
+	}
+
+	// check a value against the constraints and the schema of its property before it is stored
+	fn check_value(&mut self, being: &str, entity: &str, property: &str, value: &T) -> Result<(), PropertyError> {
+		self.constraints.validate(being, entity, property, value)?;
+		let violation = self.schemas.get(being).and_then(|s| s.check_value(being, entity, property, value));
+		self.enforce_schema(violation.into_iter().collect())
+	}
+
+	// check a value against the constraints and, when violations are rejected, the schema without storing any warning
+	// the value is checked again when it is written
+	fn precheck_value(&self, being: &str, entity: &str, property: &str, value: &T) -> Result<(), String> {
+		self.constraints.validate(being, entity, property, value)?;
+		let violation = self.schemas.get(being).and_then(|s| s.check_value(being, entity, property, value));
+		match (self.schema_mode, violation) {
+			(SchemaMode::Reject, Some(v)) => Err(v.to_string()),
+			_ => Ok(()),
 		}
-		Ok(property_id)
+	}
+
+	// every violation of the schema of a being
+	pub async fn validate_being(&self, being: Uid) -> Result<Vec<SchemaViolation>, String> {
+		self.validate_being_blocking(being)
+	}
+
+	pub fn validate_being_blocking(&self, being: Uid) -> Result<Vec<SchemaViolation>, String> {
+		let b = self.beings.iter().find(|b| b.id == being).ok_or(format!("Being with id {} does not exist", being))?;
+		match self.schemas.get(&b.name) {
+			Some(schema) => Ok(schema.validate(&b.ascend(&self.worlds)?)),
+			None => Ok(Vec::new()),
+		}
+	}
+
+	// every violation of every being that has a schema
+	pub async fn validate_all(&self) -> Result<Vec<SchemaViolation>, String> {
+		self.validate_all_blocking()
+	}
+
+	pub fn validate_all_blocking(&self) -> Result<Vec<SchemaViolation>, String> {
+		let mut violations = Vec::new();
+		for b in self.beings.iter() {
+			if let Some(schema) = self.schemas.get(&b.name) {
+				violations.extend(schema.validate(&b.ascend(&self.worlds)?));
+			}
+		}
+		Ok(violations)
+	}
+
+	/*
+			Computed properties
+	*/
+
+	// add a property whose value is computed from other properties
+	// it is recomputed whenever one of its inputs changes through set_property or set_property_by_id
+	// computed values are checked against the constraints and the schema like any other write, a change of an input that would break them fails
+	// writes through systems, par_for_each_mut or add_properties_batch do not recompute it
+	pub async fn add_computed_property(&mut self, being: Uid, entity: Uid, name: String, inputs: Vec<Uid>, compute: impl Fn(&[T]) -> T + Send + Sync + 'static) -> Result<Uid, String> {
+		self.add_computed_property_blocking(being, entity, name, inputs, compute)
+	}
+
+	pub fn add_computed_property_blocking(&mut self, being: Uid, entity: Uid, name: String, inputs: Vec<Uid>, compute: impl Fn(&[T]) -> T + Send + Sync + 'static) -> Result<Uid, String> {
+		let values = inputs.iter().map(|i| self.property_value(i)).collect::<Result<Vec<T>, String>>()?;
+		let id = self.add_property_blocking(being, entity, compute(&values), name)?;
+		self.computed.insert(id.clone(), inputs, Arc::new(compute))?;
+		Ok(id)
+	}
+
+	// turn an existing property into a computed one
+	// rejects inputs that depend on the property, directly or through other computed properties
+	// if computing fails the property keeps its value and its earlier registration
+	pub async fn set_computed_property(&mut self, property: Uid, inputs: Vec<Uid>, compute: impl Fn(&[T]) -> T + Send + Sync + 'static) -> Result<(), String> {
+		self.set_computed_property_blocking(property, inputs, compute)
+	}
+
+	pub fn set_computed_property_blocking(&mut self, property: Uid, inputs: Vec<Uid>, compute: impl Fn(&[T]) -> T + Send + Sync + 'static) -> Result<(), String> {
+		self.property_value(&property)?;
+		for input in inputs.iter() {
+			self.property_value(input)?;
+		}
+		let previous = self.computed.get(&property).cloned();
+		self.computed.insert(property.clone(), inputs, Arc::new(compute))?;
+		match self.plan_computed(&property) {
+			Ok(planned) => self.write_computed(planned),
+			Err(err) => {
+				// nothing was written, the property goes back to its earlier registration, if any
+				self.computed.remove(&property);
+				if let Some(p) = previous {
+					self.computed.insert(property, p.inputs, p.compute)?;
+				}
+				Err(err.into())
+			}
+		}
+	}
+
+	// stop computing a property, it keeps its last value
+	pub fn remove_computed_property(&mut self, property: Uid) -> Option<ComputedProperty<T>> {
+		self.computed.remove(&property)
+	}
+
+	// the new value of a computed property and of the computed properties that read it
+	fn plan_computed(&mut self, property: &Uid) -> Result<Vec<(Uid, T)>, PropertyError> {
+		let c = self.computed.get(property).ok_or(format!("Property {} is not computed", property))?;
+		let (inputs, compute) = (c.inputs.clone(), c.compute.clone());
+		let values = inputs.iter().map(|i| self.property_value(i)).collect::<Result<Vec<T>, String>>()?;
+		let value = compute(&values);
+		if let Some((b, e, p)) = self.property_path(property) {
+			self.check_value(&b, &e, &p, &value)?;
+		}
+		let mut planned = vec![(property.clone(), value.clone())];
+		planned.extend(self.plan_recompute(property, value)?);
+		Ok(planned)
+	}
+
+	// the new values of the computed properties that read a property about to be set to a value, inputs before the properties that read them
+	// computed values are checked against the constraints and the schema of their being like any other write,
+	// so a value that breaks them fails the write before anything is written
+	fn plan_recompute(&mut self, property: &Uid, value: T) -> Result<Vec<(Uid, T)>, PropertyError> {
+		let mut planned = BTreeMap::from([(property.clone(), value)]);
+		let mut order = Vec::new();
+		for id in self.computed.affected(property) {
+			let (inputs, compute) = match self.computed.get(&id) {
+				Some(c) => (c.inputs.clone(), c.compute.clone()),
+				None => continue,
+			};
+			let values = inputs.iter().map(|i| planned.get(i).cloned().map_or_else(|| self.property_value(i), Ok)).collect::<Result<Vec<T>, String>>()?;
+			let value = compute(&values);
+			if let Some((b, e, p)) = self.property_path(&id) {
+				self.check_value(&b, &e, &p, &value)?;
+			}
+			planned.insert(id.clone(), value);
+			order.push(id);
+		}
+		Ok(order.into_iter().filter_map(|id| planned.remove(&id).map(|value| (id, value))).collect())
+	}
+
+	// write the planned values of computed properties
+	fn write_computed(&self, planned: Vec<(Uid, T)>) -> Result<(), String> {
+		for (id, value) in planned {
+			match self.worlds.values().find(|w| w.has_component(id.clone())) {
+				Some(world) => world.set_component_blocking(id, value)?,
+				None => return Err(format!("Property with id {} does not exist", id)),
+			};
+		}
+		Ok(())
+	}
+
+	// flag the computed properties of an ascended being
+	fn mark_computed(&self, mut being: AscendedBeing<T>) -> AscendedBeing<T> {
+		for entity in being.entities.iter_mut() {
+			for component in entity.components.iter_mut() {
+				component.computed = self.computed.is_computed(&component.id);
+			}
+		}
+		being
+	}
+
+	// spawn many beings at once
+	// if no worlds exist, create one
+	// the schema of each being adds its required entities and default properties after the ones of the spec,
+	// and every being is checked against its schema and the constraints before anything is written
+	// every entity goes to a random world and each world is locked once for the whole batch
+	// returns the ids of the beings, entities and properties in the order of the specs
+	pub async fn spawn_batch(&mut self, specs: impl IntoIterator<Item = BeingSpec<T>>) -> Result<Vec<SpawnedBeing>, String> {
+		self.spawn_batch_blocking(specs)
+	}
+
+	pub fn spawn_batch_blocking(&mut self, specs: impl IntoIterator<Item = BeingSpec<T>>) -> Result<Vec<SpawnedBeing>, String> {
+		let mut specs: Vec<BeingSpec<T>> = specs.into_iter().collect();
+		let mut violations = Vec::new();
+		for spec in specs.iter_mut() {
+			if let Some(schema) = self.schemas.get(&spec.name) {
+				schema.complete(spec);
+				violations.extend(schema.validate(&spec.ascend()));
+			}
+			self.constraints.validate_spec(spec)?;
+		}
+		self.enforce_schema(violations)?;
+
+		if self.worlds.is_empty() {
+			self.create_world().unwrap();
+		}
+		let (beings, spawned) = spawn::spawn(&self.worlds, specs)?;
+		self.beings.extend(beings);
+		Ok(spawned)
+	}
+
+	// add many properties at once
+	// accepts (being, entity, property, property name)
+	// nothing is added if one of the entities does not exist or a value breaks a constraint or the schema of its being
+	// returns the ids of the properties in input order
+	pub async fn add_properties_batch(&mut self, properties: impl IntoIterator<Item = (Uid, Uid, T, String)>) -> Result<Vec<Uid>, String> {
+		self.add_properties_batch_blocking(properties)
+	}
+
+	pub fn add_properties_batch_blocking(&mut self, properties: impl IntoIterator<Item = (Uid, Uid, T, String)>) -> Result<Vec<Uid>, String> {
+		let properties: Vec<(Uid, Uid, T, String)> = properties.into_iter().collect();
+		let mut violations = Vec::new();
+		for (being, entity, value, name) in properties.iter() {
+			if let Some((b, e)) = self.entity_path(being, entity) {
+				self.constraints.validate(&b, &e, name, value)?;
+				violations.extend(self.schemas.get(&b).and_then(|s| s.check_value(&b, &e, name, value)));
+			}
+		}
+		self.enforce_schema(violations)?;
+
+		let mut beings: BTreeMap<Uid, &mut Being> = self.beings.iter_mut().map(|b| (b.id.clone(), b)).collect();
+		spawn::add_properties(&self.worlds, &mut beings, properties)
+	}
+
+	// store a serializable struct or map as a new being
+	// top level fields become entities and nested fields become properties named by their path below the entity, like `headers/Content-Type`
+	// a top level scalar becomes an entity with a single property named `SCALAR_PROPERTY`
+	// an empty nested list, map or struct becomes a null property at its path
+	// scalars are converted to components through the conversions of StarValue
+	pub async fn insert_serialize<S: Serialize + ?Sized>(&mut self, name: String, value: &S) -> Result<Uid, String>
+	where
+		T: TryFrom<StarValue> + Into<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.insert_serialize_blocking(name, value)
+	}
+
+	pub fn insert_serialize_blocking<S: Serialize + ?Sized>(&mut self, name: String, value: &S) -> Result<Uid, String>
+	where
+		T: TryFrom<StarValue> + Into<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.insert_serialize_with_blocking(name, value, &StarValueMapping)
+	}
+
+	// store a serializable struct or map as a new being, converting scalars through the given mapping
+	pub async fn insert_serialize_with<S: Serialize + ?Sized>(&mut self, name: String, value: &S, mapping: &impl ValueMapping<T>) -> Result<Uid, String> {
+		self.insert_serialize_with_blocking(name, value, mapping)
+	}
+
+	pub fn insert_serialize_with_blocking<S: Serialize + ?Sized>(&mut self, name: String, value: &S, mapping: &impl ValueMapping<T>) -> Result<Uid, String> {
+		let mut spec = BeingSpec::new(name.clone());
+		for (entity, properties) in mapping::entities(value)? {
+			let mut e = EntitySpec::new(entity.clone());
+			for (property, scalar) in properties {
+				let value = mapping.to_component(scalar).map_err(|err| format!("{}/{}/{}: {}", name, entity, property, err))?;
+				e = e.property(property, value);
+			}
+			spec = spec.entity(e);
+		}
+		let spawned = self.spawn_batch_blocking(vec![spec])?;
+		Ok(spawned[0].id.clone())
+	}
+
+	// read a being back into any deserializable type
+	// the reverse of insert_serialize, errors carry the path of the property that failed like `config/headers/Content-Type`
+	pub async fn extract<S: DeserializeOwned>(&self, being: Uid) -> Result<S, String>
+	where
+		T: TryFrom<StarValue> + Into<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.extract_blocking(being)
+	}
+
+	pub fn extract_blocking<S: DeserializeOwned>(&self, being: Uid) -> Result<S, String>
+	where
+		T: TryFrom<StarValue> + Into<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.extract_with_blocking(being, &StarValueMapping)
+	}
+
+	// read a being back into any deserializable type, converting components through the given mapping
+	pub async fn extract_with<S: DeserializeOwned>(&self, being: Uid, mapping: &impl ValueMapping<T>) -> Result<S, String> {
+		self.extract_with_blocking(being, mapping)
+	}
+
+	pub fn extract_with_blocking<S: DeserializeOwned>(&self, being: Uid, mapping: &impl ValueMapping<T>) -> Result<S, String> {
+		let b = self.beings.iter().find(|b| b.id == being).ok_or(format!("Being with id {} does not exist", being))?;
+		mapping::extract(&b.ascend(&self.worlds)?, mapping)
+	}
+
+	// resolve layered beings into a merged view
+	// later layers override earlier ones at the property name level and every value records the layer it came from
+	// entities and properties named with the removal marker, like `!host`, hide the ones inherited from lower layers
+	// an entity holding only removal markers hides properties of the entity below without adding one of its own
+	pub async fn resolve(&self, layers: &[Uid]) -> Result<Resolved<T>, String> {
+		self.resolve_blocking(layers)
+	}
+
+	pub fn resolve_blocking(&self, layers: &[Uid]) -> Result<Resolved<T>, String> {
+		let mut ascended = Vec::with_capacity(layers.len());
+		for layer in layers {
+			let b = self.beings.iter().find(|b| b.id == *layer).ok_or(format!("Being with id {} does not exist", layer))?;
+			ascended.push(b.ascend(&self.worlds)?);
+		}
+		Ok(Resolved::merge(ascended))
+	}
+
+	// overlay environment variables onto a being
+	// `APP__HEADERS__CONTENT_TYPE` with the prefix `APP` sets the property `Content-Type` of the entity `headers`
+	// missing entities and properties are created, values are parsed through StarValue
+	// returns the changes that were made
+	pub async fn apply_env_overlay(&mut self, being: Uid, prefix: String) -> Result<Vec<EnvChange<T>>, String>
+	where
+		T: TryFrom<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.apply_env_overlay_blocking(being, prefix)
+	}
+
+	pub fn apply_env_overlay_blocking(&mut self, being: Uid, prefix: String) -> Result<Vec<EnvChange<T>>, String>
+	where
+		T: TryFrom<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.apply_env_overlay_with_blocking(being, &EnvOverlay::new(prefix), parse_star_value)
+	}
+
+	// overlay environment variables onto a being with the given options, parsing values with the given parser
+	// every value is parsed before anything is changed, so a bad value leaves the being untouched
+	// in a dry run the changes are only listed
+	pub async fn apply_env_overlay_with(&mut self, being: Uid, overlay: &EnvOverlay, parser: impl Fn(&str) -> Result<T, String>) -> Result<Vec<EnvChange<T>>, String> {
+		self.apply_env_overlay_with_blocking(being, overlay, parser)
+	}
+
+	pub fn apply_env_overlay_with_blocking(&mut self, being: Uid, overlay: &EnvOverlay, parser: impl Fn(&str) -> Result<T, String>) -> Result<Vec<EnvChange<T>>, String> {
+		let b = self.beings.iter().find(|b| b.id == being).ok_or(format!("Being with id {} does not exist", being))?;
+		let ascended = b.ascend(&self.worlds)?;
+
+		let mut changes: Vec<EnvChange<T>> = Vec::new();
+		for (var, raw) in overlay.read_vars() {
+			let segments = match overlay.segments(&var) {
+				Some(segments) => segments,
+				None => continue,
+			};
+			// a single segment names the scalar property of an entity, like a top level scalar of insert_serialize
+			let (entity, property) = match segments.len() {
+				1 => (segments[0].clone(), SCALAR_PROPERTY.to_string()),
+				_ => (segments[0].clone(), segments[1..].join("/")),
+			};
+
+			let e = ascended.entities.iter().find(|e| env::same_name(&e.name, &entity));
+			let p = e.and_then(|e| e.components.iter().find(|c| env::same_name(&c.name, &property)));
+			let new = parser(&raw).map_err(|err| format!("{}: {}", var, err))?;
+			if p.is_some_and(|p| p.data == new) {
+				continue;
+			}
+			if let Some(p) = p.filter(|p| self.computed.is_computed(&p.id)) {
+				return Err(format!("{}: Property {} is computed", var, p.id));
+			}
+			changes.push(EnvChange {
+				var,
+				entity: e.map(|e| e.name.clone()).unwrap_or_else(|| overlay.case.apply(&entity)),
+				property: p.map(|p| p.name.clone()).unwrap_or_else(|| overlay.case.apply(&property)),
+				old: p.map(|p| p.data.clone()),
+				new,
+			});
+		}
+		if overlay.dry_run {
+			return Ok(changes);
+		}
+
+		// every change is checked before any is written, so a failing variable leaves the being untouched
+		for change in changes.iter() {
+			self.precheck_value(&ascended.name, &change.entity, &change.property, &change.new).map_err(|err| format!("{}: {}", change.var, err))?;
+		}
+		for change in changes.iter() {
+			self.upsert_property_blocking(being.clone(), change.entity.clone(), change.property.clone(), change.new.clone())?;
+		}
+		Ok(changes)
 	}
 
 	// develop being
@@ -233,50 +1192,22 @@ impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIt
 	// loop over entities and create a new entity on a random world
 	// add entities to being
 	pub async fn develop_being(&mut self, being: Uid, ascended_beings: Vec<AscendedBeing<T>>) -> Result<Vec<Uid>, String> {
+		self.develop_being_blocking(being, ascended_beings)
+	}
+
+	pub fn develop_being_blocking(&mut self, being: Uid, ascended_beings: Vec<AscendedBeing<T>>) -> Result<Vec<Uid>, String> {
 		if self.worlds.is_empty() {
-			self.create_world().await.unwrap();
+			self.create_world().unwrap();
 		}
 
+		let b = match self.beings.iter_mut().find(|b| b.id == being) {
+			Some(b) => b,
+			None => return Err(format!("Being with id {} does not exist", being)),
+		};
 		let mut entities = Vec::new();
 		for ascended_being in ascended_beings.iter() {
 			let world = self.worlds.iter().nth(rand::random::<usize>() % self.worlds.len()).unwrap().0.clone();
-			let be = self.get_being(being.clone()).await.unwrap();
-			for e in ascended_being.entities.iter() {
-				let ent = self.worlds.get_mut(&world.clone()).unwrap().set_entity(e.id.clone(), e.name.clone()).await.unwrap();
-				let mut entity = StarEntity { location: StarEntityLocation { world: world.clone(), entity: ent.clone() }, id: ent.clone(), name: e.name.clone(), properties: Vec::new() };
-
-				for b in self.beings.iter_mut() {
-					if b.id == be.id.clone() {
-						b.entities.push(entity.clone());
-						break;
-					}
-				}
-
-				// add properties
-				for c in e.components.iter() {
-					//let prop = self.set_property(being.clone(), ent.clone(), c.id.clone(), c.data.clone(), c.name.clone()).await.unwrap();
-					entity.properties.push(StarEntityProperty { location: StarEntityLocation { world: world.clone(), entity: ent.clone() }, id: c.id.clone(), name: c.name.clone() });
-				}
-
-				// update entities on being
-				for b in self.beings.iter_mut() {
-					if b.id == be.id.clone() {
-						for e in b.entities.iter_mut() {
-							if e.id == ent.clone() {
-								e.properties = entity.properties.clone();
-								break;
-							}
-						}
-						break;
-					}
-				}
-
-				/* for c in e.components {
-					let prop = self.set_property(being.clone(), ent.clone(), c.id.clone(), c.data.clone(), c.name.clone()).await.unwrap();
-					entity.properties.push(StarEntityProperty { location: StarEntityLocation { world: world.clone(), entity: ent.clone() }, id: c.id.clone(), name: c.name });
-				} */
-				entities.push(entity.id.clone());
-			}
+			entities.extend(b.develop(&self.worlds, world, ascended_being)?);
 		}
 		Ok(entities)
 	}
@@ -284,23 +1215,297 @@ impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIt
 	// ascend being
 	// accepts a being id
 	// returns BTreeMap<bening_name, AscendedBeing<T> { id: being_id, entities: BTreeMap<entity_name, T> }>>
-	pub async fn ascend_being(&mut self, being: Uid) -> Result<Vec<AscendedBeing<T>>, String> {
+	pub async fn ascend_being(&self, being: Uid) -> Result<Vec<AscendedBeing<T>>, String> {
+		self.ascend_being_blocking(being)
+	}
+
+	pub fn ascend_being_blocking(&self, being: Uid) -> Result<Vec<AscendedBeing<T>>, String> {
 		let mut res: Vec<AscendedBeing<T>> = Vec::new();
 		for b in self.beings.iter() {
 			if b.id == being {
-				let mut entities: Vec<AscendedEntity<T>> = vec![];
-				for entity in b.entities.iter() {
-					let world = self.worlds.get_mut(&entity.location.world).unwrap();
-					let components = world.get_entity_components(entity.id.clone()).await.unwrap();
-					let mut new_component: Vec<AscendedComponent<T>> = vec![];
-					for (id, component) in components.iter() {
-						new_component.push(AscendedComponent { id: id.clone(), name: component.name.clone(), data: component.data.clone() });
-					}
-					entities.push(AscendedEntity { id: entity.id.clone(), name: entity.name.clone(), components: new_component });
-				}
-				res.push(AscendedBeing { name: b.name.clone(), id: b.id.clone(), entities });
+				res.push(self.mark_computed(b.ascend(&self.worlds)?));
+			}
+		}
+		Ok(res)
+	}
+
+	// ascend being with the references in its string properties expanded
+	// `${being/entity/property}` is replaced by the text of that property and `${entity/property}` names a property of the same being
+	// a reference that names a property of the same being, like `${http/tls/cert}`, is read that way before it is read as being/entity/property
+	// `$${` is kept as a literal `${`, cycles and unresolved references are errors
+	pub async fn ascend_being_interpolated(&self, being: Uid) -> Result<Vec<AscendedBeing<T>>, String>
+	where
+		T: TryFrom<StarValue> + Into<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.ascend_being_interpolated_blocking(being)
+	}
+
+	pub fn ascend_being_interpolated_blocking(&self, being: Uid) -> Result<Vec<AscendedBeing<T>>, String>
+	where
+		T: TryFrom<StarValue> + Into<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.ascend_being_interpolated_with_blocking(being, &StarValueMapping)
+	}
+
+	// ascend being with references expanded, reading and writing strings through the given mapping
+	pub async fn ascend_being_interpolated_with(&self, being: Uid, mapping: &impl ValueMapping<T>) -> Result<Vec<AscendedBeing<T>>, String> {
+		self.ascend_being_interpolated_with_blocking(being, mapping)
+	}
+
+	pub fn ascend_being_interpolated_with_blocking(&self, being: Uid, mapping: &impl ValueMapping<T>) -> Result<Vec<AscendedBeing<T>>, String> {
+		let mut interpolator = Interpolator::new(&self.beings, &self.worlds, mapping);
+		let mut res: Vec<AscendedBeing<T>> = Vec::new();
+		for b in self.beings.iter() {
+			if b.id == being {
+				res.push(interpolator.being(self.mark_computed(b.ascend(&self.worlds)?))?);
+			}
+		}
+		Ok(res)
+	}
+
+	// read a property with the references in it expanded
+	pub async fn get_property_interpolated(&self, being: Uid, property: Uid) -> Result<T, String>
+	where
+		T: TryFrom<StarValue> + Into<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.get_property_interpolated_blocking(being, property)
+	}
+
+	pub fn get_property_interpolated_blocking(&self, being: Uid, property: Uid) -> Result<T, String>
+	where
+		T: TryFrom<StarValue> + Into<StarValue>,
+		<T as TryFrom<StarValue>>::Error: Display,
+	{
+		self.get_property_interpolated_with_blocking(being, property, &StarValueMapping)
+	}
+
+	// read a property with references expanded, reading and writing strings through the given mapping
+	pub async fn get_property_interpolated_with(&self, being: Uid, property: Uid, mapping: &impl ValueMapping<T>) -> Result<T, String> {
+		self.get_property_interpolated_with_blocking(being, property, mapping)
+	}
+
+	pub fn get_property_interpolated_with_blocking(&self, being: Uid, property: Uid, mapping: &impl ValueMapping<T>) -> Result<T, String> {
+		let b = self.beings.iter().find(|b| b.id == being).ok_or(format!("Being with id {} does not exist", being))?;
+		let ascended = b.ascend(&self.worlds)?;
+		for entity in ascended.entities.iter() {
+			if let Some(c) = entity.components.iter().find(|c| c.id == property) {
+				return Interpolator::new(&self.beings, &self.worlds, mapping).component(&ascended.name, &entity.name, &c.name, &c.data);
+			}
+		}
+		Err(format!("Property with id {} does not exist on being {}", property, being))
+	}
+
+	/*
+			Resources
+	*/
+
+	// insert a named resource
+	// returns the previous value
+	pub fn insert_resource(&mut self, name: String, value: T) -> Option<T> {
+		self.resources.insert(name, value)
+	}
+
+	// get a copy of a named resource
+	pub fn get_resource(&self, name: String) -> Option<T> {
+		self.resources.get(name)
+	}
+
+	// get a named resource for writing
+	// the resource has its own lock, holding it does not block the other resources
+	pub fn get_resource_mut(&mut self, name: String) -> Option<Resource<T>> {
+		self.resources.get_mut(name)
+	}
+
+	pub fn remove_resource(&mut self, name: String) -> Option<T> {
+		self.resources.remove(name)
+	}
+
+	// insert a resource of any type
+	// these are not serialized with the starsystem
+	pub fn insert_any_resource<R: Any + Send + Sync>(&mut self, value: R) -> Option<R> {
+		self.resources.insert_any(value)
+	}
+
+	// insert a resource of any serializable type
+	// it is serialized with the starsystem, call restore_any_resource after loading to get it back
+	pub fn insert_any_serialized_resource<R: Any + Send + Sync + Serialize>(&mut self, value: R) -> Option<R> {
+		self.resources.insert_any_serialized(value)
+	}
+
+	// restore a resource of any type from the loaded starsystem
+	// returns false if none was saved
+	pub fn restore_any_resource<R: Any + Send + Sync + Serialize + DeserializeOwned>(&mut self) -> Result<bool, String> {
+		self.resources.restore_any::<R>()
+	}
+
+	// get a resource of any type for reading or writing
+	pub fn get_any_resource<R: Any + Send + Sync>(&self) -> Option<Resource<R>> {
+		self.resources.get_any()
+	}
+
+	pub fn remove_any_resource<R: Any + Send + Sync>(&mut self) -> Option<R> {
+		self.resources.remove_any()
+	}
+
+	/*
+			Components
+	*/
+
+	// call f on every component of the variant of t in every world, in parallel
+	pub fn par_for_each(&self, t: T, f: impl Fn(&Uid, &Component<T>) + Sync + Send) {
+		self.worlds.par_iter().for_each(|(_, w)| w.par_for_each(t.clone(), &f));
+	}
+
+	// call f on every component of the variant of t in every world, in parallel, with write access
+	// every visited component is stamped as changed at the current tick of its world
+	// computed properties that read a visited component are not recomputed
+	pub fn par_for_each_mut(&self, t: T, f: impl Fn(&Uid, &mut Component<T>) + Sync + Send) {
+		self.worlds.par_iter().for_each(|(_, w)| w.par_for_each_mut(t.clone(), &f));
+	}
+
+	/*
+			Events
+	*/
+
+	// register an event type
+	pub fn add_event<E: 'static + Clone + Send + Sync>(&mut self) {
+		self.events.add::<E>();
+	}
+
+	pub fn event_writer<E: 'static + Clone + Send + Sync>(&self) -> EventWriter<E> {
+		self.events.writer()
+	}
+
+	// a reader sees every event once, keep it around to read the events of later ticks
+	pub fn event_reader<E: 'static + Clone + Send + Sync>(&self) -> EventReader<E> {
+		self.events.reader()
+	}
+
+	// advance the event channels by one tick
+	// the schedule does this at the end of every run
+	pub fn update_events(&mut self) {
+		self.events.update();
+	}
+
+	/*
+			Systems
+	*/
+
+	// query
+	// collect every property of the beings that matches the query
+	pub async fn query(&self, query: &Query) -> Result<Vec<QueryItem<T>>, String> {
+		self.query_blocking(query)
+	}
+
+	pub fn query_blocking(&self, query: &Query) -> Result<Vec<QueryItem<T>>, String> {
+		self.query_since_blocking(query, None)
+	}
+
+	// query since
+	// collect every property of the beings that matches the query
+	// the change filter of the query is compared against the given tick
+	pub async fn query_since(&self, query: &Query, since: Option<u64>) -> Result<Vec<QueryItem<T>>, String> {
+		self.query_since_blocking(query, since)
+	}
+
+	pub fn query_since_blocking(&self, query: &Query, since: Option<u64>) -> Result<Vec<QueryItem<T>>, String> {
+		// group the entities by world so every world is locked once
+		let mut owners: BTreeMap<Uid, BTreeMap<Uid, Uid>> = BTreeMap::new();
+		for b in self.beings.iter() {
+			for e in b.entities.iter() {
+				owners.entry(e.location.world.clone()).or_default().insert(e.id.clone(), b.id.clone());
+			}
+		}
+
+		let mut res = Vec::new();
+		for (world, owners) in owners {
+			let w = match self.worlds.get(&world) {
+				Some(w) => w,
+				None => return Err(format!("World with id {} does not exist", world)),
+			};
+			let entities: Vec<Uid> = owners.keys().cloned().collect();
+			for (entity, id, component) in w.collect_components_blocking(&entities, |index, c| query.matches(index, &c.name) && query.passes(c, since)) {
+				res.push(QueryItem { being: owners[&entity].clone(), entity, world: world.clone(), id, component });
 			}
 		}
 		Ok(res)
 	}
+
+	// removed since
+	// properties removed after the given tick that match the query
+	pub async fn removed_since(&self, query: &Query, since: Option<u64>) -> Vec<Removal<T>> {
+		self.removed_since_blocking(query, since)
+	}
+
+	pub fn removed_since_blocking(&self, query: &Query, since: Option<u64>) -> Vec<Removal<T>> {
+		let mut res: Vec<Removal<T>> = self.worlds.values().flat_map(|w| w.removals_since_blocking(since)).filter(|r| query.matches(r.component.data.index(), &r.component.name)).collect();
+		res.sort_by_key(|r| r.tick);
+		res
+	}
+
+	// clear removals
+	// forget the removals recorded at or before the given tick
+	pub async fn clear_removals(&self, until: u64) {
+		self.clear_removals_blocking(until)
+	}
+
+	pub fn clear_removals_blocking(&self, until: u64) {
+		for world in self.worlds.values() {
+			world.clear_removals_blocking(until);
+		}
+	}
+
+	// advance tick
+	// everything changed from now on is stamped with the new tick
+	// returns the new tick
+	pub async fn advance_tick(&mut self) -> u64 {
+		self.advance_tick_blocking()
+	}
+
+	pub fn advance_tick_blocking(&mut self) -> u64 {
+		self.tick += 1;
+		for world in self.worlds.values() {
+			world.set_tick(self.tick);
+		}
+		self.tick
+	}
+
+	// reserve the next tick without stamping the worlds with it
+	pub(crate) fn next_tick(&mut self) -> u64 {
+		self.tick += 1;
+		self.tick
+	}
+
+	// add system
+	// the system runs over the properties matching the query every time the systems are run
+	pub fn add_system(&mut self, stage: String, query: Query, system: impl Fn(&mut SystemContext<T>) + Send + Sync + 'static) -> &mut Self {
+		self.schedule.add_system(stage, query, system);
+		self
+	}
+
+	// apply commands
+	// apply a buffer of structural changes in order
+	// returns one result per command
+	pub async fn apply_commands(&mut self, commands: &mut Commands<T>) -> Vec<Result<(), String>> {
+		self.apply_commands_blocking(commands)
+	}
+
+	pub fn apply_commands_blocking(&mut self, commands: &mut Commands<T>) -> Vec<Result<(), String>> {
+		commands.apply(self)
+	}
+
+	// run the systems added to the starsystem
+	pub async fn run_systems(&mut self) -> Result<(), String> {
+		self.run_systems_blocking()
+	}
+
+	pub fn run_systems_blocking(&mut self) -> Result<(), String> {
+		let mut schedule = std::mem::take(&mut self.schedule);
+		let res = schedule.run(self);
+		self.schedule = schedule;
+		res
+	}
 }
@@ -0,0 +1,10 @@
+use super::ResolvedProperty;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// An entity of a resolved view, merged from every layer that has an entity of that name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedEntity<T> {
+	pub name: String,
+	pub properties: BTreeMap<String, ResolvedProperty<T>>,
+}
@@ -0,0 +1,69 @@
+pub use super::Uid;
+use super::AscendedBeing;
+pub use entity::ResolvedEntity;
+pub use property::ResolvedProperty;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+mod entity;
+mod property;
+
+/// Entities and properties whose name starts with this marker hide the ones of the same name in lower layers.
+pub const REMOVAL_MARKER: char = '!';
+
+/// The merged view of layered beings.
+/// Later layers override earlier ones at the property name level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Resolved<T> {
+	pub layers: Vec<Uid>,
+	pub entities: BTreeMap<String, ResolvedEntity<T>>,
+}
+
+impl<T: Clone> Resolved<T> {
+	// merge ascended beings, lowest layer first
+	pub(crate) fn merge(layers: Vec<AscendedBeing<T>>) -> Self {
+		let mut resolved = Self { layers: layers.iter().map(|l| l.id.clone()).collect(), entities: BTreeMap::new() };
+		for layer in layers {
+			for entity in layer.entities {
+				// a removal marker on an entity hides the whole entity
+				if let Some(name) = entity.name.strip_prefix(REMOVAL_MARKER) {
+					resolved.entities.remove(name);
+					continue;
+				}
+				// an entity holding only removal markers hides properties of the entity below, but never brings in an entity of its own
+				let only_markers = !entity.components.is_empty() && entity.components.iter().all(|c| c.name.starts_with(REMOVAL_MARKER));
+				let e = match resolved.entities.get_mut(&entity.name) {
+					Some(e) => e,
+					None if only_markers => continue,
+					None => resolved.entities.entry(entity.name.clone()).or_insert_with(|| ResolvedEntity { name: entity.name.clone(), properties: BTreeMap::new() }),
+				};
+				for component in entity.components {
+					match component.name.strip_prefix(REMOVAL_MARKER) {
+						Some(name) => {
+							e.properties.remove(name);
+						}
+						None => {
+							e.properties.insert(component.name.clone(), ResolvedProperty { name: component.name, data: component.data, layer: layer.id.clone(), id: component.id });
+						}
+					}
+				}
+			}
+		}
+		resolved
+	}
+
+	// the resolved property of an entity
+	pub fn get(&self, entity: &str, property: &str) -> Option<&ResolvedProperty<T>> {
+		self.entities.get(entity).and_then(|e| e.properties.get(property))
+	}
+
+	// the value of a property
+	pub fn value(&self, entity: &str, property: &str) -> Option<T> {
+		self.get(entity, property).map(|p| p.data.clone())
+	}
+
+	// the layer a property came from
+	pub fn provenance(&self, entity: &str, property: &str) -> Option<&Uid> {
+		self.get(entity, property).map(|p| &p.layer)
+	}
+}
@@ -0,0 +1,13 @@
+use super::Uid;
+use serde::{Deserialize, Serialize};
+
+/// A property of a resolved view with the layer it came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedProperty<T> {
+	pub name: String,
+	pub data: T,
+	// the being the value came from
+	pub layer: Uid,
+	// the id of the property in that being
+	pub id: Uid,
+}
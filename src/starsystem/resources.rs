@@ -0,0 +1,193 @@
+use super::mapping::{from_value, value};
+use super::StarValue;
+use serde::de::DeserializeOwned;
+use serde::ser::{Error, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::any::{type_name, Any, TypeId};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+/// A single resource behind its own lock, holding one does not block the others.
+pub type Resource<R> = Arc<RwLock<R>>;
+
+// turns a typed resource into a value of a snapshot
+type SaveFn = fn(&(dyn Any + Send + Sync)) -> Result<StarValue, String>;
+
+// a resource of any type, with how to save it if it is serializable
+#[derive(Clone)]
+struct Typed {
+	// the resource, a `RwLock<R>`
+	resource: Arc<dyn Any + Send + Sync>,
+	name: &'static str,
+	save: Option<SaveFn>,
+}
+
+fn save<R: Any + Send + Sync + Serialize>(resource: &(dyn Any + Send + Sync)) -> Result<StarValue, String> {
+	let resource = resource.downcast_ref::<RwLock<R>>().ok_or(format!("resource is not a {}", type_name::<R>()))?;
+	let resource = resource.read().unwrap();
+	value(&*resource)
+}
+
+/// Global values of a starsystem that are not tied to any being.
+/// Values of the enum are keyed by name and are serialized with the starsystem.
+/// Values of any other type are keyed by their type, they are serialized when inserted with `insert_any_serialized`
+/// and come back from a snapshot once `restore_any` is called for their type.
+/// Clones share the same values, so systems can read and write them while they run.
+#[derive(Clone)]
+pub struct Resources<T> {
+	pub named: Arc<RwLock<BTreeMap<String, Resource<T>>>>,
+	typed: Arc<RwLock<BTreeMap<TypeId, Typed>>>,
+	// typed resources read from a snapshot, keyed by type name, until they are restored
+	saved: Arc<RwLock<BTreeMap<String, StarValue>>>,
+}
+
+impl<T> Default for Resources<T> {
+	fn default() -> Self {
+		Self { named: Arc::new(RwLock::new(BTreeMap::new())), typed: Arc::new(RwLock::new(BTreeMap::new())), saved: Arc::new(RwLock::new(BTreeMap::new())) }
+	}
+}
+
+impl<T: Debug> Debug for Resources<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let typed: Vec<&str> = self.typed.read().unwrap().values().map(|t| t.name).collect();
+		f.debug_struct("Resources").field("named", &self.named).field("typed", &typed).field("saved", &self.saved).finish()
+	}
+}
+
+impl<T: Serialize> Serialize for Resources<T> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		// the maps are not held locked while each resource is read
+		let named: Vec<(String, Resource<T>)> = self.named.read().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+		let guards: Vec<(&String, RwLockReadGuard<'_, T>)> = named.iter().map(|(k, v)| (k, v.read().unwrap())).collect();
+		let named: BTreeMap<&String, &T> = guards.iter().map(|(k, v)| (*k, &**v)).collect();
+
+		// resources that were never restored are written back as they were read
+		let mut typed = self.saved.read().unwrap().clone();
+		let serialized: Vec<Typed> = self.typed.read().unwrap().values().filter(|t| t.save.is_some()).cloned().collect();
+		for t in serialized {
+			let save = t.save.unwrap();
+			typed.insert(t.name.to_string(), save(&*t.resource).map_err(S::Error::custom)?);
+		}
+
+		let mut state = serializer.serialize_struct("Resources", 2)?;
+		state.serialize_field("named", &named)?;
+		state.serialize_field("typed", &typed)?;
+		state.end()
+	}
+}
+
+// resources as they are serialized
+#[derive(Deserialize)]
+struct ResourcesData<T> {
+	named: BTreeMap<String, T>,
+	#[serde(default)]
+	typed: BTreeMap<String, StarValue>,
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Resources<T> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let data = ResourcesData::<T>::deserialize(deserializer)?;
+		let named = data.named.into_iter().map(|(k, v)| (k, Arc::new(RwLock::new(v)))).collect();
+		Ok(Self { named: Arc::new(RwLock::new(named)), typed: Arc::new(RwLock::new(BTreeMap::new())), saved: Arc::new(RwLock::new(data.typed)) })
+	}
+}
+
+impl<T: Clone> Resources<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	// insert a named resource
+	// an existing resource is replaced in place, so handles to it see the new value
+	// returns the previous value
+	pub fn insert(&self, name: String, value: T) -> Option<T> {
+		let existing = match self.named.write().unwrap().entry(name) {
+			Entry::Occupied(e) => e.get().clone(),
+			Entry::Vacant(e) => {
+				e.insert(Arc::new(RwLock::new(value)));
+				return None;
+			}
+		};
+		let previous = std::mem::replace(&mut *existing.write().unwrap(), value);
+		Some(previous)
+	}
+
+	// get a copy of a named resource
+	pub fn get(&self, name: String) -> Option<T> {
+		let resource = self.named.read().unwrap().get(&name).cloned()?;
+		let value = resource.read().unwrap().clone();
+		Some(value)
+	}
+
+	// get a named resource for writing
+	// only this resource is locked while it is written
+	pub fn get_mut(&self, name: String) -> Option<Resource<T>> {
+		self.named.read().unwrap().get(&name).cloned()
+	}
+
+	pub fn remove(&self, name: String) -> Option<T> {
+		let resource = self.named.write().unwrap().remove(&name)?;
+		let value = resource.read().unwrap().clone();
+		Some(value)
+	}
+
+	// insert a resource of any type
+	// an existing resource is replaced in place, so handles to it see the new value
+	// returns the previous value
+	pub fn insert_any<R: Any + Send + Sync>(&self, value: R) -> Option<R> {
+		self.insert_typed(value, None)
+	}
+
+	// insert a resource of any serializable type
+	// it is saved with the starsystem, use `restore_any` to get it back after loading
+	pub fn insert_any_serialized<R: Any + Send + Sync + Serialize>(&self, value: R) -> Option<R> {
+		self.insert_typed(value, Some(save::<R>))
+	}
+
+	fn insert_typed<R: Any + Send + Sync>(&self, value: R, save: Option<SaveFn>) -> Option<R> {
+		let existing = match self.typed.write().unwrap().entry(TypeId::of::<R>()) {
+			Entry::Occupied(mut e) => {
+				if save.is_some() {
+					e.get_mut().save = save;
+				}
+				e.get().resource.clone().downcast::<RwLock<R>>().ok()
+			}
+			Entry::Vacant(e) => {
+				e.insert(Typed { resource: Arc::new(RwLock::new(value)), name: type_name::<R>(), save });
+				return None;
+			}
+		};
+		existing.map(|r| std::mem::replace(&mut *r.write().unwrap(), value))
+	}
+
+	// get a resource of any type
+	// only this resource is locked while it is read or written
+	pub fn get_any<R: Any + Send + Sync>(&self) -> Option<Resource<R>> {
+		let resource = self.typed.read().unwrap().get(&TypeId::of::<R>())?.resource.clone();
+		resource.downcast::<RwLock<R>>().ok()
+	}
+
+	// remove a resource of any type
+	// returns the value, unless a handle to it is still held somewhere
+	pub fn remove_any<R: Any + Send + Sync>(&self) -> Option<R> {
+		let typed = self.typed.write().unwrap().remove(&TypeId::of::<R>())?;
+		let resource = typed.resource.downcast::<RwLock<R>>().ok()?;
+		Arc::try_unwrap(resource).ok().map(|r| r.into_inner().unwrap())
+	}
+
+	// restore a typed resource read from a snapshot
+	// returns false if the snapshot did not hold a resource of this type
+	pub fn restore_any<R: Any + Send + Sync + Serialize + DeserializeOwned>(&self) -> Result<bool, String> {
+		let name = type_name::<R>();
+		let saved = match self.saved.read().unwrap().get(name) {
+			Some(saved) => saved.clone(),
+			None => return Ok(false),
+		};
+		let value: R = from_value(saved, name)?;
+		self.saved.write().unwrap().remove(name);
+		self.insert_any_serialized(value);
+		Ok(true)
+	}
+}
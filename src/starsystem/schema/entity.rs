@@ -0,0 +1,27 @@
+use super::PropertySchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The properties an entity of a being schema has.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySchema<T> {
+	pub name: String,
+	pub required: bool,
+	pub properties: BTreeMap<String, PropertySchema<T>>,
+}
+
+impl<T> EntitySchema<T> {
+	pub fn new(name: String) -> Self {
+		Self { name, required: false, properties: BTreeMap::new() }
+	}
+
+	pub fn required(mut self) -> Self {
+		self.required = true;
+		self
+	}
+
+	pub fn property(mut self, property: PropertySchema<T>) -> Self {
+		self.properties.insert(property.name.clone(), property);
+		self
+	}
+}
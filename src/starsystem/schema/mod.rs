@@ -0,0 +1,117 @@
+use super::{AscendedBeing, BeingSpec, EntitySpec, EnumIndex, PropertySpec};
+pub use entity::EntitySchema;
+pub use property::PropertySchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+pub use violation::{SchemaViolation, ViolationKind};
+
+mod entity;
+mod property;
+mod violation;
+
+/// What a starsystem does when a mutation breaks the schema of a being.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SchemaMode {
+	// the mutation fails and nothing changes
+	#[default]
+	Reject,
+	// the mutation goes through and the violations are kept as warnings
+	Warn,
+}
+
+/// The layout beings of one name must have: required entities, required properties,
+/// the variants every property may hold and the values new properties start with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeingSchema<T> {
+	pub entities: BTreeMap<String, EntitySchema<T>>,
+}
+
+impl<T> Default for BeingSchema<T> {
+	fn default() -> Self {
+		Self { entities: BTreeMap::new() }
+	}
+}
+
+impl<T: EnumIndex> BeingSchema<T> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn entity(mut self, entity: EntitySchema<T>) -> Self {
+		self.entities.insert(entity.name.clone(), entity);
+		self
+	}
+
+	// every violation of an ascended being
+	pub fn validate(&self, being: &AscendedBeing<T>) -> Vec<SchemaViolation> {
+		let mut violations = Vec::new();
+		for (name, schema) in self.entities.iter() {
+			let entities: Vec<_> = being.entities.iter().filter(|e| e.name == *name).collect();
+			if entities.is_empty() {
+				if schema.required {
+					violations.push(SchemaViolation::new(format!("{}/{}", being.name, name), ViolationKind::MissingEntity));
+				}
+				continue;
+			}
+			for entity in entities {
+				for (property, p) in schema.properties.iter() {
+					let components: Vec<_> = entity.components.iter().filter(|c| c.name == *property).collect();
+					let path = format!("{}/{}/{}", being.name, entity.name, property);
+					if components.is_empty() && p.required {
+						violations.push(SchemaViolation::new(path.clone(), ViolationKind::MissingProperty));
+					}
+					for c in components {
+						violations.extend(p.check(&path, &c.data));
+					}
+				}
+			}
+		}
+		violations
+	}
+
+	// add the required entities and the properties with a default value a spec is missing
+	// like conceiving the being and constituting its entities would, added entities and properties go last
+	pub(crate) fn complete(&self, spec: &mut BeingSpec<T>)
+	where
+		T: Clone,
+	{
+		for (name, e) in self.entities.iter() {
+			if e.required && !spec.entities.iter().any(|s| s.name == *name) {
+				spec.entities.push(EntitySpec::new(name.clone()));
+			}
+		}
+		for entity in spec.entities.iter_mut() {
+			let schema = match self.entities.get(&entity.name) {
+				Some(schema) => schema,
+				None => continue,
+			};
+			for p in schema.properties.values() {
+				if let Some(default) = p.default.as_ref().filter(|_| !entity.properties.iter().any(|s| s.name == p.name)) {
+					entity.properties.push(PropertySpec { name: p.name.clone(), value: default.clone() });
+				}
+			}
+		}
+	}
+
+	// the violation of storing a value in a property, if any
+	pub fn check_value(&self, being: &str, entity: &str, property: &str, value: &T) -> Option<SchemaViolation> {
+		let p = self.entities.get(entity)?.properties.get(property)?;
+		p.check(&format!("{}/{}/{}", being, entity, property), value)
+	}
+
+	// the violation of removing an entity, if any
+	pub fn check_entity_removal(&self, being: &str, entity: &str) -> Option<SchemaViolation> {
+		match self.entities.get(entity) {
+			Some(e) if e.required => Some(SchemaViolation::new(format!("{}/{}", being, entity), ViolationKind::MissingEntity)),
+			_ => None,
+		}
+	}
+
+	// the violation of removing a property, if any
+	pub fn check_property_removal(&self, being: &str, entity: &str, property: &str) -> Option<SchemaViolation> {
+		match self.entities.get(entity).and_then(|e| e.properties.get(property)) {
+			Some(p) if p.required => Some(SchemaViolation::new(format!("{}/{}/{}", being, entity, property), ViolationKind::MissingProperty)),
+			_ => None,
+		}
+	}
+}
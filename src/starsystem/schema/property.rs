@@ -0,0 +1,43 @@
+use super::{EnumIndex, SchemaViolation, ViolationKind};
+use serde::{Deserialize, Serialize};
+
+/// A property of an entity schema.
+/// An empty list of variants allows every variant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertySchema<T> {
+	pub name: String,
+	pub required: bool,
+	pub variants: Vec<usize>,
+	// the value the property is created with when its entity is constituted
+	pub default: Option<T>,
+}
+
+impl<T: EnumIndex> PropertySchema<T> {
+	pub fn new(name: String) -> Self {
+		Self { name, required: false, variants: Vec::new(), default: None }
+	}
+
+	pub fn required(mut self) -> Self {
+		self.required = true;
+		self
+	}
+
+	// allow the variant of the given value
+	pub fn variant(mut self, value: T) -> Self {
+		self.variants.push(value.index());
+		self
+	}
+
+	pub fn default(mut self, value: T) -> Self {
+		self.default = Some(value);
+		self
+	}
+
+	pub(crate) fn check(&self, path: &str, value: &T) -> Option<SchemaViolation> {
+		let index = value.index();
+		if self.variants.is_empty() || self.variants.contains(&index) {
+			return None;
+		}
+		Some(SchemaViolation::new(path.to_string(), ViolationKind::VariantNotAllowed { found: index, allowed: self.variants.clone() }))
+	}
+}
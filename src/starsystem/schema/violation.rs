@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ViolationKind {
+	MissingEntity,
+	MissingProperty,
+	// the variant index of the value and the ones the schema allows
+	VariantNotAllowed { found: usize, allowed: Vec<usize> },
+}
+
+/// A break of a being schema, at a path like `config/headers/Content-Type`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+	pub path: String,
+	pub kind: ViolationKind,
+}
+
+impl SchemaViolation {
+	pub fn new(path: String, kind: ViolationKind) -> Self {
+		Self { path, kind }
+	}
+}
+
+impl Display for SchemaViolation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.kind {
+			ViolationKind::MissingEntity => write!(f, "{}: missing required entity", self.path),
+			ViolationKind::MissingProperty => write!(f, "{}: missing required property", self.path),
+			ViolationKind::VariantNotAllowed { found, allowed } => write!(f, "{}: variant {} is not allowed, expected one of {:?}", self.path, found, allowed),
+		}
+	}
+}
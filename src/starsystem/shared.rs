@@ -0,0 +1,565 @@
+use super::{spawn, AscendedBeing, Being, BeingSchema, BeingSpec, Component, ComputedProperties, Constraint, Constraints, SpawnedBeing, EnumIndex, EventBus, NameConflictPolicy, NameConflicts, PropertyError, Resources, Schedule, SchemaMode, SchemaViolation, StarSystem, StorageKind, Template, TemplateLink, Uid, World};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, RwLock};
+use strum::IntoEnumIterator;
+
+/// A cloneable handle to a starsystem that can be shared across threads.
+/// Every method takes `&self`. Reads take shared locks, writes only lock the being and the world they touch.
+/// Writes are checked against the schemas and constraints of the starsystem it was made from and recompute its computed properties.
+/// Its schedule, events and templates are kept for the starsystem the handle collapses back into.
+#[derive(Debug, Clone)]
+pub struct SharedStarSystem<T> {
+	pub worlds: Arc<RwLock<BTreeMap<Uid, World<T>>>>,
+	pub beings: Arc<RwLock<BTreeMap<Uid, Arc<RwLock<Being>>>>>,
+	pub resources: Resources<T>,
+	// the kind of storage new worlds keep their components in
+	pub storage: StorageKind,
+	// checked by every write of a property value, shared by every clone of the handle
+	pub constraints: Arc<RwLock<Constraints<T>>>,
+	// what adding an entity or property under a name already in use does, shared by every clone of the handle
+	pub name_conflicts: Arc<RwLock<NameConflicts>>,
+	// the schemas beings are held to, by being name, shared by every clone of the handle
+	pub schemas: Arc<RwLock<BTreeMap<String, BeingSchema<T>>>>,
+	pub schema_mode: Arc<RwLock<SchemaMode>>,
+	// violations let through in warn mode
+	pub schema_warnings: Arc<RwLock<Vec<SchemaViolation>>>,
+	// properties derived from other properties, recomputed by the writes of the handle
+	pub computed: Arc<RwLock<ComputedProperties<T>>>,
+	// not run by the handle, given back by `to_star_system`
+	pub schedule: Arc<Mutex<Schedule<T>>>,
+	pub events: EventBus,
+	pub templates: Arc<RwLock<BTreeMap<String, Template<T>>>>,
+	pub template_links: Arc<RwLock<BTreeMap<Uid, TemplateLink<T>>>>,
+}
+
+impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> From<StarSystem<T>> for SharedStarSystem<T> {
+	fn from(starsystem: StarSystem<T>) -> Self {
+		let beings = starsystem.beings.into_iter().map(|b| (b.id.clone(), Arc::new(RwLock::new(b)))).collect();
+		Self { worlds: Arc::new(RwLock::new(starsystem.worlds)), beings: Arc::new(RwLock::new(beings)), resources: starsystem.resources, storage: starsystem.storage, constraints: Arc::new(RwLock::new(starsystem.constraints)), name_conflicts: Arc::new(RwLock::new(starsystem.name_conflicts)), schemas: Arc::new(RwLock::new(starsystem.schemas)), schema_mode: Arc::new(RwLock::new(starsystem.schema_mode)), schema_warnings: Arc::new(RwLock::new(starsystem.schema_warnings)), computed: Arc::new(RwLock::new(starsystem.computed)), schedule: Arc::new(Mutex::new(starsystem.schedule)), events: starsystem.events, templates: Arc::new(RwLock::new(starsystem.templates)), template_links: Arc::new(RwLock::new(starsystem.template_links)) }
+	}
+}
+
+impl<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> SharedStarSystem<T> {
+	// create a new shared starsystem
+	pub async fn new() -> Self {
+		Self::new_blocking()
+	}
+
+	pub fn new_blocking() -> Self {
+		Self::from(StarSystem::new_blocking())
+	}
+
+	// collapse the shared starsystem back into a starsystem
+	// worlds are handles, so the returned starsystem shares its components with this one
+	// the schemas, computed properties, schedule, events and templates are copied back
+	pub async fn to_star_system(&self) -> StarSystem<T> {
+		self.to_star_system_blocking()
+	}
+
+	pub fn to_star_system_blocking(&self) -> StarSystem<T> {
+		let worlds = self.worlds.read().unwrap().clone();
+		let beings = self.beings.read().unwrap().values().map(|b| b.read().unwrap().clone()).collect();
+		let tick = worlds.values().map(|w| w.current_tick()).max().unwrap_or(0);
+		StarSystem { worlds, beings, schedule: self.schedule.lock().unwrap().clone(), tick, resources: self.resources.clone(), events: self.events.clone(), storage: self.storage, computed: self.computed.read().unwrap().clone(), schemas: self.schemas.read().unwrap().clone(), schema_mode: *self.schema_mode.read().unwrap(), schema_warnings: self.schema_warnings.read().unwrap().clone(), constraints: self.constraints.read().unwrap().clone(), templates: self.templates.read().unwrap().clone(), template_links: self.template_links.read().unwrap().clone(), name_conflicts: *self.name_conflicts.read().unwrap() }
+	}
+
+	// constrain the values of every property of a name
+	pub fn add_constraint(&self, property_name: String, constraint: Constraint<T>) -> &Self {
+		self.constraints.write().unwrap().by_name.entry(property_name).or_default().push(constraint);
+		self
+	}
+
+	// constrain the values of the property at a path like `config/server/port`
+	pub fn add_path_constraint(&self, path: String, constraint: Constraint<T>) -> &Self {
+		self.constraints.write().unwrap().by_path.entry(path).or_default().push(constraint);
+		self
+	}
+
+	// choose what adding an entity under a name its being already uses does
+	pub fn set_entity_conflict_policy(&self, policy: NameConflictPolicy) -> &Self {
+		self.name_conflicts.write().unwrap().entities = policy;
+		self
+	}
+
+	// choose what adding a property under a name its entity already uses does
+	pub fn set_property_conflict_policy(&self, policy: NameConflictPolicy) -> &Self {
+		self.name_conflicts.write().unwrap().properties = policy;
+		self
+	}
+
+	// hold every being of the given name to a schema
+	pub fn set_schema(&self, being_name: String, schema: BeingSchema<T>) -> &Self {
+		self.schemas.write().unwrap().insert(being_name, schema);
+		self
+	}
+
+	// choose whether writes that break a schema are rejected or kept as warnings
+	pub fn set_schema_mode(&self, mode: SchemaMode) -> &Self {
+		*self.schema_mode.write().unwrap() = mode;
+		self
+	}
+
+	// check a value against the constraints and the schema of its being
+	fn check_value(&self, being: &str, entity: &str, property: &str, value: &T) -> Result<(), PropertyError> {
+		self.constraints.read().unwrap().validate(being, entity, property, value)?;
+		let violation = self.schemas.read().unwrap().get(being).and_then(|s| s.check_value(being, entity, property, value));
+		self.enforce_schema(violation.into_iter().collect())
+	}
+
+	// reject violations or keep them as warnings, depending on the schema mode
+	fn enforce_schema(&self, violations: Vec<SchemaViolation>) -> Result<(), PropertyError> {
+		if violations.is_empty() {
+			return Ok(());
+		}
+		match *self.schema_mode.read().unwrap() {
+			SchemaMode::Reject => Err(PropertyError::Schema(violations)),
+			SchemaMode::Warn => {
+				self.schema_warnings.write().unwrap().extend(violations);
+				Ok(())
+			}
+		}
+	}
+
+	// the new values of the computed properties that read a property about to be set to a value, inputs before the properties that read them
+	// computed values are checked against the constraints and the schema of their being before anything is written
+	// reads the names of the beings, so the caller may not hold a being locked
+	fn plan_recompute(&self, worlds: &BTreeMap<Uid, World<T>>, property: &Uid, value: T) -> Result<Vec<(Uid, T)>, PropertyError> {
+		// the computed lock is let go before the beings are read
+		let affected: Vec<_> = {
+			let computed = self.computed.read().unwrap();
+			computed.affected(property).into_iter().filter_map(|id| computed.get(&id).map(|c| (id, c.inputs.clone(), c.compute.clone()))).collect()
+		};
+		let check = !affected.is_empty() && (!self.constraints.read().unwrap().is_empty() || !self.schemas.read().unwrap().is_empty());
+		let mut planned = BTreeMap::from([(property.clone(), value)]);
+		let mut order = Vec::with_capacity(affected.len());
+		for (id, inputs, compute) in affected {
+			let values = inputs.iter().map(|i| match (planned.get(i), worlds.values().find(|w| w.has_component(i.clone()))) {
+				(Some(value), _) => Ok(value.clone()),
+				(None, Some(world)) => world.get_component_blocking(i.clone()).map(|c| c.data),
+				(None, None) => Err(format!("Property with id {} does not exist", i)),
+			}).collect::<Result<Vec<T>, String>>()?;
+			let value = compute(&values);
+			if check {
+				if let Some((b, e, p)) = self.property_path(&id) {
+					self.check_value(&b, &e, &p, &value)?;
+				}
+			}
+			planned.insert(id.clone(), value);
+			order.push(id);
+		}
+		Ok(order.into_iter().filter_map(|id| planned.remove(&id).map(|value| (id, value))).collect())
+	}
+
+	// write the planned values of computed properties
+	fn write_computed(&self, worlds: &BTreeMap<Uid, World<T>>, planned: Vec<(Uid, T)>) -> Result<(), String> {
+		for (id, value) in planned {
+			match worlds.values().find(|w| w.has_component(id.clone())) {
+				Some(world) => world.set_component_blocking(id, value)?,
+				None => return Err(format!("Property with id {} does not exist", id)),
+			};
+		}
+		Ok(())
+	}
+
+	// reject writes to a computed property, it is only written through its inputs
+	fn check_not_computed(&self, property: &Uid) -> Result<(), PropertyError> {
+		match self.computed.read().unwrap().is_computed(property) {
+			true => Err(format!("Property {} is computed", property).into()),
+			false => Ok(()),
+		}
+	}
+
+	// the names of the being, entity and property of a property id
+	// only searched for when there are constraints or schemas to check
+	fn property_path(&self, property: &Uid) -> Option<(String, String, String)> {
+		let beings: Vec<Arc<RwLock<Being>>> = self.beings.read().unwrap().values().cloned().collect();
+		beings.iter().find_map(|b| {
+			let b = b.read().unwrap();
+			b.entities.iter().find_map(|e| e.properties.iter().find(|p| p.id == *property).map(|p| (b.name.clone(), e.name.clone(), p.name.clone())))
+		})
+	}
+
+	// get the lock of a single being
+	fn being(&self, id: &Uid) -> Result<Arc<RwLock<Being>>, String> {
+		match self.beings.read().unwrap().get(id) {
+			Some(b) => Ok(b.clone()),
+			None => Err(format!("Being with id {} does not exist", id)),
+		}
+	}
+
+	// if no worlds exist, create one
+	// returns a random world
+	fn random_world(&self) -> Uid {
+		if self.worlds.read().unwrap().is_empty() {
+			let mut worlds = self.worlds.write().unwrap();
+			if worlds.is_empty() {
+				let world = World::<T>::with_storage_blocking(self.storage);
+				worlds.insert(world.id.clone(), world);
+			}
+		}
+		let worlds = self.worlds.read().unwrap();
+		worlds.iter().nth(rand::random::<usize>() % worlds.len()).unwrap().0.clone()
+	}
+
+	// Create a new being
+	pub async fn conceive_being(&self, name: String) -> Result<Uid, String> {
+		self.conceive_being_blocking(name)
+	}
+
+	pub fn conceive_being_blocking(&self, name: String) -> Result<Uid, String> {
+		let being = Being::new(name);
+		let id = being.id.clone();
+		let required: Vec<String> = self.schemas.read().unwrap().get(&being.name).map(|s| s.entities.values().filter(|e| e.required).map(|e| e.name.clone()).collect()).unwrap_or_default();
+		self.beings.write().unwrap().insert(id.clone(), Arc::new(RwLock::new(being)));
+
+		// constitute the entities the schema of the being requires
+		// if one fails the being is killed again, so no half made being is left behind
+		for entity in required {
+			if let Err(err) = self.constitute_being_blocking(id.clone(), entity) {
+				self.kill_being_blocking(id)?;
+				return Err(err);
+			}
+		}
+		Ok(id)
+	}
+
+	// set being
+	// create a new being with provided id and name
+	// if the being already exists, it will be overwritten
+	pub async fn set_being(&self, id: Uid, name: String) -> Result<Uid, String> {
+		self.set_being_blocking(id, name)
+	}
+
+	pub fn set_being_blocking(&self, id: Uid, name: String) -> Result<Uid, String> {
+		let being = Being { id: id.clone(), entities: Vec::new(), name };
+		self.beings.write().unwrap().insert(id.clone(), Arc::new(RwLock::new(being)));
+		Ok(id)
+	}
+
+	// kill being
+	// remove all entities from each world that the being owns
+	// remove being from beings
+	pub async fn kill_being(&self, id: Uid) -> Result<(), String> {
+		self.kill_being_blocking(id)
+	}
+
+	pub fn kill_being_blocking(&self, id: Uid) -> Result<(), String> {
+		let being = match self.beings.write().unwrap().remove(&id) {
+			Some(b) => b,
+			None => return Err(format!("Being with id {} does not exist", id)),
+		};
+		let worlds = self.worlds.read().unwrap();
+		let mut b = being.write().unwrap();
+		let properties = b.property_ids();
+		b.dissolve_all(&worlds);
+		self.computed.write().unwrap().forget(&properties);
+		self.template_links.write().unwrap().remove(&id);
+		Ok(())
+	}
+
+	// get being by id
+	pub async fn get_being(&self, id: Uid) -> Result<Being, String> {
+		self.get_being_blocking(id)
+	}
+
+	pub fn get_being_blocking(&self, id: Uid) -> Result<Being, String> {
+		Ok(self.being(&id)?.read().unwrap().clone())
+	}
+
+	// constitue being
+	// if no worlds exist, create one
+	// create a new entity on a random world
+	// add entity to being
+	pub async fn constitute_being(&self, being: Uid, entity_name: String) -> Result<Uid, String> {
+		self.constitute_being_blocking(being, entity_name)
+	}
+
+	pub fn constitute_being_blocking(&self, being: Uid, entity_name: String) -> Result<Uid, String> {
+		let policy = self.name_conflicts.read().unwrap().entities;
+		self.constitute_being_with_policy_blocking(being, entity_name, policy)
+	}
+
+	// constitute being, resolving a name already in use with the given policy instead of the one of the starsystem
+	// with Merge the existing entity is returned
+	pub async fn constitute_being_with_policy(&self, being: Uid, entity_name: String, policy: NameConflictPolicy) -> Result<Uid, String> {
+		self.constitute_being_with_policy_blocking(being, entity_name, policy)
+	}
+
+	pub fn constitute_being_with_policy_blocking(&self, being: Uid, entity_name: String, policy: NameConflictPolicy) -> Result<Uid, String> {
+		let world = self.random_world();
+		let being = self.being(&being)?;
+		let worlds = self.worlds.read().unwrap();
+		let mut b = being.write().unwrap();
+		if let Some(existing) = b.entities.iter().find(|e| e.name == entity_name).map(|e| e.id.clone()) {
+			match policy {
+				NameConflictPolicy::Reject => return Err(format!("Being with id {} already has an entity named {}", b.id, entity_name)),
+				NameConflictPolicy::Merge => return Ok(existing),
+				NameConflictPolicy::Replace | NameConflictPolicy::AllowDuplicates => {}
+			}
+		}
+
+		// the properties the schema gives a default value are checked before anything is replaced
+		let defaults: Vec<(String, T)> = self.schemas.read().unwrap().get(&b.name).and_then(|s| s.entities.get(&entity_name)).map(|e| e.properties.values().filter_map(|p| p.default.clone().map(|d| (p.name.clone(), d))).collect()).unwrap_or_default();
+		for (name, value) in defaults.iter() {
+			self.check_value(&b.name, &entity_name, name, value)?;
+		}
+		let (entity, replaced) = match policy {
+			NameConflictPolicy::AllowDuplicates => (b.insert_entity(&worlds, world, Uid::new(), entity_name)?, Vec::new()),
+			_ => {
+				// the entity it replaces takes its properties with it
+				let replaced: Vec<Uid> = b.entities.iter().filter(|e| e.name == entity_name).flat_map(|e| e.properties.iter().map(|p| p.id.clone())).collect();
+				(b.constitute(&worlds, world, Uid::new(), entity_name)?, replaced)
+			}
+		};
+		for (name, value) in defaults {
+			b.add_property(&worlds, entity.clone(), Uid::new(), value, name)?;
+		}
+		self.computed.write().unwrap().forget(&replaced);
+		Ok(entity)
+	}
+
+	// dissolve entity
+	// remove entity from being
+	// remove entity from world
+	pub async fn dissolve_entity(&self, being: Uid, entity: Uid) -> Result<(), String> {
+		self.dissolve_entity_blocking(being, entity)
+	}
+
+	pub fn dissolve_entity_blocking(&self, being: Uid, entity: Uid) -> Result<(), String> {
+		if let Ok(b) = self.being(&being) {
+			let worlds = self.worlds.read().unwrap();
+			let mut b = b.write().unwrap();
+			let properties: Vec<Uid> = b.entities.iter().filter(|e| e.id == entity).flat_map(|e| e.properties.iter().map(|p| p.id.clone())).collect();
+			if let Some(e) = b.entities.iter().find(|e| e.id == entity) {
+				let violation = self.schemas.read().unwrap().get(&b.name).and_then(|s| s.check_entity_removal(&b.name, &e.name));
+				self.enforce_schema(violation.into_iter().collect())?;
+			}
+			b.dissolve(&worlds, entity);
+			self.computed.write().unwrap().forget(&properties);
+		}
+		Ok(())
+	}
+
+	// add property to entity
+	pub async fn add_property(&self, being: Uid, entity: Uid, property: T, property_name: String) -> Result<Uid, PropertyError> {
+		self.add_property_blocking(being, entity, property, property_name)
+	}
+
+	pub fn add_property_blocking(&self, being: Uid, entity: Uid, property: T, property_name: String) -> Result<Uid, PropertyError> {
+		let policy = self.name_conflicts.read().unwrap().properties;
+		self.add_property_with_policy_blocking(being, entity, property, property_name, policy)
+	}
+
+	// add property to entity, resolving a name already in use with the given policy instead of the one of the starsystem
+	// with Merge the existing property is set and its id returned
+	pub async fn add_property_with_policy(&self, being: Uid, entity: Uid, property: T, property_name: String, policy: NameConflictPolicy) -> Result<Uid, PropertyError> {
+		self.add_property_with_policy_blocking(being, entity, property, property_name, policy)
+	}
+
+	pub fn add_property_with_policy_blocking(&self, being: Uid, entity: Uid, property: T, property_name: String, policy: NameConflictPolicy) -> Result<Uid, PropertyError> {
+		let being_id = being;
+		let being = self.being(&being_id).map_err(|_| "Could not add property to entity".to_string())?;
+		let worlds = self.worlds.read().unwrap();
+		let mut b = being.write().unwrap();
+		let mut existing = Vec::new();
+		if let Some(e) = b.entities.iter().find(|e| e.id == entity) {
+			self.check_value(&b.name, &e.name, &property_name, &property)?;
+			existing = e.properties.iter().filter(|p| p.name == property_name).map(|p| p.id.clone()).collect();
+			// replacing removes the existing properties first, which a schema may not allow
+			if policy == NameConflictPolicy::Replace && !existing.is_empty() {
+				let violation = self.schemas.read().unwrap().get(&b.name).and_then(|s| s.check_property_removal(&b.name, &e.name, &property_name));
+				self.enforce_schema(violation.into_iter().collect())?;
+			}
+		}
+		if let Some(first) = existing.first().cloned() {
+			match policy {
+				NameConflictPolicy::Reject => return Err(format!("Entity with id {} already has a property named {}", entity, property_name).into()),
+				// the computed properties that read it are planned without the being locked
+				NameConflictPolicy::Merge => {
+					drop(b);
+					drop(worlds);
+					return self.set_property_blocking(being_id, entity, first, property, property_name);
+				}
+				NameConflictPolicy::Replace => {
+					for p in existing.iter() {
+						b.remove_property(&worlds, p.clone());
+					}
+					self.computed.write().unwrap().forget(&existing);
+				}
+				NameConflictPolicy::AllowDuplicates => {}
+			}
+		}
+		Ok(b.add_property(&worlds, entity, Uid::new(), property, property_name)?)
+	}
+
+	// set property
+	pub async fn set_property(&self, being: Uid, entity: Uid, property: Uid, value: T, name: String) -> Result<Uid, PropertyError> {
+		self.set_property_blocking(being, entity, property, value, name)
+	}
+
+	pub fn set_property_blocking(&self, being: Uid, entity: Uid, property: Uid, value: T, name: String) -> Result<Uid, PropertyError> {
+		self.check_not_computed(&property)?;
+		let being = self.being(&being)?;
+		let worlds = self.worlds.read().unwrap();
+		let planned = self.plan_recompute(&worlds, &property, value.clone())?;
+		let mut b = being.write().unwrap();
+		if let Some(e) = b.entities.iter().find(|e| e.id == entity) {
+			self.check_value(&b.name, &e.name, &name, &value)?;
+		}
+		let id = b.set_property(&worlds, entity, property.clone(), value, name).ok_or(format!("Could not set property {}", property))?;
+		self.write_computed(&worlds, planned)?;
+		Ok(id)
+	}
+
+	// remove property by id
+	pub async fn remove_property(&self, property: Uid) -> Result<(), String> {
+		self.remove_property_blocking(property)
+	}
+
+	pub fn remove_property_blocking(&self, property: Uid) -> Result<(), String> {
+		let beings: Vec<Arc<RwLock<Being>>> = self.beings.read().unwrap().values().cloned().collect();
+		let worlds = self.worlds.read().unwrap();
+		for b in beings {
+			let mut b = b.write().unwrap();
+			let path = b.entities.iter().find_map(|e| e.properties.iter().find(|p| p.id == property).map(|p| (e.name.clone(), p.name.clone())));
+			if let Some((e, p)) = path {
+				let violation = self.schemas.read().unwrap().get(&b.name).and_then(|s| s.check_property_removal(&b.name, &e, &p));
+				self.enforce_schema(violation.into_iter().collect())?;
+				b.remove_property(&worlds, property.clone());
+				break;
+			}
+		}
+		self.computed.write().unwrap().forget(&[property]);
+		Ok(())
+	}
+
+	// set property given property id
+	pub async fn set_property_by_id(&self, property_id: Uid, property_value: T) -> Result<Uid, PropertyError> {
+		self.set_property_by_id_blocking(property_id, property_value)
+	}
+
+	pub fn set_property_by_id_blocking(&self, property_id: Uid, property_value: T) -> Result<Uid, PropertyError> {
+		self.check_not_computed(&property_id)?;
+		if !self.constraints.read().unwrap().is_empty() || !self.schemas.read().unwrap().is_empty() {
+			if let Some((b, e, p)) = self.property_path(&property_id) {
+				self.check_value(&b, &e, &p, &property_value)?;
+			}
+		}
+		let worlds = self.worlds.read().unwrap();
+		let planned = self.plan_recompute(&worlds, &property_id, property_value.clone())?;
+		if let Some(world) = worlds.values().find(|w| w.has_component(property_id.clone())) {
+			world.set_component_blocking(property_id.clone(), property_value)?;
+		}
+		self.write_computed(&worlds, planned)?;
+		Ok(property_id)
+	}
+
+	// spawn many beings at once
+	// the schema of each being adds its required entities and default properties after the ones of the spec,
+	// nothing is spawned if a being breaks its schema or a value breaks a constraint
+	// every entity goes to a random world and each world is locked once for the whole batch
+	// returns the ids of the beings, entities and properties in the order of the specs
+	pub async fn spawn_batch(&self, specs: impl IntoIterator<Item = BeingSpec<T>>) -> Result<Vec<SpawnedBeing>, String> {
+		self.spawn_batch_blocking(specs)
+	}
+
+	pub fn spawn_batch_blocking(&self, specs: impl IntoIterator<Item = BeingSpec<T>>) -> Result<Vec<SpawnedBeing>, String> {
+		let mut specs: Vec<BeingSpec<T>> = specs.into_iter().collect();
+		let mut violations = Vec::new();
+		let schemas = self.schemas.read().unwrap();
+		let constraints = self.constraints.read().unwrap();
+		for spec in specs.iter_mut() {
+			if let Some(schema) = schemas.get(&spec.name) {
+				schema.complete(spec);
+				violations.extend(schema.validate(&spec.ascend()));
+			}
+			constraints.validate_spec(spec)?;
+		}
+		drop((schemas, constraints));
+		self.enforce_schema(violations)?;
+		self.random_world();
+		let (beings, spawned) = spawn::spawn(&self.worlds.read().unwrap(), specs)?;
+		self.beings.write().unwrap().extend(beings.into_iter().map(|b| (b.id.clone(), Arc::new(RwLock::new(b)))));
+		Ok(spawned)
+	}
+
+	// add many properties at once
+	// accepts (being, entity, property, property name)
+	// the beings involved are locked for the whole batch, nothing is added if one of the entities does not exist
+	// or a value breaks a constraint or the schema of its being
+	// returns the ids of the properties in input order
+	pub async fn add_properties_batch(&self, properties: impl IntoIterator<Item = (Uid, Uid, T, String)>) -> Result<Vec<Uid>, String> {
+		self.add_properties_batch_blocking(properties)
+	}
+
+	pub fn add_properties_batch_blocking(&self, properties: impl IntoIterator<Item = (Uid, Uid, T, String)>) -> Result<Vec<Uid>, String> {
+		let properties: Vec<(Uid, Uid, T, String)> = properties.into_iter().collect();
+		let mut locks = BTreeMap::new();
+		for (being, ..) in properties.iter() {
+			if !locks.contains_key(being) {
+				locks.insert(being.clone(), self.being(being)?);
+			}
+		}
+		// lock the beings in id order
+		let mut guards: Vec<_> = locks.iter().map(|(id, b)| (id.clone(), b.write().unwrap())).collect();
+		let mut beings: BTreeMap<Uid, &mut Being> = guards.iter_mut().map(|(id, g)| (id.clone(), &mut **g)).collect();
+		let mut violations = Vec::new();
+		let schemas = self.schemas.read().unwrap();
+		let constraints = self.constraints.read().unwrap();
+		for (being, entity, value, name) in properties.iter() {
+			let b = &beings[being];
+			if let Some(e) = b.entities.iter().find(|e| e.id == *entity) {
+				constraints.validate(&b.name, &e.name, name, value)?;
+				violations.extend(schemas.get(&b.name).and_then(|s| s.check_value(&b.name, &e.name, name, value)));
+			}
+		}
+		drop((schemas, constraints));
+		self.enforce_schema(violations)?;
+		spawn::add_properties(&self.worlds.read().unwrap(), &mut beings, properties)
+	}
+
+	// develop being
+	// loop over ascended beings and recreate their entities on a random world
+	// add entities to being
+	pub async fn develop_being(&self, being: Uid, ascended_beings: Vec<AscendedBeing<T>>) -> Result<Vec<Uid>, String> {
+		self.develop_being_blocking(being, ascended_beings)
+	}
+
+	pub fn develop_being_blocking(&self, being: Uid, ascended_beings: Vec<AscendedBeing<T>>) -> Result<Vec<Uid>, String> {
+		let being = self.being(&being)?;
+		let mut entities = Vec::new();
+		for ascended_being in ascended_beings.iter() {
+			let world = self.random_world();
+			let worlds = self.worlds.read().unwrap();
+			entities.extend(being.write().unwrap().develop(&worlds, world, ascended_being)?);
+		}
+		Ok(entities)
+	}
+
+	// ascend being
+	// only takes read locks, so any number of threads can ascend at once
+	pub async fn ascend_being(&self, being: Uid) -> Result<Vec<AscendedBeing<T>>, String> {
+		self.ascend_being_blocking(being)
+	}
+
+	pub fn ascend_being_blocking(&self, being: Uid) -> Result<Vec<AscendedBeing<T>>, String> {
+		let being = self.being(&being)?;
+		let worlds = self.worlds.read().unwrap();
+		let res = being.read().unwrap().ascend(&worlds)?;
+		Ok(vec![res])
+	}
+
+	// call f on every component of the variant of t in every world, in parallel
+	pub fn par_for_each(&self, t: T, f: impl Fn(&Uid, &Component<T>) + Sync + Send) {
+		self.worlds.read().unwrap().par_iter().for_each(|(_, w)| w.par_for_each(t.clone(), &f));
+	}
+
+	// call f on every component of the variant of t in every world, in parallel, with write access
+	// every visited component is stamped as changed at the current tick of its world
+	pub fn par_for_each_mut(&self, t: T, f: impl Fn(&Uid, &mut Component<T>) + Sync + Send) {
+		self.worlds.read().unwrap().par_iter().for_each(|(_, w)| w.par_for_each_mut(t.clone(), &f));
+	}
+}
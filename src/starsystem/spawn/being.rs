@@ -0,0 +1,38 @@
+use super::{AscendedBeing, AscendedComponent, AscendedEntity, EntitySpec, PropertySpec, Uid};
+use serde::{Deserialize, Serialize};
+
+/// A being to spawn with `spawn_batch`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeingSpec<T> {
+	pub name: String,
+	pub entities: Vec<EntitySpec<T>>,
+}
+
+impl<T> BeingSpec<T> {
+	pub fn new(name: String) -> Self {
+		Self { name, entities: Vec::new() }
+	}
+
+	pub fn entity(mut self, entity: EntitySpec<T>) -> Self {
+		self.entities.push(entity);
+		self
+	}
+
+	// the spec of an ascended being under another name, to check a copy of it before anything is written
+	pub(crate) fn from_ascended(name: String, being: &AscendedBeing<T>) -> Self
+	where
+		T: Clone,
+	{
+		let entities = being.entities.iter().map(|e| EntitySpec { name: e.name.clone(), properties: e.components.iter().map(|c| PropertySpec { name: c.name.clone(), value: c.data.clone() }).collect() }).collect();
+		Self { name, entities }
+	}
+
+	// the being as it will look once spawned, under placeholder ids, to check it before anything is written
+	pub(crate) fn ascend(&self) -> AscendedBeing<T>
+	where
+		T: Clone,
+	{
+		let entities = self.entities.iter().map(|e| AscendedEntity { name: e.name.clone(), id: Uid::new(), components: e.properties.iter().map(|p| AscendedComponent { name: p.name.clone(), id: Uid::new(), data: p.value.clone(), computed: false }).collect() }).collect();
+		AscendedBeing { name: self.name.clone(), id: Uid::new(), entities }
+	}
+}
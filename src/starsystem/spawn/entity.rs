@@ -0,0 +1,20 @@
+use super::PropertySpec;
+use serde::{Deserialize, Serialize};
+
+/// An entity to spawn as part of a `BeingSpec`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySpec<T> {
+	pub name: String,
+	pub properties: Vec<PropertySpec<T>>,
+}
+
+impl<T> EntitySpec<T> {
+	pub fn new(name: String) -> Self {
+		Self { name, properties: Vec::new() }
+	}
+
+	pub fn property(mut self, name: String, value: T) -> Self {
+		self.properties.push(PropertySpec { name, value });
+		self
+	}
+}
@@ -0,0 +1,79 @@
+use super::{AscendedBeing, AscendedComponent, AscendedEntity, Being, EntityBatch, EnumIndex, StarEntity, StarEntityLocation, StarEntityProperty, Uid, World};
+pub use being::BeingSpec;
+pub use entity::EntitySpec;
+pub use property::PropertySpec;
+use serde::{Deserialize, Serialize};
+pub use spawned::{SpawnedBeing, SpawnedEntity};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use strum::IntoEnumIterator;
+
+mod being;
+mod entity;
+mod property;
+mod spawned;
+
+type ComponentBatch<T> = Vec<(Uid, Uid, T, String)>;
+
+// create the beings of the specs
+// every entity goes to a random world and each world is written to once
+// returns the beings to add to the starsystem and their ids in the order of the specs
+pub(crate) fn spawn<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(worlds: &BTreeMap<Uid, World<T>>, specs: Vec<BeingSpec<T>>) -> Result<(Vec<Being>, Vec<SpawnedBeing>), String> {
+	let world_ids: Vec<Uid> = worlds.keys().cloned().collect();
+	let mut batches: BTreeMap<Uid, EntityBatch<T>> = BTreeMap::new();
+	let mut beings = Vec::with_capacity(specs.len());
+	let mut spawned = Vec::with_capacity(specs.len());
+	for spec in specs {
+		let mut being = Being::new(spec.name);
+		let mut entities = Vec::with_capacity(spec.entities.len());
+		for e in spec.entities {
+			if world_ids.is_empty() {
+				return Err("No world to spawn entities on".to_string());
+			}
+			let world = world_ids[rand::random::<usize>() % world_ids.len()].clone();
+			let id = Uid::new();
+			let components: Vec<(Uid, T, String)> = e.properties.into_iter().map(|p| (Uid::new(), p.value, p.name)).collect();
+			let properties = components.iter().map(|c| StarEntityProperty { location: StarEntityLocation { world: world.clone(), entity: c.0.clone() }, id: c.0.clone(), name: c.2.clone() }).collect();
+			entities.push(SpawnedEntity { id: id.clone(), properties: components.iter().map(|c| c.0.clone()).collect() });
+			being.entities.push(StarEntity { location: StarEntityLocation { world: world.clone(), entity: id.clone() }, id: id.clone(), name: e.name.clone(), properties });
+			batches.entry(world).or_default().push((id, e.name, components));
+		}
+		spawned.push(SpawnedBeing { id: being.id.clone(), entities });
+		beings.push(being);
+	}
+
+	for (world, batch) in batches {
+		worlds.get(&world).unwrap().insert_entities_blocking(batch)?;
+	}
+	Ok((beings, spawned))
+}
+
+// add properties to existing entities
+// accepts (being, entity, value, property name)
+// every property is checked before anything is written, and each world is written to once
+// returns the ids of the properties in input order
+pub(crate) fn add_properties<T: 'static + Sync + Send + Serialize + for<'a> Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug>(worlds: &BTreeMap<Uid, World<T>>, beings: &mut BTreeMap<Uid, &mut Being>, properties: Vec<(Uid, Uid, T, String)>) -> Result<Vec<Uid>, String> {
+	let mut batches: BTreeMap<Uid, ComponentBatch<T>> = BTreeMap::new();
+	let mut placed = Vec::with_capacity(properties.len());
+	for (being, entity, value, name) in properties {
+		let b = beings.get(&being).ok_or(format!("Being with id {} does not exist", being))?;
+		let e = b.entities.iter().find(|e| e.id == entity).ok_or(format!("Entity with id {} does not exist on being {}", entity, being))?;
+		if !worlds.contains_key(&e.location.world) {
+			return Err(format!("World with id {} does not exist", e.location.world));
+		}
+		let id = Uid::new();
+		batches.entry(e.location.world.clone()).or_default().push((entity.clone(), id.clone(), value, name.clone()));
+		placed.push((being, entity, e.location.world.clone(), id, name));
+	}
+
+	for (world, batch) in batches {
+		worlds.get(&world).unwrap().add_components_blocking(batch)?;
+	}
+	let mut ids = Vec::with_capacity(placed.len());
+	for (being, entity, world, id, name) in placed {
+		let e = beings.get_mut(&being).unwrap().entities.iter_mut().find(|e| e.id == entity).unwrap();
+		e.properties.push(StarEntityProperty { location: StarEntityLocation { world, entity: id.clone() }, id: id.clone(), name });
+		ids.push(id);
+	}
+	Ok(ids)
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// A property to add as part of an `EntitySpec`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertySpec<T> {
+	pub name: String,
+	pub value: T,
+}
@@ -0,0 +1,16 @@
+use super::Uid;
+use serde::{Deserialize, Serialize};
+
+/// The ids of a spawned being, in the order of its spec.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnedBeing {
+	pub id: Uid,
+	pub entities: Vec<SpawnedEntity>,
+}
+
+/// The ids of a spawned entity, in the order of its spec.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnedEntity {
+	pub id: Uid,
+	pub properties: Vec<Uid>,
+}
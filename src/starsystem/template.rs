@@ -0,0 +1,69 @@
+use super::{AscendedBeing, BeingSpec, EntitySpec};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A blueprint of the entities and properties of a being.
+/// Define one in code, or convert an `AscendedBeing` loaded from JSON with `Template::from`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Template<T> {
+	pub name: String,
+	pub entities: Vec<EntitySpec<T>>,
+}
+
+// the entities and properties of an ascended being, its ids are dropped
+impl<T> From<AscendedBeing<T>> for Template<T> {
+	fn from(being: AscendedBeing<T>) -> Self {
+		let entities = being.entities.into_iter().map(|e| e.components.into_iter().fold(EntitySpec::new(e.name), |spec, c| spec.property(c.name, c.data))).collect();
+		Self { name: being.name, entities }
+	}
+}
+
+impl<T: Clone> Template<T> {
+	pub fn new(name: String) -> Self {
+		Self { name, entities: Vec::new() }
+	}
+
+	pub fn entity(mut self, entity: EntitySpec<T>) -> Self {
+		self.entities.push(entity);
+		self
+	}
+
+	// the value of a property of the template
+	pub fn value(&self, entity: &str, property: &str) -> Option<&T> {
+		self.entities.iter().find(|e| e.name == entity)?.properties.iter().find(|p| p.name == property).map(|p| &p.value)
+	}
+
+	// the spec of a being made from the template
+	// overrides replace the values of properties named by their path, like `server/port`
+	pub(crate) fn spec(&self, name: String, overrides: &BTreeMap<String, T>) -> Result<BeingSpec<T>, String> {
+		for path in overrides.keys() {
+			let (entity, property) = split_path(path)?;
+			if self.value(entity, property).is_none() {
+				return Err(format!("Override {} does not match a property of template {}", path, self.name));
+			}
+		}
+		let mut spec = BeingSpec::new(name);
+		for e in self.entities.iter() {
+			let mut entity = EntitySpec::new(e.name.clone());
+			for p in e.properties.iter() {
+				let value = overrides.get(&format!("{}/{}", e.name, p.name)).unwrap_or(&p.value);
+				entity = entity.property(p.name.clone(), value.clone());
+			}
+			spec = spec.entity(entity);
+		}
+		Ok(spec)
+	}
+}
+
+// split a path like `server/port` into its entity and property
+// the property keeps any further segments, like `http/tls/cert`
+pub(crate) fn split_path(path: &str) -> Result<(&str, &str), String> {
+	path.split_once('/').ok_or(format!("Path {} is not of the form entity/property", path))
+}
+
+/// The template a being was instantiated from, with the overrides it keeps when the template changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateLink<T> {
+	pub template: String,
+	pub overrides: BTreeMap<String, T>,
+}
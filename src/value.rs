@@ -0,0 +1,336 @@
+use super::EnumIndex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+/// A general purpose value that can be used as the component type of a starsystem.
+/// `StarSystem<StarValue>` works without defining an enum of your own.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum StarValue {
+	#[default]
+	Null,
+	Bool(bool),
+	Int(i64),
+	Float(f64),
+	String(String),
+	Bytes(Vec<u8>),
+	List(Vec<StarValue>),
+	Map(BTreeMap<String, StarValue>),
+}
+
+impl EnumIndex for StarValue {
+	fn index(&self) -> usize {
+		match self {
+			StarValue::Null => 0,
+			StarValue::Bool(_) => 1,
+			StarValue::Int(_) => 2,
+			StarValue::Float(_) => 3,
+			StarValue::String(_) => 4,
+			StarValue::Bytes(_) => 5,
+			StarValue::List(_) => 6,
+			StarValue::Map(_) => 7,
+		}
+	}
+}
+
+impl IntoEnumIterator for StarValue {
+	type Iterator = std::vec::IntoIter<StarValue>;
+
+	// one value of every variant, in the order of their index
+	fn iter() -> Self::Iterator {
+		vec![StarValue::Null, StarValue::Bool(false), StarValue::Int(0), StarValue::Float(0.0), StarValue::String(String::new()), StarValue::Bytes(Vec::new()), StarValue::List(Vec::new()), StarValue::Map(BTreeMap::new())].into_iter()
+	}
+}
+
+impl StarValue {
+	// the name of the variant, used in conversion errors
+	pub fn type_name(&self) -> &'static str {
+		match self {
+			StarValue::Null => "null",
+			StarValue::Bool(_) => "bool",
+			StarValue::Int(_) => "int",
+			StarValue::Float(_) => "float",
+			StarValue::String(_) => "string",
+			StarValue::Bytes(_) => "bytes",
+			StarValue::List(_) => "list",
+			StarValue::Map(_) => "map",
+		}
+	}
+
+	pub fn is_null(&self) -> bool {
+		matches!(self, StarValue::Null)
+	}
+
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			StarValue::Bool(b) => Some(*b),
+			_ => None,
+		}
+	}
+
+	pub fn as_int(&self) -> Option<i64> {
+		match self {
+			StarValue::Int(i) => Some(*i),
+			_ => None,
+		}
+	}
+
+	// ints are widened to floats
+	pub fn as_float(&self) -> Option<f64> {
+		match self {
+			StarValue::Float(f) => Some(*f),
+			StarValue::Int(i) => Some(*i as f64),
+			_ => None,
+		}
+	}
+
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			StarValue::String(s) => Some(s),
+			_ => None,
+		}
+	}
+
+	pub fn as_bytes(&self) -> Option<&[u8]> {
+		match self {
+			StarValue::Bytes(b) => Some(b),
+			_ => None,
+		}
+	}
+
+	pub fn as_list(&self) -> Option<&Vec<StarValue>> {
+		match self {
+			StarValue::List(l) => Some(l),
+			_ => None,
+		}
+	}
+
+	pub fn as_map(&self) -> Option<&BTreeMap<String, StarValue>> {
+		match self {
+			StarValue::Map(m) => Some(m),
+			_ => None,
+		}
+	}
+}
+
+/*
+		Conversions into StarValue
+*/
+
+macro_rules! from_int {
+	($($t:ty),*) => {
+		$(
+			impl From<$t> for StarValue {
+				fn from(value: $t) -> Self {
+					StarValue::Int(value as i64)
+				}
+			}
+		)*
+	};
+}
+
+from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<()> for StarValue {
+	fn from(_: ()) -> Self {
+		StarValue::Null
+	}
+}
+
+impl From<bool> for StarValue {
+	fn from(value: bool) -> Self {
+		StarValue::Bool(value)
+	}
+}
+
+impl From<f32> for StarValue {
+	fn from(value: f32) -> Self {
+		StarValue::Float(value as f64)
+	}
+}
+
+impl From<f64> for StarValue {
+	fn from(value: f64) -> Self {
+		StarValue::Float(value)
+	}
+}
+
+impl From<String> for StarValue {
+	fn from(value: String) -> Self {
+		StarValue::String(value)
+	}
+}
+
+impl From<&str> for StarValue {
+	fn from(value: &str) -> Self {
+		StarValue::String(value.to_string())
+	}
+}
+
+impl From<Vec<u8>> for StarValue {
+	fn from(value: Vec<u8>) -> Self {
+		StarValue::Bytes(value)
+	}
+}
+
+impl From<&[u8]> for StarValue {
+	fn from(value: &[u8]) -> Self {
+		StarValue::Bytes(value.to_vec())
+	}
+}
+
+impl From<Vec<StarValue>> for StarValue {
+	fn from(value: Vec<StarValue>) -> Self {
+		StarValue::List(value)
+	}
+}
+
+impl From<BTreeMap<String, StarValue>> for StarValue {
+	fn from(value: BTreeMap<String, StarValue>) -> Self {
+		StarValue::Map(value)
+	}
+}
+
+impl From<HashMap<String, StarValue>> for StarValue {
+	fn from(value: HashMap<String, StarValue>) -> Self {
+		StarValue::Map(value.into_iter().collect())
+	}
+}
+
+// none becomes null
+impl<V: Into<StarValue>> From<Option<V>> for StarValue {
+	fn from(value: Option<V>) -> Self {
+		value.map(Into::into).unwrap_or(StarValue::Null)
+	}
+}
+
+impl TryFrom<u64> for StarValue {
+	type Error = String;
+
+	fn try_from(value: u64) -> Result<Self, Self::Error> {
+		i64::try_from(value).map(StarValue::Int).map_err(|_| format!("{} does not fit in an int", value))
+	}
+}
+
+impl TryFrom<usize> for StarValue {
+	type Error = String;
+
+	fn try_from(value: usize) -> Result<Self, Self::Error> {
+		i64::try_from(value).map(StarValue::Int).map_err(|_| format!("{} does not fit in an int", value))
+	}
+}
+
+// parse text like an environment variable
+// booleans, ints and floats are recognized, anything else stays a string
+impl FromStr for StarValue {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if let Ok(b) = s.parse::<bool>() {
+			return Ok(StarValue::Bool(b));
+		}
+		if let Ok(i) = s.parse::<i64>() {
+			return Ok(StarValue::Int(i));
+		}
+		match s.parse::<f64>() {
+			Ok(f) if f.is_finite() => Ok(StarValue::Float(f)),
+			_ => Ok(StarValue::String(s.to_string())),
+		}
+	}
+}
+
+/*
+		Conversions out of StarValue
+*/
+
+fn mismatch(expected: &str, value: &StarValue) -> String {
+	format!("expected {}, found {}", expected, value.type_name())
+}
+
+macro_rules! try_into_int {
+	($($t:ty),*) => {
+		$(
+			impl TryFrom<StarValue> for $t {
+				type Error = String;
+
+				fn try_from(value: StarValue) -> Result<Self, Self::Error> {
+					match value {
+						StarValue::Int(i) => <$t>::try_from(i).map_err(|_| format!("{} does not fit in {}", i, stringify!($t))),
+						other => Err(mismatch("int", &other)),
+					}
+				}
+			}
+		)*
+	};
+}
+
+try_into_int!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl TryFrom<StarValue> for bool {
+	type Error = String;
+
+	fn try_from(value: StarValue) -> Result<Self, Self::Error> {
+		value.as_bool().ok_or_else(|| mismatch("bool", &value))
+	}
+}
+
+impl TryFrom<StarValue> for f64 {
+	type Error = String;
+
+	fn try_from(value: StarValue) -> Result<Self, Self::Error> {
+		value.as_float().ok_or_else(|| mismatch("float", &value))
+	}
+}
+
+impl TryFrom<StarValue> for f32 {
+	type Error = String;
+
+	fn try_from(value: StarValue) -> Result<Self, Self::Error> {
+		value.as_float().map(|f| f as f32).ok_or_else(|| mismatch("float", &value))
+	}
+}
+
+impl TryFrom<StarValue> for String {
+	type Error = String;
+
+	fn try_from(value: StarValue) -> Result<Self, Self::Error> {
+		match value {
+			StarValue::String(s) => Ok(s),
+			other => Err(mismatch("string", &other)),
+		}
+	}
+}
+
+impl TryFrom<StarValue> for Vec<u8> {
+	type Error = String;
+
+	fn try_from(value: StarValue) -> Result<Self, Self::Error> {
+		match value {
+			StarValue::Bytes(b) => Ok(b),
+			other => Err(mismatch("bytes", &other)),
+		}
+	}
+}
+
+impl TryFrom<StarValue> for Vec<StarValue> {
+	type Error = String;
+
+	fn try_from(value: StarValue) -> Result<Self, Self::Error> {
+		match value {
+			StarValue::List(l) => Ok(l),
+			other => Err(mismatch("list", &other)),
+		}
+	}
+}
+
+impl TryFrom<StarValue> for BTreeMap<String, StarValue> {
+	type Error = String;
+
+	fn try_from(value: StarValue) -> Result<Self, Self::Error> {
+		match value {
+			StarValue::Map(m) => Ok(m),
+			other => Err(mismatch("map", &other)),
+		}
+	}
+}
@@ -4,4 +4,10 @@ use serde::{Deserialize, Serialize};
 pub struct Component<T> {
 	pub name: String,
 	pub data: T,
+	// the tick the component was added at
+	#[serde(default)]
+	pub added: u64,
+	// the tick the data of the component last changed at
+	#[serde(default)]
+	pub changed: u64,
 }
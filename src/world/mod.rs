@@ -1,187 +1,671 @@
 use super::Uid;
-use component::Component;
-use entity::Entity;
+pub use component::Component;
+pub use entity::Entity;
+pub use removal::Removal;
+pub use storage::{Column, Columns, Handle, StorageKind};
+use storage::{ComponentsRead, ComponentsWrite, Storage};
 use rayon::prelude::*;
-use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::fmt::Debug;
 use std::sync::Arc;
-use std::sync::Mutex;
+use std::sync::RwLock;
 use strum::IntoEnumIterator;
 pub use enum_index::EnumIndex;
 
 mod component;
 mod entity;
 mod enum_index;
+mod removal;
+mod storage;
+
+/// The number of removals a world keeps by default.
+pub const REMOVAL_LIMIT: usize = 4096;
 
 /// A collection of components of a given type.
 pub type CompMap<T> = BTreeMap<Uid, Component<T>>;
 
-/// 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Entities to insert at once as (entity id, entity name, [(component id, data, component name)]).
+pub type EntityBatch<T> = Vec<(Uid, String, Vec<(Uid, T, String)>)>;
+
+/// A world holds the entities and components of a starsystem.
+/// Every collection is behind its own lock so a world can be shared and read concurrently.
+/// Locks are always taken in the order entities, components, dense columns.
+#[derive(Debug, Clone)]
 pub struct World<T> {
 	pub id: Uid,
-	pub indexes: Arc<Mutex<Vec<usize>>>,
-	pub entities_count: Arc<Mutex<usize>>,
-	pub entities: Arc<Mutex<BTreeMap<Uid, Entity>>>,
-	pub components: Arc<Mutex<BTreeMap<usize, CompMap<T>>>>,
+	pub indexes: Arc<RwLock<Vec<usize>>>,
+	pub entities_count: Arc<RwLock<usize>>,
+	pub entities: Arc<RwLock<BTreeMap<Uid, Entity>>>,
+	// the components of a world with sparse storage
+	// a world with dense storage keeps its components in columns and leaves these empty, so they are only read through `components()`
+	components: Arc<RwLock<BTreeMap<usize, CompMap<T>>>>,
+	// the tick new and changed components are stamped with
+	pub tick: Arc<RwLock<u64>>,
+	// components removed from the world, oldest first
+	// only the latest removals up to the limit are kept and they are not serialized
+	pub removals: Arc<RwLock<VecDeque<Removal<T>>>>,
+	pub removal_limit: Arc<RwLock<usize>>,
+	// the dense columns of a world with dense storage, none for sparse storage
+	dense: Arc<RwLock<Option<Columns<T>>>>,
+}
+
+// components are written as the map of components per variant worlds always had, whatever the storage
+impl<T: Serialize> Serialize for World<T> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("World", 7)?;
+		state.serialize_field("id", &self.id)?;
+		state.serialize_field("indexes", &self.indexes)?;
+		state.serialize_field("entities_count", &self.entities_count)?;
+		state.serialize_field("entities", &self.entities)?;
+		let components = self.components.read().unwrap();
+		let dense = self.dense.read().unwrap();
+		match &*dense {
+			Some(columns) => state.serialize_field("components", columns)?,
+			None => state.serialize_field("components", &*components)?,
+		}
+		let kind = if dense.is_some() { StorageKind::Dense } else { StorageKind::Sparse };
+		drop(dense);
+		drop(components);
+		state.serialize_field("tick", &self.tick)?;
+		state.serialize_field("storage", &kind)?;
+		state.end()
+	}
+}
+
+// a world as it is serialized, components are always read as sparse storage
+#[derive(Deserialize)]
+struct WorldData<T> {
+	id: Uid,
+	indexes: Arc<RwLock<Vec<usize>>>,
+	entities_count: Arc<RwLock<usize>>,
+	entities: Arc<RwLock<BTreeMap<Uid, Entity>>>,
+	components: BTreeMap<usize, CompMap<T>>,
+	#[serde(default)]
+	tick: Arc<RwLock<u64>>,
+	#[serde(default)]
+	storage: StorageKind,
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for World<T> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let mut data = WorldData::<T>::deserialize(deserializer)?;
+		let dense = match data.storage {
+			StorageKind::Sparse => None,
+			StorageKind::Dense => Some(Columns::from_map(&mut data.components)),
+		};
+		Ok(Self { id: data.id, indexes: data.indexes, entities_count: data.entities_count, entities: data.entities, components: Arc::new(RwLock::new(data.components)), tick: data.tick, removals: Arc::new(RwLock::new(VecDeque::new())), removal_limit: Arc::new(RwLock::new(REMOVAL_LIMIT)), dense: Arc::new(RwLock::new(dense)) })
+	}
 }
 
 impl<'a, T: 'static + Sync + Send + Serialize + Deserialize<'a> + IntoEnumIterator + PartialEq + EnumIndex + Clone + Default + Debug> World<T> {
 	// creates a new world
 	pub async fn new() -> Self {
-		let entities_count = Arc::new(Mutex::new(0));
-		let entities = Arc::new(Mutex::new(BTreeMap::new()));
-		let components = Arc::new(Mutex::new(BTreeMap::new()));
-		let indexes = Arc::new(Mutex::new(Vec::new()));
-		for (i, _) in &mut T::iter().enumerate() {
-			indexes.lock().unwrap().push(i);
-			components.lock().unwrap().insert(i, BTreeMap::new());
+		Self::new_blocking()
+	}
+
+	pub fn new_blocking() -> Self {
+		Self::with_storage_blocking(StorageKind::Sparse)
+	}
+
+	// creates a new world that keeps its components in the given kind of storage
+	pub fn with_storage_blocking(kind: StorageKind) -> Self {
+		let entities_count = Arc::new(RwLock::new(0));
+		let entities = Arc::new(RwLock::new(BTreeMap::new()));
+		let indexes: Vec<usize> = T::iter().enumerate().map(|(i, _)| i).collect();
+		let components = Arc::new(RwLock::new(indexes.iter().map(|i| (*i, BTreeMap::new())).collect()));
+		let dense = match kind {
+			StorageKind::Sparse => None,
+			StorageKind::Dense => Some(Columns::new(indexes.iter().cloned())),
+		};
+		Self { id: Uid::new(), indexes: Arc::new(RwLock::new(indexes)), entities_count, entities, components, tick: Arc::new(RwLock::new(0)), removals: Arc::new(RwLock::new(VecDeque::new())), removal_limit: Arc::new(RwLock::new(REMOVAL_LIMIT)), dense: Arc::new(RwLock::new(dense)) }
+	}
+
+	// lock the components for reading, wherever they are kept
+	fn read_components(&self) -> ComponentsRead<'_, T> {
+		ComponentsRead { sparse: self.components.read().unwrap(), dense: self.dense.read().unwrap() }
+	}
+
+	// lock the components for writing, wherever they are kept
+	fn write_components(&self) -> ComponentsWrite<'_, T> {
+		ComponentsWrite { sparse: self.components.write().unwrap(), dense: self.dense.write().unwrap() }
+	}
+
+	pub fn storage_kind(&self) -> StorageKind {
+		match *self.dense.read().unwrap() {
+			Some(_) => StorageKind::Dense,
+			None => StorageKind::Sparse,
 		}
-		Self { id: Uid::new(), indexes, entities_count, entities, components }
+	}
+
+	// move the components to the given kind of storage
+	pub fn set_storage(&self, kind: StorageKind) {
+		let mut components = self.write_components();
+		let ComponentsWrite { sparse, dense } = &mut components;
+		match (kind, dense.take()) {
+			(StorageKind::Dense, None) => **dense = Some(Columns::from_map(sparse)),
+			(StorageKind::Sparse, Some(columns)) => columns.into_map(sparse),
+			(_, columns) => **dense = columns,
+		}
+	}
+
+	// a copy of every component by the index of its variant, whatever the storage
+	pub fn components(&self) -> BTreeMap<usize, CompMap<T>> {
+		let indexes = self.indexes.read().unwrap().clone();
+		let locked = self.read_components();
+		let components = locked.storage();
+		indexes.into_iter().map(|i| (i, components.column(i))).collect()
+	}
+
+	// the handle of a component, if the world uses dense storage
+	pub fn handle(&self, component_id: Uid) -> Option<Handle> {
+		self.read_components().storage().handle(&component_id)
+	}
+
+	// the tick new and changed components are stamped with
+	pub fn current_tick(&self) -> u64 {
+		*self.tick.read().unwrap()
+	}
+
+	pub fn set_tick(&self, tick: u64) {
+		*self.tick.write().unwrap() = tick;
 	}
 
 	// has component
 	pub fn has_component(&self, component_id: Uid) -> bool {
-		self.components.lock().unwrap().iter().any(|(_, comps)| comps.contains_key(&component_id))
+		self.read_components().storage().contains(&component_id)
 	}
 
 	// has entity
 	pub fn has_entity(&self, entity_id: Uid) -> bool {
-		self.entities.lock().unwrap().contains_key(&entity_id)
+		self.entities.read().unwrap().contains_key(&entity_id)
 	}
 
 	// creates a new entity in the world
 	// adds 1 to the entities_count
 	// adds a new entity to the entities vec
-	pub async fn create_entity(&mut self, name: String) -> Result<Uid, String> {
-		let id = Uid::new();
+	pub async fn create_entity(&self, name: String) -> Result<Uid, String> {
+		self.create_entity_blocking(name)
+	}
+
+	pub fn create_entity_blocking(&self, name: String) -> Result<Uid, String> {
+		self.insert_entity_blocking(Uid::new(), name)
+	}
+
+	// insert entity
+	// creates a new entity with a given id
+	// adds 1 to the entities_count
+	pub fn insert_entity_blocking(&self, id: Uid, name: String) -> Result<Uid, String> {
 		let location = Vec::new();
 		let entity = Entity { location, name };
-		self.entities.lock().unwrap().insert(id.clone(), entity);
-		*self.entities_count.lock().unwrap() += 1;
+		self.entities.write().unwrap().insert(id.clone(), entity);
+		*self.entities_count.write().unwrap() += 1;
 		Ok(id)
 	}
 
+	// insert many entities with their components at once
+	// the entities and components are locked once for the whole batch
+	pub fn insert_entities_blocking(&self, entities: EntityBatch<T>) -> Result<Vec<Uid>, String> {
+		let tick = self.current_tick();
+		let count = entities.len();
+		let mut ids = Vec::with_capacity(count);
+		let mut ents = self.entities.write().unwrap();
+		let mut locked = self.write_components();
+		let mut components = locked.storage();
+		for (id, name, comps) in entities {
+			let mut location = Vec::with_capacity(comps.len());
+			for (component_id, data, component_name) in comps {
+				let index = data.index();
+				components.insert(index, component_id.clone(), Component { name: component_name, data, added: tick, changed: tick });
+				location.push((index, component_id));
+			}
+			ents.insert(id.clone(), Entity { location, name });
+			ids.push(id);
+		}
+		*self.entities_count.write().unwrap() += count;
+		Ok(ids)
+	}
+
 	// set entity
 	// create a new entity from provided id and name
 	// if the entity already exists, it will be overwritten
-	pub async fn set_entity(&mut self, id: Uid, name: String) -> Result<Uid, String> {
+	pub async fn set_entity(&self, id: Uid, name: String) -> Result<Uid, String> {
+		self.set_entity_blocking(id, name)
+	}
+
+	pub fn set_entity_blocking(&self, id: Uid, name: String) -> Result<Uid, String> {
 		let location = Vec::new();
 		let entity = Entity { location, name };
-		self.entities.lock().unwrap().insert(id.clone(), entity);
+		self.entities.write().unwrap().insert(id.clone(), entity);
 		Ok(id)
 	}
 
 	// add component to entity
 	// adds a component to the component vec where the index is the index of the component in the enum
 	// adds the location of the component to the entity
-	pub async fn add_component_to_entity(&mut self, entity: Uid, component: T, component_name: String) -> Result<Uid, String> {
+	pub async fn add_component_to_entity(&self, entity: Uid, component: T, component_name: String) -> Result<Uid, String> {
+		self.add_component_to_entity_blocking(entity, component, component_name)
+	}
+
+	pub fn add_component_to_entity_blocking(&self, entity: Uid, component: T, component_name: String) -> Result<Uid, String> {
 		let index = T::index(&component);
 		let id = Uid::new();
-		let comp: Component<T> = Component { name: component_name, data: component };
-		self.components.lock().unwrap().get_mut(&index).unwrap().insert(id.clone(), comp);
-		self.entities.lock().unwrap().par_iter_mut().find_any(|e| *e.0 == entity).unwrap().1.location.push((index, id.clone()));
+		let tick = self.current_tick();
+		let comp: Component<T> = Component { name: component_name, data: component, added: tick, changed: tick };
+		self.write_components().storage().insert(index, id.clone(), comp);
+		self.entities.write().unwrap().get_mut(&entity).unwrap().location.push((index, id.clone()));
 		Ok(id)
 	}
 
+	// add many components to entities at once
+	// accepts (entity id, component id, data, component name)
+	// fails without adding anything if one of the entities does not exist
+	pub fn add_components_blocking(&self, components: Vec<(Uid, Uid, T, String)>) -> Result<Vec<Uid>, String> {
+		let tick = self.current_tick();
+		let mut ents = self.entities.write().unwrap();
+		if let Some(missing) = components.iter().find(|c| !ents.contains_key(&c.0)) {
+			return Err(format!("entity: {} not found", missing.0));
+		}
+		let mut locked = self.write_components();
+		let mut comps = locked.storage();
+		let mut ids = Vec::with_capacity(components.len());
+		for (entity, id, data, name) in components {
+			let index = data.index();
+			comps.insert(index, id.clone(), Component { name, data, added: tick, changed: tick });
+			ents.get_mut(&entity).unwrap().location.push((index, id.clone()));
+			ids.push(id);
+		}
+		Ok(ids)
+	}
+
 	// set component to entity
 	// adds a component to the component vec where the index is the index of the component in the enum
 	// adds the location of the component to the entity
-	pub async fn set_component_to_entity(&mut self, entity: Uid, component: T, component_name: String, component_id: Uid) -> Result<Uid, String> {
+	// if the component already exists, it is replaced and keeps the tick it was added at
+	pub async fn set_component_to_entity(&self, entity: Uid, component: T, component_name: String, component_id: Uid) -> Result<Uid, String> {
+		self.set_component_to_entity_blocking(entity, component, component_name, component_id)
+	}
+
+	pub fn set_component_to_entity_blocking(&self, entity: Uid, component: T, component_name: String, component_id: Uid) -> Result<Uid, String> {
 		let index = T::index(&component);
-		let comp: Component<T> = Component { name: component_name, data: component };
-		self.components.lock().unwrap().get_mut(&index).unwrap().insert(component_id.clone(), comp);
-		self.entities.lock().unwrap().par_iter_mut().find_any(|e| *e.0 == entity).unwrap().1.location.push((index, component_id.clone()));
+		let tick = self.current_tick();
+		let mut entities = self.entities.write().unwrap();
+		let location = &mut entities.get_mut(&entity).ok_or(format!("entity: {} not found", entity))?.location;
+		let mut locked = self.write_components();
+		let mut components = locked.storage();
+
+		match components.as_ref().index_of(&component_id) {
+			// the variant is unchanged, update in place so a dense handle stays valid
+			Some(i) if i == index => {
+				let comp = components.get_mut(index, &component_id).unwrap();
+				comp.name = component_name;
+				comp.data = component;
+				comp.changed = tick;
+			}
+			// the component may exist under another variant
+			existing => {
+				let added = existing.and_then(|i| components.remove(i, &component_id)).map(|c| c.added).unwrap_or(tick);
+				let comp: Component<T> = Component { name: component_name, data: component, added, changed: tick };
+				components.insert(index, component_id.clone(), comp);
+			}
+		}
+
+		location.retain(|c| c.1 != component_id);
+		location.push((index, component_id.clone()));
 		Ok(component_id)
 	}
 
 	// removes a component from an entity
 	// removes the component from the component vec where the index is the index of the component in the enum
 	// removes the location of the component from the entity
-	pub async fn remove_component_from_entity(&mut self, entity: Uid, component: Uid) -> Result<(), String> {
-		/* let ent: Entity = self
-		.entities
-		.lock()
-		.unwrap()
-		.par_iter()
-		.find_any(|e| *e.0 == entity)
-		.unwrap()
-		.1
-		.clone(); */
-		let mut index = 0;
-		let mut component_name = Uid::new();
-		let components = self.components.lock().unwrap().clone();
-		for (i, c) in components.iter() {
-			if c.par_iter().find_any(|c| *c.0 == component).is_some() {
-				index = *i;
-				component_name = c.par_iter().find_any(|c| *c.0 == component).unwrap().0.clone();
-				break;
-			}
+	pub async fn remove_component_from_entity(&self, entity: Uid, component: Uid) -> Result<(), String> {
+		self.remove_component_from_entity_blocking(entity, component)
+	}
+
+	pub fn remove_component_from_entity_blocking(&self, entity: Uid, component: Uid) -> Result<(), String> {
+		let removed = self.write_components().storage().remove_any(&component);
+		if let Some((_, removed)) = removed {
+			self.record_removal(entity.clone(), component.clone(), removed);
 		}
-		self.components.lock().unwrap().get_mut(&index).unwrap().remove(&component_name);
 
 		// remove the component from the entity locations
-		self.entities.lock().unwrap().par_iter_mut().find_any(|e| *e.0 == entity).unwrap().1.location.retain(|c| c.1 != component);
-		assert!(self.entities.lock().unwrap().par_iter().find_any(|e| *e.0 == entity).unwrap().1.location.par_iter().find_any(|c| c.1 == component_name).is_none());
+		self.entities.write().unwrap().get_mut(&entity).unwrap().location.retain(|c| c.1 != component);
+		assert!(self.entities.read().unwrap().get(&entity).unwrap().location.iter().all(|c| c.1 != component));
+		Ok(())
+	}
+
+	// rename an entity
+	pub async fn rename_entity(&self, entity: Uid, name: String) -> Result<(), String> {
+		self.rename_entity_blocking(entity, name)
+	}
+
+	pub fn rename_entity_blocking(&self, entity: Uid, name: String) -> Result<(), String> {
+		match self.entities.write().unwrap().get_mut(&entity) {
+			Some(e) => {
+				e.name = name;
+				Ok(())
+			}
+			None => Err(format!("entity: {} not found", entity)),
+		}
+	}
+
+	// rename a component, its data and ticks stay as they are
+	pub async fn rename_component(&self, component: Uid, name: String) -> Result<(), String> {
+		self.rename_component_blocking(component, name)
+	}
+
+	pub fn rename_component_blocking(&self, component: Uid, name: String) -> Result<(), String> {
+		let mut locked = self.write_components();
+		let mut components = locked.storage();
+		let index = components.as_ref().index_of(&component).ok_or(format!("component: {} not found", component))?;
+		components.get_mut(index, &component).unwrap().name = name;
+		Ok(())
+	}
+
+	// move a component to another entity of the world
+	pub async fn move_component(&self, component: Uid, from: Uid, to: Uid) -> Result<(), String> {
+		self.move_component_blocking(component, from, to)
+	}
+
+	pub fn move_component_blocking(&self, component: Uid, from: Uid, to: Uid) -> Result<(), String> {
+		let mut entities = self.entities.write().unwrap();
+		if !entities.contains_key(&to) {
+			return Err(format!("entity: {} not found", to));
+		}
+		let source = entities.get_mut(&from).ok_or(format!("entity: {} not found", from))?;
+		let position = source.location.iter().position(|c| c.1 == component).ok_or(format!("component: {} not found on entity {}", component, from))?;
+		let location = source.location.remove(position);
+		entities.get_mut(&to).unwrap().location.push(location);
+		Ok(())
+	}
+
+	// take a component out of an entity without recording a removal, to put it into another world
+	pub fn take_component_blocking(&self, entity: Uid, component: Uid) -> Result<Component<T>, String> {
+		let mut entities = self.entities.write().unwrap();
+		let e = entities.get_mut(&entity).ok_or(format!("entity: {} not found", entity))?;
+		if !e.location.iter().any(|c| c.1 == component) {
+			return Err(format!("component: {} not found on entity {}", component, entity));
+		}
+		e.location.retain(|c| c.1 != component);
+		let taken = self.write_components().storage().remove_any(&component);
+		taken.map(|c| c.1).ok_or(format!("component: {} not found", component))
+	}
+
+	// put a component taken from another world into an entity, keeping its id and ticks
+	pub fn put_component_blocking(&self, entity: Uid, component: Uid, data: Component<T>) -> Result<(), String> {
+		let mut entities = self.entities.write().unwrap();
+		let e = entities.get_mut(&entity).ok_or(format!("entity: {} not found", entity))?;
+		let index = data.data.index();
+		self.write_components().storage().insert(index, component.clone(), data);
+		e.location.push((index, component));
 		Ok(())
 	}
 
 	// return component for givien Uid
 	// returns a vec of components for given type
 	pub async fn get_components_of_type(&self, t: T) -> Result<BTreeMap<Uid, Component<T>>, String> {
+		self.get_components_of_type_blocking(t)
+	}
+
+	pub fn get_components_of_type_blocking(&self, t: T) -> Result<BTreeMap<Uid, Component<T>>, String> {
 		let index = T::index(&t);
-		Ok(self.components.lock().unwrap().get(&index).unwrap().clone())
+		Ok(self.read_components().storage().column(index))
 	}
 
 	// replace a vec of components with a given vec<T>
-	pub async fn set_components(&mut self, components: BTreeMap<Uid, Component<T>>) -> Result<BTreeMap<Uid, Component<T>>, String> {
-		let index = components.iter().next().unwrap().1.data.index();
-		self.components.lock().unwrap().insert(index, components.clone());
+	// the columns of the variants in the map are replaced under one lock, components left out of them are removed from their entities
+	pub async fn set_components(&self, components: BTreeMap<Uid, Component<T>>) -> Result<BTreeMap<Uid, Component<T>>, String> {
+		self.set_components_blocking(components)
+	}
+
+	pub fn set_components_blocking(&self, components: BTreeMap<Uid, Component<T>>) -> Result<BTreeMap<Uid, Component<T>>, String> {
+		let tick = self.current_tick();
+		let mut components = components;
+		// every component goes to the column of its own variant, and only those columns are replaced
+		let indexes: BTreeSet<usize> = components.values().map(|c| c.data.index()).collect();
+		let mut entities = self.entities.write().unwrap();
+		let mut locked = self.write_components();
+		let mut storage = locked.storage();
+		let mut previous = BTreeMap::new();
+		for index in indexes.iter() {
+			previous.extend(storage.replace_column(*index, BTreeMap::new()));
+		}
+		// a component that was of another variant moves out of its old column
+		for id in components.keys() {
+			if !previous.contains_key(id) {
+				if let Some((_, p)) = storage.remove_any(id) {
+					previous.insert(id.clone(), p);
+				}
+			}
+		}
+
+		// keep the ticks of untouched components and stamp the rest
+		for (id, c) in components.iter_mut() {
+			match previous.get(id) {
+				Some(p) if p.data == c.data => {
+					c.added = p.added;
+					c.changed = p.changed;
+				}
+				Some(p) => {
+					c.added = p.added;
+					c.changed = tick;
+				}
+				None => {
+					c.added = tick;
+					c.changed = tick;
+				}
+			}
+		}
+		for (id, c) in components.iter() {
+			storage.insert(c.data.index(), id.clone(), c.clone());
+		}
+
+		// the components left out are removed from their entities, the others point to the column of their variant
+		for (entity, e) in entities.iter_mut() {
+			for (index, id) in std::mem::take(&mut e.location) {
+				match (components.get(&id), previous.remove(&id)) {
+					(Some(c), _) => e.location.push((c.data.index(), id)),
+					(None, Some(p)) => self.record_removal(entity.clone(), id, p),
+					(None, None) => e.location.push((index, id)),
+				}
+			}
+		}
 		Ok(components)
 	}
 
+	// get a component by id
+	pub async fn get_component(&self, component: Uid) -> Result<Component<T>, String> {
+		self.get_component_blocking(component)
+	}
+
+	pub fn get_component_blocking(&self, component: Uid) -> Result<Component<T>, String> {
+		let locked = self.read_components();
+		let components = locked.storage();
+		components.index_of(&component).and_then(|i| components.get(i, &component)).cloned().ok_or(format!("component: {} not found", component))
+	}
+
 	// set a component for a given component Uid
-	pub async fn set_component(&mut self, component: Uid, data: T) -> Result<Uid, String> {
-		let components = self.components.lock().unwrap().clone();
-		let mut index = 0;
-		for (i, c) in components.iter() {
-			if c.par_iter().find_any(|c| *c.0 == component).is_some() {
-				index = *i;
-				break;
-			}
+	pub async fn set_component(&self, component: Uid, data: T) -> Result<Uid, String> {
+		self.set_component_blocking(component, data)
+	}
+
+	pub fn set_component_blocking(&self, component: Uid, data: T) -> Result<Uid, String> {
+		let entity = self.entities.read().unwrap().iter().find(|e| e.1.location.iter().any(|l| l.1 == component)).map(|e| e.0.clone());
+		match entity {
+			Some(entity) => self.update_components_blocking(vec![(entity, component.clone(), data)], self.current_tick()).map(|_| component),
+			None => Err(format!("component: {} not found", component)),
 		}
-		let mut comp = self.components.lock().unwrap().get_mut(&index).unwrap().get_mut(&component).unwrap().clone();
-		comp.data = data;
-		self.components.lock().unwrap().get_mut(&index).unwrap().insert(component.clone(), comp);
-		Ok(component)
 	}
 
 	// removes an entity from the world
 	// removes the entity from the entities vec
 	// removes the components from the components vec
 	// removes 1 from the entities_count
-	pub async fn remove_entity(&mut self, entity: Uid) -> Result<(), String> {
-		let ent: Entity = if let Some(e) = self.entities.lock().unwrap().par_iter().find_any(|e| *e.0 == entity) {
-			e.1.clone()
+	pub async fn remove_entity(&self, entity: Uid) -> Result<(), String> {
+		self.remove_entity_blocking(entity)
+	}
+
+	pub fn remove_entity_blocking(&self, entity: Uid) -> Result<(), String> {
+		let ent: Entity = if let Some(e) = self.entities.read().unwrap().get(&entity) {
+			e.clone()
 		} else {
 			return Err(format!("entity: {} not found", entity));
 		};
 		for (index, component) in ent.location {
-			self.components.lock().unwrap().get_mut(&index).unwrap().remove(&component);
+			let removed = self.write_components().storage().remove(index, &component);
+			if let Some(removed) = removed {
+				self.record_removal(entity.clone(), component, removed);
+			}
 		}
-		self.entities.lock().unwrap().retain(|i, _| *i != entity);
-		*self.entities_count.lock().unwrap() -= 1;
+		self.entities.write().unwrap().retain(|i, _| *i != entity);
+		*self.entities_count.write().unwrap() -= 1;
 		Ok(())
 	}
 
 	pub async fn get_entity_components(&self, entity: Uid) -> Result<Vec<(Uid, Component<T>)>, String> {
-		let ent: Entity = self.entities.lock().unwrap().par_iter().find_any(|e| *e.0 == entity).unwrap().1.clone();
-		let mut components = Vec::new();
+		self.get_entity_components_blocking(entity)
+	}
+
+	pub fn get_entity_components_blocking(&self, entity: Uid) -> Result<Vec<(Uid, Component<T>)>, String> {
+		let ent: Entity = self.entities.read().unwrap().get(&entity).ok_or(format!("entity: {} not found", entity))?.clone();
+		let locked = self.read_components();
+		let components = locked.storage();
+		let mut res = Vec::new();
 		for (index, component) in ent.location {
-			let comp = self.components.lock().unwrap().get(&index).unwrap().get(&component).unwrap().clone();
-			components.push((component, comp));
+			let comp = components.get(index, &component).ok_or(format!("component: {} not found", component))?.clone();
+			res.push((component, comp));
 		}
-		Ok(components)
+		Ok(res)
+	}
+
+	// call f on every component of the variant of t, in parallel
+	pub fn par_for_each(&self, t: T, f: impl Fn(&Uid, &Component<T>) + Sync + Send) {
+		let index = T::index(&t);
+		self.read_components().storage().par_for_each(index, f);
+	}
+
+	// call f on every component of the variant of t, in parallel, with write access
+	// every visited component is stamped as changed at the current tick of its world
+	// components whose data is set to another variant are moved to their new index
+	// the entities are locked before the components, as everywhere else, since moved components change their location
+	pub fn par_for_each_mut(&self, t: T, f: impl Fn(&Uid, &mut Component<T>) + Sync + Send) {
+		let index = T::index(&t);
+		let tick = self.current_tick();
+		let mut entities = self.entities.write().unwrap();
+		let mut locked = self.write_components();
+		let mut components = locked.storage();
+		components.par_for_each_mut(index, |id, c| {
+			f(id, c);
+			c.changed = tick;
+		});
+		let moved = components.as_ref().find_in_column(index, |c| c.data.index() != index);
+		if moved.is_empty() {
+			return;
+		}
+
+		for id in moved {
+			let comp = components.remove(index, &id).unwrap();
+			let new_index = comp.data.index();
+			components.insert(new_index, id.clone(), comp);
+			if let Some(location) = entities.values_mut().find_map(|e| e.location.iter_mut().find(|l| l.1 == id)) {
+				location.0 = new_index;
+			}
+		}
+	}
+
+	// collect the components of the given entities that pass the filter
+	// the filter receives the enum index and the component
+	// returns (entity, component id, component)
+	pub fn collect_components_blocking(&self, entities: &[Uid], filter: impl Fn(usize, &Component<T>) -> bool + Sync) -> Vec<(Uid, Uid, Component<T>)> {
+		let ents = self.entities.read().unwrap();
+		let locked = self.read_components();
+		let components = locked.storage();
+		entities
+			.par_iter()
+			.filter_map(|entity| ents.get(entity).map(|e| (entity, e)))
+			.flat_map_iter(|(entity, e)| e.location.iter().filter_map(|(index, id)| components.get(*index, id).filter(|c| filter(*index, c)).map(|c| (entity.clone(), id.clone(), c.clone()))).collect::<Vec<_>>())
+			.collect()
+	}
+
+	// check that every component of an update exists on its entity
+	// accepts (entity, component id, data)
+	pub fn check_updates_blocking(&self, updates: &[(Uid, Uid, T)]) -> Result<(), String> {
+		let entities = self.entities.read().unwrap();
+		let locked = self.read_components();
+		let components = locked.storage();
+		Self::check_updates(&entities, components, updates)
+	}
+
+	fn check_updates(entities: &BTreeMap<Uid, Entity>, components: Storage<'_, T>, updates: &[(Uid, Uid, T)]) -> Result<(), String> {
+		for (entity, id, _) in updates {
+			let ent = entities.get(entity).ok_or(format!("entity: {} not found", entity))?;
+			let location = ent.location.iter().find(|l| l.1 == *id).ok_or(format!("component: {} not found on entity: {}", id, entity))?;
+			if components.get(location.0, id).is_none() {
+				return Err(format!("component: {} not found", id));
+			}
+		}
+		Ok(())
+	}
+
+	// update the data of many components at once
+	// accepts (entity, component id, data)
+	// every update is checked before any is applied, so nothing is updated if one of them fails
+	// if the data is of another variant, the component is moved to its new index
+	// components whose data differs are stamped as changed at the given tick
+	pub fn update_components_blocking(&self, updates: Vec<(Uid, Uid, T)>, tick: u64) -> Result<(), String> {
+		let mut entities = self.entities.write().unwrap();
+		let mut locked = self.write_components();
+		let mut components = locked.storage();
+		Self::check_updates(&entities, components.as_ref(), &updates)?;
+		for (entity, id, data) in updates {
+			let index = data.index();
+			let location = entities.get_mut(&entity).unwrap().location.iter_mut().find(|l| l.1 == id).unwrap();
+			if location.0 == index {
+				// the variant is unchanged, update in place so a dense handle stays valid
+				let comp = components.get_mut(index, &id).unwrap();
+				if comp.data != data {
+					comp.data = data;
+					comp.changed = tick;
+				}
+				continue;
+			}
+			let mut comp = components.remove(location.0, &id).unwrap();
+			comp.data = data;
+			comp.changed = tick;
+			components.insert(index, id.clone(), comp);
+			location.0 = index;
+		}
+		Ok(())
+	}
+
+	// record a removed component so it can be seen by change detection
+	fn record_removal(&self, entity: Uid, id: Uid, component: Component<T>) {
+		let tick = self.current_tick();
+		let limit = *self.removal_limit.read().unwrap();
+		let mut removals = self.removals.write().unwrap();
+		removals.push_back(Removal { tick, entity, id, component });
+		while removals.len() > limit {
+			removals.pop_front();
+		}
+	}
+
+	// components removed after the given tick
+	pub fn removals_since_blocking(&self, since: Option<u64>) -> Vec<Removal<T>> {
+		self.removals.read().unwrap().iter().filter(|r| since.is_none_or(|s| r.tick > s)).cloned().collect()
+	}
+
+	// keep at most the given number of removals, the oldest are forgotten first
+	pub fn set_removal_limit(&self, limit: usize) {
+		*self.removal_limit.write().unwrap() = limit;
+		let mut removals = self.removals.write().unwrap();
+		while removals.len() > limit {
+			removals.pop_front();
+		}
+	}
+
+	// forget the removals recorded at or before the given tick
+	pub fn clear_removals_blocking(&self, until: u64) {
+		self.removals.write().unwrap().retain(|r| r.tick > until);
 	}
 }
@@ -0,0 +1,11 @@
+use super::{Component, Uid};
+use serde::{Deserialize, Serialize};
+
+/// A component that was removed from a world, kept for change detection.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Deserialize)]
+pub struct Removal<T> {
+	pub tick: u64,
+	pub entity: Uid,
+	pub id: Uid,
+	pub component: Component<T>,
+}
@@ -0,0 +1,364 @@
+use super::{CompMap, Component, Uid};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+use std::collections::BTreeMap;
+
+/// How a world stores its components.
+/// `Sparse` keeps a map of components per variant, `Dense` keeps a packed column per variant
+/// addressed by generational handles, which is faster to iterate over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StorageKind {
+	#[default]
+	Sparse,
+	Dense,
+}
+
+/// The address of a component in a dense column.
+/// A handle stops resolving once its component is removed, even if the slot is reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Handle {
+	pub slot: u32,
+	pub generation: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Slot {
+	generation: u32,
+	// position of the component in the column, none if the slot is free
+	dense: Option<usize>,
+}
+
+/// A packed column of the components of one variant.
+/// Removing a component moves the last one into its place, so the column never has holes.
+#[derive(Debug, Clone)]
+pub struct Column<T> {
+	slots: Vec<Slot>,
+	free: Vec<u32>,
+	ids: Vec<Uid>,
+	data: Vec<Component<T>>,
+	// the slot of every component in the column
+	owners: Vec<u32>,
+}
+
+impl<T> Default for Column<T> {
+	fn default() -> Self {
+		Self { slots: Vec::new(), free: Vec::new(), ids: Vec::new(), data: Vec::new(), owners: Vec::new() }
+	}
+}
+
+impl<T> Column<T> {
+	pub fn len(&self) -> usize {
+		self.data.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.data.is_empty()
+	}
+
+	pub fn insert(&mut self, id: Uid, component: Component<T>) -> Handle {
+		let dense = self.data.len();
+		let slot = match self.free.pop() {
+			Some(slot) => slot,
+			None => {
+				self.slots.push(Slot { generation: 0, dense: None });
+				(self.slots.len() - 1) as u32
+			}
+		};
+		self.slots[slot as usize].dense = Some(dense);
+		self.ids.push(id);
+		self.data.push(component);
+		self.owners.push(slot);
+		Handle { slot, generation: self.slots[slot as usize].generation }
+	}
+
+	fn dense(&self, handle: Handle) -> Option<usize> {
+		self.slots.get(handle.slot as usize).filter(|s| s.generation == handle.generation).and_then(|s| s.dense)
+	}
+
+	pub fn get(&self, handle: Handle) -> Option<&Component<T>> {
+		self.dense(handle).map(|d| &self.data[d])
+	}
+
+	pub fn get_mut(&mut self, handle: Handle) -> Option<&mut Component<T>> {
+		self.dense(handle).map(|d| &mut self.data[d])
+	}
+
+	// remove a component and free its slot for reuse under a new generation
+	pub fn remove(&mut self, handle: Handle) -> Option<(Uid, Component<T>)> {
+		let dense = self.dense(handle)?;
+		let slot = &mut self.slots[handle.slot as usize];
+		slot.generation = slot.generation.wrapping_add(1);
+		slot.dense = None;
+		self.free.push(handle.slot);
+
+		let id = self.ids.swap_remove(dense);
+		let component = self.data.swap_remove(dense);
+		self.owners.swap_remove(dense);
+		if dense < self.owners.len() {
+			self.slots[self.owners[dense] as usize].dense = Some(dense);
+		}
+		Some((id, component))
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&Uid, &Component<T>)> {
+		self.ids.iter().zip(self.data.iter())
+	}
+}
+
+/// Dense columns of every variant, with the handle of every component id.
+#[derive(Debug, Clone)]
+pub struct Columns<T> {
+	pub columns: BTreeMap<usize, Column<T>>,
+	pub handles: BTreeMap<Uid, (usize, Handle)>,
+}
+
+impl<T> Columns<T> {
+	pub fn new(indexes: impl Iterator<Item = usize>) -> Self {
+		Self { columns: indexes.map(|i| (i, Column::default())).collect(), handles: BTreeMap::new() }
+	}
+
+	// move every component of a sparse map into columns
+	// the map keeps an empty map for every variant
+	pub fn from_map(map: &mut BTreeMap<usize, CompMap<T>>) -> Self {
+		let mut columns = Self::new(map.keys().cloned());
+		for (index, components) in map.iter_mut() {
+			for (id, c) in std::mem::take(components) {
+				StorageMut::Dense(&mut columns).insert(*index, id, c);
+			}
+		}
+		columns
+	}
+
+	// move every component back into a sparse map
+	pub fn into_map(self, map: &mut BTreeMap<usize, CompMap<T>>) {
+		for (index, column) in self.columns {
+			map.insert(index, column.ids.into_iter().zip(column.data).collect());
+		}
+	}
+}
+
+// serializes a dense column as a map of components
+struct ColumnMap<'a, T>(&'a Column<T>);
+
+impl<T: Serialize> Serialize for ColumnMap<'_, T> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_map(self.0.iter())
+	}
+}
+
+// columns serialize to the map of components per variant worlds always had
+impl<T: Serialize> Serialize for Columns<T> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_map(self.columns.iter().map(|(i, c)| (i, ColumnMap(c))))
+	}
+}
+
+/// The components of a world locked for reading, wherever they are kept.
+pub(crate) struct ComponentsRead<'a, T> {
+	pub sparse: RwLockReadGuard<'a, BTreeMap<usize, CompMap<T>>>,
+	pub dense: RwLockReadGuard<'a, Option<Columns<T>>>,
+}
+
+impl<T> ComponentsRead<'_, T> {
+	pub fn storage(&self) -> Storage<'_, T> {
+		match &*self.dense {
+			Some(columns) => Storage::Dense(columns),
+			None => Storage::Sparse(&self.sparse),
+		}
+	}
+}
+
+/// The components of a world locked for writing, wherever they are kept.
+pub(crate) struct ComponentsWrite<'a, T> {
+	pub sparse: RwLockWriteGuard<'a, BTreeMap<usize, CompMap<T>>>,
+	pub dense: RwLockWriteGuard<'a, Option<Columns<T>>>,
+}
+
+impl<T> ComponentsWrite<'_, T> {
+	pub fn storage(&mut self) -> StorageMut<'_, T> {
+		match &mut *self.dense {
+			Some(columns) => StorageMut::Dense(columns),
+			None => StorageMut::Sparse(&mut self.sparse),
+		}
+	}
+}
+
+/// The component storage of a world, either its sparse map or its dense columns.
+pub(crate) enum Storage<'a, T> {
+	Sparse(&'a BTreeMap<usize, CompMap<T>>),
+	Dense(&'a Columns<T>),
+}
+
+/// The component storage of a world, borrowed for writing.
+pub(crate) enum StorageMut<'a, T> {
+	Sparse(&'a mut BTreeMap<usize, CompMap<T>>),
+	Dense(&'a mut Columns<T>),
+}
+
+impl<'a, T> Storage<'a, T> {
+	// the handle of a component in dense storage
+	pub fn handle(&self, id: &Uid) -> Option<Handle> {
+		match self {
+			Storage::Sparse(_) => None,
+			Storage::Dense(columns) => columns.handles.get(id).map(|h| h.1),
+		}
+	}
+
+	// the variant index a component is stored under
+	pub fn index_of(&self, id: &Uid) -> Option<usize> {
+		match self {
+			Storage::Sparse(map) => map.iter().find(|(_, c)| c.contains_key(id)).map(|(i, _)| *i),
+			Storage::Dense(columns) => columns.handles.get(id).map(|h| h.0),
+		}
+	}
+
+	pub fn contains(&self, id: &Uid) -> bool {
+		self.index_of(id).is_some()
+	}
+
+	pub fn get(&self, index: usize, id: &Uid) -> Option<&'a Component<T>> {
+		match self {
+			Storage::Sparse(map) => map.get(&index).and_then(|c| c.get(id)),
+			Storage::Dense(columns) => {
+				let (i, handle) = columns.handles.get(id)?;
+				if *i != index {
+					return None;
+				}
+				columns.columns.get(i).and_then(|c| c.get(*handle))
+			}
+		}
+	}
+}
+
+impl<T> StorageMut<'_, T> {
+	pub fn as_ref(&self) -> Storage<'_, T> {
+		match self {
+			StorageMut::Sparse(map) => Storage::Sparse(map),
+			StorageMut::Dense(columns) => Storage::Dense(columns),
+		}
+	}
+
+	pub fn get_mut(&mut self, index: usize, id: &Uid) -> Option<&mut Component<T>> {
+		match self {
+			StorageMut::Sparse(map) => map.get_mut(&index).and_then(|c| c.get_mut(id)),
+			StorageMut::Dense(columns) => {
+				let (i, handle) = columns.handles.get(id)?;
+				if *i != index {
+					return None;
+				}
+				columns.columns.get_mut(i).and_then(|c| c.get_mut(*handle))
+			}
+		}
+	}
+
+	// insert a component under the given variant index
+	// returns the component it replaced
+	pub fn insert(&mut self, index: usize, id: Uid, component: Component<T>) -> Option<Component<T>> {
+		match self {
+			StorageMut::Sparse(map) => map.entry(index).or_default().insert(id, component),
+			StorageMut::Dense(columns) => {
+				if let Some((i, handle)) = columns.handles.get(&id).cloned() {
+					// a dense storage holds an id once, so an id stored under another variant is moved
+					if i == index {
+						return columns.columns.get_mut(&i).and_then(|c| c.get_mut(handle)).map(|c| std::mem::replace(c, component));
+					}
+					columns.columns.get_mut(&i).and_then(|c| c.remove(handle));
+				}
+				let handle = columns.columns.entry(index).or_default().insert(id.clone(), component);
+				columns.handles.insert(id, (index, handle));
+				None
+			}
+		}
+	}
+
+	pub fn remove(&mut self, index: usize, id: &Uid) -> Option<Component<T>> {
+		match self {
+			StorageMut::Sparse(map) => map.get_mut(&index).and_then(|c| c.remove(id)),
+			StorageMut::Dense(columns) => {
+				let (i, handle) = *columns.handles.get(id)?;
+				if i != index {
+					return None;
+				}
+				columns.handles.remove(id);
+				columns.columns.get_mut(&i).and_then(|c| c.remove(handle)).map(|c| c.1)
+			}
+		}
+	}
+
+	// remove a component whatever variant it is stored under
+	// returns the index it was stored under and the component
+	pub fn remove_any(&mut self, id: &Uid) -> Option<(usize, Component<T>)> {
+		let index = self.as_ref().index_of(id)?;
+		self.remove(index, id).map(|c| (index, c))
+	}
+
+	// replace every component of a variant
+	// returns the components that were replaced
+	pub fn replace_column(&mut self, index: usize, components: CompMap<T>) -> CompMap<T> {
+		let previous = match self {
+			StorageMut::Sparse(map) => return map.insert(index, components).unwrap_or_default(),
+			StorageMut::Dense(columns) => {
+				let previous = std::mem::take(columns.columns.entry(index).or_default());
+				for id in previous.ids.iter() {
+					columns.handles.remove(id);
+				}
+				previous.ids.into_iter().zip(previous.data).collect()
+			}
+		};
+		for (id, c) in components {
+			self.insert(index, id, c);
+		}
+		previous
+	}
+}
+
+impl<T: Clone + Send + Sync> Storage<'_, T> {
+	// a copy of every component of a variant
+	pub fn column(&self, index: usize) -> CompMap<T> {
+		match self {
+			Storage::Sparse(map) => map.get(&index).cloned().unwrap_or_default(),
+			Storage::Dense(columns) => columns.columns.get(&index).map(|c| c.iter().map(|(id, c)| (id.clone(), c.clone())).collect()).unwrap_or_default(),
+		}
+	}
+
+	pub fn par_for_each(&self, index: usize, f: impl Fn(&Uid, &Component<T>) + Sync + Send) {
+		match self {
+			Storage::Sparse(map) => {
+				if let Some(c) = map.get(&index) {
+					c.par_iter().for_each(|(id, c)| f(id, c));
+				}
+			}
+			Storage::Dense(columns) => {
+				if let Some(c) = columns.columns.get(&index) {
+					c.ids.par_iter().zip(c.data.par_iter()).for_each(|(id, c)| f(id, c));
+				}
+			}
+		}
+	}
+
+	// the ids of the components of a variant that match the predicate
+	pub fn find_in_column(&self, index: usize, f: impl Fn(&Component<T>) -> bool + Sync + Send) -> Vec<Uid> {
+		match self {
+			Storage::Sparse(map) => map.get(&index).map(|c| c.par_iter().filter(|(_, c)| f(c)).map(|(id, _)| id.clone()).collect()).unwrap_or_default(),
+			Storage::Dense(columns) => columns.columns.get(&index).map(|c| c.ids.par_iter().zip(c.data.par_iter()).filter(|(_, c)| f(c)).map(|(id, _)| id.clone()).collect()).unwrap_or_default(),
+		}
+	}
+}
+
+impl<T: Clone + Send + Sync> StorageMut<'_, T> {
+	pub fn par_for_each_mut(&mut self, index: usize, f: impl Fn(&Uid, &mut Component<T>) + Sync + Send) {
+		match self {
+			StorageMut::Sparse(map) => {
+				if let Some(c) = map.get_mut(&index) {
+					c.par_iter_mut().for_each(|(id, c)| f(id, c));
+				}
+			}
+			StorageMut::Dense(columns) => {
+				if let Some(c) = columns.columns.get_mut(&index) {
+					c.ids.par_iter().zip(c.data.par_iter_mut()).for_each(|(id, c)| f(id, c));
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,26 @@
+use starsystem::*;
+
+#[test]
+fn panicking_call_keeps_the_actor_running() {
+	let actor = StarSystemActor::spawn(StarSystem::<StarValue>::new_blocking());
+	let being = actor.conceive_being("config".to_string()).wait().unwrap();
+
+	let err = actor.call(|_| -> () { panic!("boom") }).wait().unwrap_err();
+	assert!(err.contains("boom"), "{}", err);
+
+	// the thread survived the panic and still owns the starsystem
+	assert_eq!(actor.get_being(being.clone()).wait().unwrap().id, being);
+	assert!(actor.stop().wait().is_ok());
+}
+
+#[test]
+fn set_property_on_unknown_being_is_an_error() {
+	let actor = StarSystemActor::spawn(StarSystem::<StarValue>::new_blocking());
+	let being = actor.conceive_being("config".to_string()).wait().unwrap();
+	let entity = actor.constitute_being(being.clone(), "server".to_string()).wait().unwrap();
+	let property = actor.add_property(being.clone(), entity.clone(), StarValue::Int(1), "port".to_string()).wait().unwrap();
+
+	assert!(actor.set_property(Uid::new(), entity.clone(), property.clone(), StarValue::Int(2), "port".to_string()).wait().is_err());
+	assert!(actor.set_property(being.clone(), Uid::new(), property.clone(), StarValue::Int(2), "port".to_string()).wait().is_err());
+	assert!(actor.set_property(being, entity, property, StarValue::Int(2), "port".to_string()).wait().is_ok());
+}
@@ -0,0 +1,70 @@
+use futures::executor::block_on;
+use starsystem::*;
+
+// the names and values of a being, without its ids
+fn shape(being: &AscendedBeing<StarValue>) -> Vec<(String, Vec<(String, StarValue)>)> {
+	being.entities.iter().map(|e| (e.name.clone(), e.components.iter().map(|c| (c.name.clone(), c.data.clone())).collect())).collect()
+}
+
+#[test]
+fn async_and_blocking_agree() {
+	let mut blocking = StarSystem::<StarValue>::new_blocking();
+	let being = blocking.conceive_being_blocking("config".to_string()).unwrap();
+	let entity = blocking.constitute_being_blocking(being.clone(), "server".to_string()).unwrap();
+	let port = blocking.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	blocking.add_property_blocking(being.clone(), entity.clone(), StarValue::from("localhost"), "host".to_string()).unwrap();
+	blocking.set_property_by_id_blocking(port, StarValue::Int(8080)).unwrap();
+	let from_blocking = blocking.ascend_being_blocking(being).unwrap();
+
+	let from_async = block_on(async {
+		let mut starsystem = StarSystem::<StarValue>::new().await;
+		let being = starsystem.conceive_being("config".to_string()).await.unwrap();
+		let entity = starsystem.constitute_being(being.clone(), "server".to_string()).await.unwrap();
+		let port = starsystem.add_property(being.clone(), entity.clone(), StarValue::Int(80), "port".to_string()).await.unwrap();
+		starsystem.add_property(being.clone(), entity.clone(), StarValue::from("localhost"), "host".to_string()).await.unwrap();
+		starsystem.set_property_by_id(port, StarValue::Int(8080)).await.unwrap();
+		starsystem.ascend_being(being).await.unwrap()
+	});
+
+	assert_eq!(shape(&from_blocking[0]), shape(&from_async[0]));
+	assert_eq!(shape(&from_blocking[0]), vec![("server".to_string(), vec![("port".to_string(), StarValue::Int(8080)), ("host".to_string(), StarValue::from("localhost"))])]);
+}
+
+#[test]
+fn blocking_lifecycle() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let being = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+	let server = starsystem.constitute_being_blocking(being.clone(), "server".to_string()).unwrap();
+	let headers = starsystem.constitute_being_blocking(being.clone(), "headers".to_string()).unwrap();
+	let port = starsystem.add_property_blocking(being.clone(), server.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	starsystem.add_property_blocking(being.clone(), headers.clone(), StarValue::from("application/json"), "Content-Type".to_string()).unwrap();
+
+	starsystem.set_property_blocking(being.clone(), server.clone(), port.clone(), StarValue::Int(443), "port".to_string()).unwrap();
+	starsystem.dissolve_entity_blocking(being.clone(), headers).unwrap();
+	let ascended = starsystem.ascend_being_blocking(being.clone()).unwrap();
+	assert_eq!(shape(&ascended[0]), vec![("server".to_string(), vec![("port".to_string(), StarValue::Int(443))])]);
+
+	starsystem.remove_property_blocking(port).unwrap();
+	assert!(starsystem.ascend_being_blocking(being.clone()).unwrap()[0].entities[0].components.is_empty());
+
+	starsystem.kill_being_blocking(being.clone()).unwrap();
+	assert!(starsystem.get_being_blocking(being.clone()).is_err());
+	assert!(starsystem.kill_being_blocking(being).is_err());
+	assert!(starsystem.worlds.values().all(|w| w.entities.read().unwrap().is_empty()));
+}
+
+#[test]
+fn world_blocking_twins() {
+	let world = World::<StarValue>::new_blocking();
+	let entity = world.create_entity_blocking("server".to_string()).unwrap();
+	let port = world.add_component_to_entity_blocking(entity.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	world.set_component_blocking(port.clone(), StarValue::Int(8080)).unwrap();
+
+	// the async methods see what the blocking ones wrote
+	let component = block_on(world.get_component(port.clone())).unwrap();
+	assert_eq!(component.data, StarValue::Int(8080));
+	assert_eq!(block_on(world.get_entity_components(entity.clone())).unwrap().len(), 1);
+
+	world.remove_entity_blocking(entity).unwrap();
+	assert!(world.get_component_blocking(port).is_err());
+}
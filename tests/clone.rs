@@ -0,0 +1,149 @@
+use starsystem::*;
+
+// a being with a server and a headers entity, returns the being, its entities and its properties
+fn config(starsystem: &mut StarSystem<StarValue>) -> (Uid, Vec<Uid>, Vec<Uid>) {
+	let being = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+	let server = starsystem.constitute_being_blocking(being.clone(), "server".to_string()).unwrap();
+	let headers = starsystem.constitute_being_blocking(being.clone(), "headers".to_string()).unwrap();
+	let port = starsystem.add_property_blocking(being.clone(), server.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	let host = starsystem.add_property_blocking(being.clone(), server.clone(), StarValue::from("localhost"), "host".to_string()).unwrap();
+	let accept = starsystem.add_property_blocking(being.clone(), headers.clone(), StarValue::from("*/*"), "Accept".to_string()).unwrap();
+	(being, vec![server, headers], vec![port, host, accept])
+}
+
+// the names and values of a being, without its ids
+fn shape(being: &AscendedBeing<StarValue>) -> Vec<(String, Vec<(String, StarValue)>)> {
+	being.entities.iter().map(|e| (e.name.clone(), e.components.iter().map(|c| (c.name.clone(), c.data.clone())).collect())).collect()
+}
+
+// the value of a property in whichever world holds it
+fn value(starsystem: &StarSystem<StarValue>, property: &Uid) -> StarValue {
+	starsystem.worlds.values().find(|w| w.has_component(property.clone())).unwrap().get_component_blocking(property.clone()).unwrap().data
+}
+
+#[test]
+fn every_id_is_remapped() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (being, entities, properties) = config(&mut starsystem);
+	let cloned = starsystem.clone_being_blocking(being.clone(), "copy".to_string(), ClonePlacement::SameWorlds).unwrap();
+
+	// every original maps to a fresh id, and the copy holds exactly the mapped ids
+	assert_eq!(cloned.ids[&being], cloned.id);
+	assert_eq!(cloned.ids.len(), 1 + entities.len() + properties.len());
+	assert!(cloned.ids.iter().all(|(original, copy)| original != copy));
+	let copy = starsystem.get_being_blocking(cloned.id.clone()).unwrap();
+	assert_eq!(copy.name, "copy");
+	assert_eq!(copy.entities.iter().map(|e| e.id.clone()).collect::<Vec<Uid>>(), entities.iter().map(|e| cloned.ids[e].clone()).collect::<Vec<Uid>>());
+	for p in properties.iter() {
+		assert_eq!(value(&starsystem, &cloned.ids[p]), value(&starsystem, p));
+	}
+
+	let original = starsystem.ascend_being_blocking(being).unwrap();
+	let copied = starsystem.ascend_being_blocking(cloned.id).unwrap();
+	assert_eq!(shape(&original[0]), shape(&copied[0]));
+}
+
+#[test]
+fn copies_go_where_they_are_placed() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	for _ in 0..4 {
+		let world = World::<StarValue>::new_blocking();
+		starsystem.worlds.insert(world.id.clone(), world);
+	}
+	let (being, entities, _) = config(&mut starsystem);
+	let world_of = |starsystem: &StarSystem<StarValue>, being: &Uid, entity: &Uid| starsystem.get_being_blocking(being.clone()).unwrap().entities.iter().find(|e| e.id == *entity).unwrap().location.world.clone();
+
+	let same = starsystem.clone_being_blocking(being.clone(), "same".to_string(), ClonePlacement::SameWorlds).unwrap();
+	for e in entities.iter() {
+		let world = world_of(&starsystem, &same.id, &same.ids[e]);
+		assert_eq!(world, world_of(&starsystem, &being, e));
+		assert!(starsystem.worlds[&world].entities.read().unwrap().contains_key(&same.ids[e]));
+	}
+
+	// random copies land on a world of the starsystem, with their properties next to them
+	let random = starsystem.clone_being_blocking(being, "random".to_string(), ClonePlacement::Random).unwrap();
+	let copy = starsystem.get_being_blocking(random.id).unwrap();
+	for e in copy.entities.iter() {
+		let world = &starsystem.worlds[&e.location.world];
+		assert!(world.entities.read().unwrap().contains_key(&e.id));
+		assert!(e.properties.iter().all(|p| world.has_component(p.id.clone()) && p.location.world == e.location.world));
+	}
+}
+
+#[test]
+fn copy_is_independent_of_its_source() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (being, entities, properties) = config(&mut starsystem);
+	let cloned = starsystem.clone_being_blocking(being.clone(), "copy".to_string(), ClonePlacement::SameWorlds).unwrap();
+
+	starsystem.set_property_by_id_blocking(properties[0].clone(), StarValue::Int(443)).unwrap();
+	assert_eq!(value(&starsystem, &cloned.ids[&properties[0]]), StarValue::Int(80));
+	starsystem.set_property_by_id_blocking(cloned.ids[&properties[1]].clone(), StarValue::from("0.0.0.0")).unwrap();
+	assert_eq!(value(&starsystem, &properties[1]), StarValue::from("localhost"));
+
+	// removing parts of the source or the source itself leaves the copy whole
+	starsystem.dissolve_entity_blocking(being.clone(), entities[1].clone()).unwrap();
+	starsystem.kill_being_blocking(being).unwrap();
+	let copied = starsystem.ascend_being_blocking(cloned.id).unwrap();
+	assert_eq!(shape(&copied[0]), vec![("server".to_string(), vec![("port".to_string(), StarValue::Int(80)), ("host".to_string(), StarValue::from("0.0.0.0"))]), ("headers".to_string(), vec![("Accept".to_string(), StarValue::from("*/*"))])]);
+}
+
+#[test]
+fn computed_inputs_are_remapped() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (being, entities, properties) = config(&mut starsystem);
+	let double = starsystem.add_computed_property_blocking(being.clone(), entities[0].clone(), "double".to_string(), vec![properties[0].clone()], |v| StarValue::Int(v[0].as_int().unwrap() * 2)).unwrap();
+	let cloned = starsystem.clone_being_blocking(being, "copy".to_string(), ClonePlacement::SameWorlds).unwrap();
+
+	// the copy reads the copy of its input, not the original
+	let copied = cloned.ids[&double].clone();
+	assert!(starsystem.computed.is_computed(&copied));
+	assert_eq!(starsystem.computed.get(&copied).unwrap().inputs, vec![cloned.ids[&properties[0]].clone()]);
+	starsystem.set_property_by_id_blocking(cloned.ids[&properties[0]].clone(), StarValue::Int(21)).unwrap();
+	assert_eq!(value(&starsystem, &copied), StarValue::Int(42));
+	assert_eq!(value(&starsystem, &double), StarValue::Int(160));
+
+	starsystem.set_property_by_id_blocking(properties[0].clone(), StarValue::Int(1)).unwrap();
+	assert_eq!(value(&starsystem, &double), StarValue::Int(2));
+	assert_eq!(value(&starsystem, &copied), StarValue::Int(42));
+}
+
+#[test]
+fn copy_follows_the_schema_and_constraints_of_its_name() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (being, _, _) = config(&mut starsystem);
+	let schema = BeingSchema::new().entity(EntitySchema::new("server".to_string()).property(PropertySchema::new("workers".to_string()).default(StarValue::Int(4)))).entity(EntitySchema::new("log".to_string()).required().property(PropertySchema::new("level".to_string()).default(StarValue::from("info"))));
+	starsystem.set_schema("service".to_string(), schema);
+
+	// the copy gets the required entities and default properties of the schema of its name
+	let cloned = starsystem.clone_being_blocking(being.clone(), "service".to_string(), ClonePlacement::SameWorlds).unwrap();
+	let copied = starsystem.ascend_being_blocking(cloned.id).unwrap();
+	let names: Vec<(String, Vec<String>)> = copied[0].entities.iter().map(|e| (e.name.clone(), e.components.iter().map(|c| c.name.clone()).collect())).collect();
+	assert!(names.contains(&("log".to_string(), vec!["level".to_string()])));
+	assert!(names.iter().any(|(e, p)| e == "server" && p.contains(&"workers".to_string()) && p.contains(&"port".to_string())));
+
+	// a value breaking the schema or a constraint of the new name writes nothing
+	starsystem.set_schema("strict".to_string(), BeingSchema::new().entity(EntitySchema::new("server".to_string()).property(PropertySchema::new("port".to_string()).variant(StarValue::from("")))));
+	let beings = starsystem.beings.len();
+	let entities: usize = starsystem.worlds.values().map(|w| w.entities.read().unwrap().len()).sum();
+	assert!(starsystem.clone_being_blocking(being.clone(), "strict".to_string(), ClonePlacement::SameWorlds).is_err());
+	starsystem.add_path_constraint("limited/server/port".to_string(), Constraint::range(Some(1024.0), None));
+	assert!(starsystem.clone_being_blocking(being, "limited".to_string(), ClonePlacement::SameWorlds).is_err());
+	assert_eq!(starsystem.beings.len(), beings);
+	assert_eq!(starsystem.worlds.values().map(|w| w.entities.read().unwrap().len()).sum::<usize>(), entities);
+}
+
+#[test]
+fn stale_entity_is_an_error() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (being, entities, _) = config(&mut starsystem);
+
+	// taken out of its world behind the back of the being
+	let world = starsystem.worlds.values().find(|w| w.entities.read().unwrap().contains_key(&entities[1])).unwrap().clone();
+	world.remove_entity_blocking(entities[1].clone()).unwrap();
+	assert!(world.get_entity_components_blocking(entities[1].clone()).is_err());
+
+	let beings = starsystem.beings.len();
+	assert!(starsystem.clone_being_blocking(being, "copy".to_string(), ClonePlacement::SameWorlds).is_err());
+	assert_eq!(starsystem.beings.len(), beings);
+}
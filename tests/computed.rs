@@ -0,0 +1,84 @@
+use starsystem::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn failed_registration_is_undone() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let being = starsystem.conceive_being_blocking("order".to_string()).unwrap();
+	let entity = starsystem.constitute_being_blocking(being.clone(), "line".to_string()).unwrap();
+	let price = starsystem.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(2), "price".to_string()).unwrap();
+	let total = starsystem.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(0), "total".to_string()).unwrap();
+	let label = starsystem.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(0), "label".to_string()).unwrap();
+
+	// label reads total, then fails once total becomes computed because its input is gone
+	let gone = starsystem.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(1), "gone".to_string()).unwrap();
+	starsystem.set_computed_property_blocking(label.clone(), vec![total.clone(), gone.clone()], |v| v[1].clone()).unwrap();
+	// removed straight from the world, so the registration of label still reads it
+	let world = starsystem.worlds.values().find(|w| w.has_component(gone.clone())).unwrap();
+	world.remove_component_from_entity_blocking(entity, gone).unwrap();
+
+	let called = Arc::new(AtomicBool::new(false));
+	let c = called.clone();
+	let res = starsystem.set_computed_property_blocking(total.clone(), vec![price], move |v| {
+		c.store(true, Ordering::SeqCst);
+		v[0].clone()
+	});
+	assert!(res.is_err());
+	assert!(called.load(Ordering::SeqCst));
+	assert!(!starsystem.computed.is_computed(&total));
+	let world = starsystem.worlds.values().find(|w| w.has_component(total.clone())).unwrap();
+	assert_eq!(world.get_component_blocking(total).unwrap().data, StarValue::Int(0));
+}
+
+// an order with a price, a quantity and a total computed from them, limited to 100
+fn order(starsystem: &mut StarSystem<StarValue>) -> (Uid, Uid, Uid) {
+	starsystem.add_constraint("total".to_string(), Constraint::range(None, Some(100.0)));
+	let being = starsystem.conceive_being_blocking("order".to_string()).unwrap();
+	let entity = starsystem.constitute_being_blocking(being.clone(), "line".to_string()).unwrap();
+	let price = starsystem.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(2), "price".to_string()).unwrap();
+	let quantity = starsystem.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(3), "quantity".to_string()).unwrap();
+	let total = starsystem.add_computed_property_blocking(being, entity, "total".to_string(), vec![price.clone(), quantity.clone()], |v| StarValue::Int(v[0].as_int().unwrap() * v[1].as_int().unwrap())).unwrap();
+	(price, quantity, total)
+}
+
+// the value of a property in whichever world holds it
+fn value(worlds: &std::collections::BTreeMap<Uid, World<StarValue>>, property: &Uid) -> StarValue {
+	worlds.values().find(|w| w.has_component(property.clone())).unwrap().get_component_blocking(property.clone()).unwrap().data
+}
+
+#[test]
+fn computed_values_are_checked_before_the_input_is_written() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (price, quantity, total) = order(&mut starsystem);
+	assert_eq!(value(&starsystem.worlds, &total), StarValue::Int(6));
+
+	// 50 * 3 breaks the limit of total, so price keeps its value
+	let err = starsystem.set_property_by_id_blocking(price.clone(), StarValue::Int(50)).unwrap_err();
+	assert!(matches!(err, PropertyError::Invalid(_)), "{:?}", err);
+	assert_eq!(value(&starsystem.worlds, &price), StarValue::Int(2));
+	assert_eq!(value(&starsystem.worlds, &total), StarValue::Int(6));
+
+	starsystem.set_property_by_id_blocking(quantity.clone(), StarValue::Int(10)).unwrap();
+	assert_eq!(value(&starsystem.worlds, &total), StarValue::Int(20));
+
+	// registering a compute function whose value breaks the limit is undone
+	assert!(starsystem.set_computed_property_blocking(total.clone(), vec![price, quantity], |v| StarValue::Int(v[0].as_int().unwrap() * 1000 * v[1].as_int().unwrap())).is_err());
+	assert_eq!(value(&starsystem.worlds, &total), StarValue::Int(20));
+}
+
+#[test]
+fn shared_computed_values_are_checked_before_the_input_is_written() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (price, _, total) = order(&mut starsystem);
+	let shared = SharedStarSystem::from(starsystem);
+
+	assert!(matches!(shared.set_property_by_id_blocking(price.clone(), StarValue::Int(50)), Err(PropertyError::Invalid(_))));
+	let worlds = shared.worlds.read().unwrap().clone();
+	assert_eq!(value(&worlds, &price), StarValue::Int(2));
+	assert_eq!(value(&worlds, &total), StarValue::Int(6));
+
+	shared.set_property_by_id_blocking(price.clone(), StarValue::Int(30)).unwrap();
+	let worlds = shared.worlds.read().unwrap().clone();
+	assert_eq!(value(&worlds, &total), StarValue::Int(90));
+}
@@ -0,0 +1,88 @@
+use starsystem::*;
+
+#[test]
+fn shared_follows_name_conflict_policies() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	starsystem.set_entity_conflict_policy(NameConflictPolicy::Merge).set_property_conflict_policy(NameConflictPolicy::Reject);
+	let shared = SharedStarSystem::from(starsystem);
+	let being = shared.conceive_being_blocking("config".to_string()).unwrap();
+
+	let entity = shared.constitute_being_blocking(being.clone(), "server".to_string()).unwrap();
+	assert_eq!(shared.constitute_being_blocking(being.clone(), "server".to_string()).unwrap(), entity);
+
+	let port = shared.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	assert!(shared.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(81), "port".to_string()).is_err());
+	assert_eq!(shared.add_property_with_policy_blocking(being.clone(), entity.clone(), StarValue::Int(82), "port".to_string(), NameConflictPolicy::Merge).unwrap(), port);
+
+	shared.set_property_conflict_policy(NameConflictPolicy::Replace);
+	let replaced = shared.add_property_blocking(being.clone(), entity, StarValue::Int(83), "port".to_string()).unwrap();
+	assert_ne!(replaced, port);
+	let ascended = shared.ascend_being_blocking(being).unwrap();
+	assert_eq!(ascended[0].entities.len(), 1);
+	assert_eq!(ascended[0].entities[0].components.len(), 1);
+	assert_eq!(ascended[0].entities[0].components[0].data, StarValue::Int(83));
+}
+
+#[test]
+fn replacing_a_required_property_is_checked_like_removing_it() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	starsystem.set_schema("config".to_string(), BeingSchema::new().entity(EntitySchema::new("server".to_string()).required().property(PropertySchema::new("port".to_string()).required().default(StarValue::Int(80)))));
+	let being = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+	let entity = starsystem.get_being_blocking(being.clone()).unwrap().entities[0].id.clone();
+
+	let replace = starsystem.add_property_with_policy_blocking(being.clone(), entity.clone(), StarValue::Int(81), "port".to_string(), NameConflictPolicy::Replace);
+	assert!(matches!(replace, Err(PropertyError::Schema(_))));
+	assert_eq!(starsystem.ascend_being_blocking(being.clone()).unwrap()[0].entities[0].components[0].data, StarValue::Int(80));
+
+	starsystem.add_property_with_policy_blocking(being.clone(), entity, StarValue::Int(81), "port".to_string(), NameConflictPolicy::Merge).unwrap();
+	assert_eq!(starsystem.ascend_being_blocking(being).unwrap()[0].entities[0].components[0].data, StarValue::Int(81));
+}
+
+#[test]
+fn replace_with_a_bad_default_keeps_the_original() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let being = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+	let server = starsystem.constitute_being_blocking(being.clone(), "server".to_string()).unwrap();
+	let port = starsystem.add_property_blocking(being.clone(), server.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	let next = starsystem.add_computed_property_blocking(being.clone(), server.clone(), "next".to_string(), vec![port.clone()], |v| StarValue::Int(v[0].as_int().unwrap() + 1)).unwrap();
+
+	// the default of the new entity breaks a constraint
+	starsystem.add_constraint("port".to_string(), Constraint::range(Some(1.0), Some(65535.0)));
+	starsystem.set_schema("config".to_string(), BeingSchema::new().entity(EntitySchema::new("server".to_string()).property(PropertySchema::new("port".to_string()).default(StarValue::Int(70000)))));
+	let err = starsystem.constitute_being_with_policy_blocking(being.clone(), "server".to_string(), NameConflictPolicy::Replace).unwrap_err();
+	assert!(err.contains("port"), "{}", err);
+
+	// the original entity, its properties and the computed property reading them are untouched
+	let b = starsystem.get_being_blocking(being.clone()).unwrap();
+	assert_eq!(b.entities.iter().map(|e| e.id.clone()).collect::<Vec<Uid>>(), vec![server.clone()]);
+	assert_eq!(starsystem.worlds.values().map(|w| w.entities.read().unwrap().len()).sum::<usize>(), 1);
+	assert!(starsystem.computed.is_computed(&next));
+	starsystem.set_property_by_id_blocking(port, StarValue::Int(8080)).unwrap();
+	let ascended = starsystem.ascend_being_blocking(being.clone()).unwrap();
+	assert_eq!(ascended[0].entities[0].components.iter().find(|c| c.id == next).unwrap().data, StarValue::Int(8081));
+
+	// a valid default replaces it and forgets the computed property of the old entity
+	starsystem.set_schema("config".to_string(), BeingSchema::new().entity(EntitySchema::new("server".to_string()).property(PropertySchema::new("port".to_string()).default(StarValue::Int(443)))));
+	let replaced = starsystem.constitute_being_with_policy_blocking(being.clone(), "server".to_string(), NameConflictPolicy::Replace).unwrap();
+	assert_ne!(replaced, server);
+	assert!(!starsystem.computed.is_computed(&next));
+	let ascended = starsystem.ascend_being_blocking(being).unwrap();
+	assert_eq!(ascended[0].entities.len(), 1);
+	assert_eq!(ascended[0].entities[0].components.iter().map(|c| (c.name.clone(), c.data.clone())).collect::<Vec<_>>(), vec![("port".to_string(), StarValue::Int(443))]);
+}
+
+#[test]
+fn shared_replace_with_a_bad_default_keeps_the_original() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let being = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+	let server = starsystem.constitute_being_blocking(being.clone(), "server".to_string()).unwrap();
+	starsystem.add_property_blocking(being.clone(), server.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	starsystem.add_constraint("port".to_string(), Constraint::range(Some(1.0), Some(65535.0)));
+	starsystem.set_schema("config".to_string(), BeingSchema::new().entity(EntitySchema::new("server".to_string()).property(PropertySchema::new("port".to_string()).default(StarValue::Int(70000)))));
+	let shared = SharedStarSystem::from(starsystem);
+
+	assert!(shared.constitute_being_with_policy_blocking(being.clone(), "server".to_string(), NameConflictPolicy::Replace).is_err());
+	let ascended = shared.ascend_being_blocking(being).unwrap();
+	assert_eq!(ascended[0].entities.iter().map(|e| e.id.clone()).collect::<Vec<Uid>>(), vec![server]);
+	assert_eq!(ascended[0].entities[0].components[0].data, StarValue::Int(80));
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use starsystem::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Http {
+	host: String,
+	port: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Config {
+	http: Http,
+}
+
+fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+	vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn failing_variable_leaves_the_being_untouched() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let config = Config { http: Http { host: "localhost".to_string(), port: 80 } };
+	let being = starsystem.insert_serialize_blocking("config".to_string(), &config).unwrap();
+	starsystem.add_constraint("port".to_string(), Constraint::range(Some(1.0), Some(65535.0)));
+
+	// the host sorts before the port, but must not be written when the port is out of range
+	let overlay = EnvOverlay::new("APP".to_string()).vars(vars(&[("APP__HTTP__HOST", "example.com"), ("APP__HTTP__PORT", "70000")]));
+	assert!(starsystem.apply_env_overlay_with_blocking(being.clone(), &overlay, parse_star_value).is_err());
+	assert_eq!(starsystem.extract_blocking::<Config>(being).unwrap(), config);
+}
+
+#[test]
+fn leading_zero_numerals_stay_strings() {
+	assert_eq!(parse_star_value::<StarValue>("007").unwrap(), StarValue::String("007".to_string()));
+	assert_eq!(parse_star_value::<StarValue>("-01.5").unwrap(), StarValue::String("-01.5".to_string()));
+	assert_eq!(parse_star_value::<StarValue>("0").unwrap(), StarValue::Int(0));
+	assert_eq!(parse_star_value::<StarValue>("0.5").unwrap(), StarValue::Float(0.5));
+	assert_eq!(parse_star_value::<StarValue>("7").unwrap(), StarValue::Int(7));
+}
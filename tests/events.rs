@@ -0,0 +1,93 @@
+use starsystem::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn events_live_two_updates() {
+	let mut events = Events::<u32>::default();
+	events.send(1);
+	assert_eq!(events.len(), 1);
+
+	events.update();
+	events.send(2);
+	assert_eq!(events.read_from(0).0, vec![1, 2]);
+
+	// the first event is dropped after its second update, the second one is kept for one more
+	events.update();
+	assert_eq!(events.read_from(0).0, vec![2]);
+	events.update();
+	assert!(events.is_empty());
+}
+
+#[test]
+fn reader_sees_each_event_once_across_the_swap() {
+	let bus = EventBus::new();
+	let reader = bus.reader::<u32>();
+	let writer = bus.writer::<u32>();
+
+	writer.send(1);
+	assert_eq!(reader.len(), 1);
+	assert_eq!(reader.read(), vec![1]);
+	assert!(reader.is_empty());
+
+	// the event moves to the previous buffer, but the reader has seen it already
+	bus.update();
+	writer.send(2);
+	assert_eq!(reader.read(), vec![2]);
+	assert!(reader.read().is_empty());
+
+	bus.update();
+	assert!(reader.read().is_empty());
+}
+
+#[test]
+fn late_reader_only_sees_buffered_events() {
+	let bus = EventBus::new();
+	let writer = bus.writer::<u32>();
+	writer.send(1);
+	bus.update();
+	writer.send(2);
+	bus.update();
+
+	// 1 is gone, 2 is still in the previous buffer
+	let reader = bus.reader::<u32>();
+	assert_eq!(reader.read(), vec![2]);
+
+	// a reader that falls behind for two updates misses the events of that time
+	writer.send(3);
+	bus.update();
+	bus.update();
+	writer.send(4);
+	assert_eq!(reader.read(), vec![4]);
+}
+
+#[test]
+fn systems_talk_through_events() {
+	#[derive(Clone)]
+	struct Ping(u64);
+
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	starsystem.add_event::<Ping>();
+	let reader = starsystem.event_reader::<Ping>();
+	let seen = Arc::new(Mutex::new(Vec::new()));
+	let sent = Arc::new(AtomicUsize::new(0));
+
+	let counter = sent.clone();
+	starsystem.add_system("send".to_string(), Query::new(), move |ctx| {
+		ctx.events.writer::<Ping>().send(Ping(ctx.tick));
+		counter.fetch_add(1, Ordering::SeqCst);
+	});
+	let log = seen.clone();
+	starsystem.add_system("receive".to_string(), Query::new(), move |_ctx| {
+		log.lock().unwrap().extend(reader.read().into_iter().map(|p| p.0));
+	});
+
+	for _ in 0..3 {
+		starsystem.run_systems_blocking().unwrap();
+	}
+
+	// every ping is received once, in the order it was sent
+	let seen = seen.lock().unwrap();
+	assert_eq!(seen.len(), sent.load(Ordering::SeqCst));
+	assert!(seen.windows(2).all(|w| w[0] < w[1]));
+}
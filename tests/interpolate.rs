@@ -0,0 +1,87 @@
+use serde::Serialize;
+use starsystem::*;
+
+// a being with one entity and the given properties, returns the being and the property ids
+fn being(starsystem: &mut StarSystem<StarValue>, name: &str, entity: &str, properties: &[(&str, &str)]) -> (Uid, Vec<Uid>) {
+	let being = starsystem.conceive_being_blocking(name.to_string()).unwrap();
+	let e = starsystem.constitute_being_blocking(being.clone(), entity.to_string()).unwrap();
+	let ids = properties.iter().map(|(property, value)| starsystem.add_property_blocking(being.clone(), e.clone(), StarValue::from(*value), property.to_string()).unwrap()).collect();
+	(being, ids)
+}
+
+#[test]
+fn references_expand_across_beings() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (config, _) = being(&mut starsystem, "config", "server", &[("host", "localhost"), ("port", "8080")]);
+	let (app, ids) = being(&mut starsystem, "app", "client", &[("url", "http://${config/server/host}:${config/server/port}/"), ("same", "${client/url}")]);
+
+	assert_eq!(starsystem.get_property_interpolated_blocking(app.clone(), ids[0].clone()).unwrap(), StarValue::from("http://localhost:8080/"));
+	assert_eq!(starsystem.get_property_interpolated_blocking(app.clone(), ids[1].clone()).unwrap(), StarValue::from("http://localhost:8080/"));
+
+	// the stored values keep their references
+	let ascended = starsystem.ascend_being_blocking(app.clone()).unwrap();
+	assert_eq!(ascended[0].entities[0].components.iter().find(|c| c.name == "url").unwrap().data, StarValue::from("http://${config/server/host}:${config/server/port}/"));
+	let interpolated = starsystem.ascend_being_interpolated_blocking(app).unwrap();
+	assert!(interpolated[0].entities[0].components.iter().all(|c| c.data == StarValue::from("http://localhost:8080/")));
+	assert_eq!(starsystem.ascend_being_interpolated_blocking(config).unwrap()[0].entities[0].components[0].data, StarValue::from("localhost"));
+}
+
+#[test]
+fn nested_property_names_resolve_against_the_same_being() {
+	#[derive(Serialize)]
+	struct Tls {
+		cert: String,
+	}
+	#[derive(Serialize)]
+	struct Http {
+		tls: Tls,
+		cert_path: String,
+	}
+	#[derive(Serialize)]
+	struct Config {
+		http: Http,
+	}
+
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let config = Config { http: Http { tls: Tls { cert: "/etc/cert.pem".to_string() }, cert_path: "${http/tls/cert}".to_string() } };
+	let id = starsystem.insert_serialize_blocking("config".to_string(), &config).unwrap();
+	let ascended = starsystem.ascend_being_interpolated_blocking(id).unwrap();
+	let path = ascended[0].entities[0].components.iter().find(|c| c.name == "cert_path").unwrap();
+	assert_eq!(path.data, StarValue::from("/etc/cert.pem"));
+
+	// a being named like the entity is only used when the same being has no such property
+	being(&mut starsystem, "http", "tls", &[("cert", "other.pem"), ("key", "key.pem")]);
+	let (app, ids) = being(&mut starsystem, "app", "http", &[("tls/cert", "mine.pem"), ("cert", "${http/tls/cert}"), ("key", "${http/tls/key}")]);
+	assert_eq!(starsystem.get_property_interpolated_blocking(app.clone(), ids[1].clone()).unwrap(), StarValue::from("mine.pem"));
+	assert_eq!(starsystem.get_property_interpolated_blocking(app, ids[2].clone()).unwrap(), StarValue::from("key.pem"));
+}
+
+#[test]
+fn dollar_dollar_is_an_escape() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (app, ids) = being(&mut starsystem, "app", "shell", &[("home", "/root"), ("script", "echo $${HOME} ${shell/home} $$ $x")]);
+	assert_eq!(starsystem.get_property_interpolated_blocking(app, ids[1].clone()).unwrap(), StarValue::from("echo ${HOME} /root $$ $x"));
+}
+
+#[test]
+fn cycles_are_errors() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (app, ids) = being(&mut starsystem, "app", "loop", &[("a", "${loop/b}"), ("b", "x${loop/c}"), ("c", "${loop/a}"), ("me", "${loop/me}")]);
+
+	let err = starsystem.get_property_interpolated_blocking(app.clone(), ids[0].clone()).unwrap_err();
+	assert!(err.contains("cycle in references: app/loop/a -> app/loop/b -> app/loop/c -> app/loop/a"), "{}", err);
+	let err = starsystem.get_property_interpolated_blocking(app.clone(), ids[3].clone()).unwrap_err();
+	assert!(err.contains("cycle in references: app/loop/me -> app/loop/me"), "{}", err);
+	assert!(starsystem.ascend_being_interpolated_blocking(app).is_err());
+}
+
+#[test]
+fn unresolved_and_invalid_references_are_errors() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let (app, ids) = being(&mut starsystem, "app", "server", &[("missing", "${server/nothing}"), ("other", "${nobody/server/host}"), ("bare", "${host}"), ("open", "${server/missing")]);
+
+	assert_eq!(starsystem.get_property_interpolated_blocking(app.clone(), ids[0].clone()).unwrap_err(), "app/server/missing: unresolved reference ${app/server/nothing}");
+	assert_eq!(starsystem.get_property_interpolated_blocking(app.clone(), ids[1].clone()).unwrap_err(), "app/server/other: unresolved reference ${nobody/server/host}");
+	assert!(starsystem.get_property_interpolated_blocking(app.clone(), ids[2].clone()).unwrap_err().contains("invalid reference ${host}"));
+	assert!(starsystem.get_property_interpolated_blocking(app, ids[3].clone()).unwrap_err().contains("unterminated reference"));
+}
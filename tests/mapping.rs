@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use starsystem::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Port {
+	port: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Config {
+	name: String,
+	port: Port,
+}
+
+#[test]
+fn entity_with_a_field_of_its_own_name_round_trips() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let config = Config { name: "server".to_string(), port: Port { port: 8080 } };
+	let being = starsystem.insert_serialize_blocking("config".to_string(), &config).unwrap();
+
+	let ascended = starsystem.ascend_being_blocking(being.clone()).unwrap();
+	let name = ascended[0].entities.iter().find(|e| e.name == "name").unwrap();
+	assert_eq!(name.components[0].name, SCALAR_PROPERTY);
+	assert_eq!(starsystem.extract_blocking::<Config>(being).unwrap(), config);
+}
+
+#[test]
+fn reserved_property_name_is_rejected() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let mut headers = std::collections::BTreeMap::new();
+	headers.insert("headers", std::collections::BTreeMap::from([(SCALAR_PROPERTY, 1)]));
+	assert!(starsystem.insert_serialize_blocking("config".to_string(), &headers).is_err());
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Http {
+	port: u16,
+	hosts: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+struct Tls {
+	cert: Option<String>,
+	key: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Server {
+	http: Http,
+	tls: Tls,
+	headers: std::collections::BTreeMap<String, String>,
+	aliases: Vec<String>,
+}
+
+#[test]
+fn nested_empty_containers_round_trip() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let server = Server { http: Http { port: 80, hosts: vec![] }, tls: Tls::default(), headers: std::collections::BTreeMap::new(), aliases: vec![] };
+	let being = starsystem.insert_serialize_blocking("config".to_string(), &server).unwrap();
+	assert_eq!(starsystem.extract_blocking::<Server>(being.clone()).unwrap(), server);
+
+	// nested empty containers are kept as null at their path, empty entities have no properties
+	let ascended = starsystem.ascend_being_blocking(being).unwrap();
+	let http = ascended[0].entities.iter().find(|e| e.name == "http").unwrap();
+	assert!(http.components.iter().any(|c| c.name == "hosts" && c.data == StarValue::Null));
+	assert!(ascended[0].entities.iter().find(|e| e.name == "tls").unwrap().components.is_empty());
+
+	// the same containers one level deeper
+	let nested = std::collections::BTreeMap::from([("main".to_string(), server.clone())]);
+	let being = starsystem.insert_serialize_blocking("servers".to_string(), &nested).unwrap();
+	assert_eq!(starsystem.extract_blocking::<std::collections::BTreeMap<String, Server>>(being).unwrap(), nested);
+
+	// filled containers still round trip next to empty ones
+	let filled = Server { http: Http { port: 443, hosts: vec!["a".to_string(), "b".to_string()] }, tls: Tls { cert: Some("cert.pem".to_string()), key: None }, headers: std::collections::BTreeMap::from([("Accept".to_string(), "*/*".to_string())]), aliases: vec![] };
+	let being = starsystem.insert_serialize_blocking("filled".to_string(), &filled).unwrap();
+	assert_eq!(starsystem.extract_blocking::<Server>(being).unwrap(), filled);
+}
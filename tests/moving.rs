@@ -0,0 +1,31 @@
+use starsystem::*;
+
+#[test]
+fn failed_move_across_worlds_keeps_the_property() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	for _ in 0..2 {
+		let world = World::<StarValue>::new_blocking();
+		starsystem.worlds.insert(world.id.clone(), world);
+	}
+	let being = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+	let source = starsystem.constitute_being_blocking(being.clone(), "source".to_string()).unwrap();
+	let world_of = |s: &StarSystem<StarValue>, entity: &Uid| s.worlds.values().find(|w| w.has_entity(entity.clone())).unwrap().id.clone();
+
+	// constitute until the target lands on the other world
+	let target = loop {
+		let target = starsystem.constitute_being_blocking(being.clone(), "target".to_string()).unwrap();
+		if world_of(&starsystem, &target) != world_of(&starsystem, &source) {
+			break target;
+		}
+	};
+	let property = starsystem.add_property_blocking(being.clone(), source.clone(), StarValue::Int(1), "port".to_string()).unwrap();
+
+	// the target is gone from its world but still listed on the being
+	let world = world_of(&starsystem, &target);
+	starsystem.worlds[&world].remove_entity_blocking(target.clone()).unwrap();
+
+	assert!(starsystem.move_property_blocking(property.clone(), source.clone(), target).is_err());
+	let components = starsystem.worlds[&world_of(&starsystem, &source)].get_entity_components_blocking(source).unwrap();
+	assert_eq!(components, vec![(property, components[0].1.clone())]);
+	assert_eq!(components[0].1.data, StarValue::Int(1));
+}
@@ -0,0 +1,29 @@
+use starsystem::*;
+
+#[test]
+fn removal_log_is_capped() {
+	let world = World::<StarValue>::new_blocking();
+	world.set_removal_limit(3);
+	let entity = world.create_entity_blocking("server".to_string()).unwrap();
+	for i in 0..10 {
+		let id = world.add_component_to_entity_blocking(entity.clone(), StarValue::Int(i), "n".to_string()).unwrap();
+		world.remove_component_from_entity_blocking(entity.clone(), id).unwrap();
+	}
+	let kept: Vec<StarValue> = world.removals_since_blocking(None).into_iter().map(|r| r.component.data).collect();
+	assert_eq!(kept, vec![StarValue::Int(7), StarValue::Int(8), StarValue::Int(9)]);
+}
+
+#[test]
+fn removal_log_is_not_serialized() {
+	let world = World::<StarValue>::new_blocking();
+	let entity = world.create_entity_blocking("server".to_string()).unwrap();
+	let id = world.add_component_to_entity_blocking(entity.clone(), StarValue::Int(1), "n".to_string()).unwrap();
+	world.remove_component_from_entity_blocking(entity, id).unwrap();
+	assert_eq!(world.removals_since_blocking(None).len(), 1);
+
+	let json = serde_json::to_string(&world).unwrap();
+	assert!(!json.contains("removals"));
+	let loaded: World<StarValue> = serde_json::from_str(&json).unwrap();
+	assert!(loaded.removals_since_blocking(None).is_empty());
+	assert_eq!(*loaded.removal_limit.read().unwrap(), REMOVAL_LIMIT);
+}
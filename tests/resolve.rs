@@ -0,0 +1,92 @@
+use starsystem::*;
+
+// a being with the given entities and properties
+fn layer(starsystem: &mut StarSystem<StarValue>, name: &str, entities: &[(&str, &[(&str, StarValue)])]) -> Uid {
+	let being = starsystem.conceive_being_blocking(name.to_string()).unwrap();
+	for (entity, properties) in entities {
+		let e = starsystem.constitute_being_blocking(being.clone(), entity.to_string()).unwrap();
+		for (property, value) in properties.iter() {
+			starsystem.add_property_blocking(being.clone(), e.clone(), value.clone(), property.to_string()).unwrap();
+		}
+	}
+	being
+}
+
+#[test]
+fn later_layers_win_property_by_property() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let base = layer(&mut starsystem, "base", &[("server", &[("port", StarValue::Int(80)), ("host", StarValue::from("localhost"))]), ("log", &[("level", StarValue::from("info"))])]);
+	let production = layer(&mut starsystem, "production", &[("server", &[("port", StarValue::Int(443))])]);
+	let local = layer(&mut starsystem, "local", &[("log", &[("level", StarValue::from("debug"))])]);
+
+	let resolved = starsystem.resolve_blocking(&[base.clone(), production.clone(), local.clone()]).unwrap();
+	assert_eq!(resolved.layers, vec![base.clone(), production.clone(), local.clone()]);
+	assert_eq!(resolved.value("server", "port"), Some(StarValue::Int(443)));
+	assert_eq!(resolved.value("server", "host"), Some(StarValue::from("localhost")));
+	assert_eq!(resolved.value("log", "level"), Some(StarValue::from("debug")));
+	assert_eq!(resolved.value("server", "missing"), None);
+
+	// the order of the layers decides, not the order the beings were made in
+	let reversed = starsystem.resolve_blocking(&[production, base]).unwrap();
+	assert_eq!(reversed.value("server", "port"), Some(StarValue::Int(80)));
+}
+
+#[test]
+fn every_value_records_its_layer() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let base = layer(&mut starsystem, "base", &[("server", &[("port", StarValue::Int(80)), ("host", StarValue::from("localhost"))])]);
+	let production = layer(&mut starsystem, "production", &[("server", &[("port", StarValue::Int(443))])]);
+
+	let resolved = starsystem.resolve_blocking(&[base.clone(), production.clone()]).unwrap();
+	assert_eq!(resolved.provenance("server", "port"), Some(&production));
+	assert_eq!(resolved.provenance("server", "host"), Some(&base));
+	assert_eq!(resolved.provenance("server", "missing"), None);
+
+	// the id is the one of the property in the layer it came from
+	let port = resolved.get("server", "port").unwrap();
+	let ascended = starsystem.ascend_being_blocking(production).unwrap();
+	assert_eq!(port.id, ascended[0].entities[0].components[0].id);
+}
+
+#[test]
+fn removal_markers_hide_lower_layers() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let base = layer(&mut starsystem, "base", &[("server", &[("port", StarValue::Int(80)), ("host", StarValue::from("localhost"))]), ("debug", &[("trace", StarValue::Bool(true))])]);
+	let production = layer(&mut starsystem, "production", &[("server", &[("!host", StarValue::Null), ("port", StarValue::Int(443))]), ("!debug", &[])]);
+
+	let resolved = starsystem.resolve_blocking(&[base.clone(), production.clone()]).unwrap();
+	assert_eq!(resolved.value("server", "host"), None);
+	assert_eq!(resolved.value("server", "port"), Some(StarValue::Int(443)));
+	assert!(!resolved.entities.contains_key("debug"));
+	assert!(resolved.entities.keys().all(|k| !k.starts_with(REMOVAL_MARKER)));
+	assert!(resolved.entities["server"].properties.keys().all(|k| !k.starts_with(REMOVAL_MARKER)));
+
+	// a higher layer brings a hidden property back
+	let local = layer(&mut starsystem, "local", &[("server", &[("host", StarValue::from("0.0.0.0"))]), ("debug", &[])]);
+	let resolved = starsystem.resolve_blocking(&[base, production, local.clone()]).unwrap();
+	assert_eq!(resolved.value("server", "host"), Some(StarValue::from("0.0.0.0")));
+	assert_eq!(resolved.provenance("server", "host"), Some(&local));
+	assert!(resolved.entities["debug"].properties.is_empty());
+}
+
+#[test]
+fn entity_of_only_removal_markers_adds_no_entity() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let base = layer(&mut starsystem, "base", &[("server", &[("port", StarValue::Int(80)), ("host", StarValue::from("localhost"))])]);
+	let production = layer(&mut starsystem, "production", &[("server", &[("!host", StarValue::Null)]), ("cache", &[("!size", StarValue::Null)])]);
+
+	// the markers still hide the properties of an entity from below
+	let resolved = starsystem.resolve_blocking(&[base.clone(), production.clone()]).unwrap();
+	assert_eq!(resolved.value("server", "port"), Some(StarValue::Int(80)));
+	assert_eq!(resolved.value("server", "host"), None);
+	assert!(!resolved.entities.contains_key("cache"));
+
+	// hiding every property leaves the entity in place, only `!server` hides it whole
+	let strict = layer(&mut starsystem, "strict", &[("server", &[("!port", StarValue::Null)])]);
+	let resolved = starsystem.resolve_blocking(&[base, production, strict]).unwrap();
+	assert!(resolved.entities["server"].properties.is_empty());
+
+	// an entity that is empty on purpose is kept
+	let empty = layer(&mut starsystem, "empty", &[("cache", &[])]);
+	assert!(starsystem.resolve_blocking(&[empty]).unwrap().entities["cache"].properties.is_empty());
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use starsystem::*;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Settings {
+	retries: u32,
+	hosts: Vec<String>,
+	timeout: Option<f64>,
+}
+
+fn settings() -> Settings {
+	Settings { retries: 3, hosts: vec!["a".to_string(), "b".to_string()], timeout: None }
+}
+
+#[test]
+fn holding_one_resource_does_not_block_the_others() {
+	let mut s = StarSystem::<StarValue>::new_blocking();
+	s.insert_resource("frames".to_string(), StarValue::Int(0));
+	s.insert_resource("name".to_string(), StarValue::from("pipeline"));
+	s.insert_any_resource(Duration::from_secs(1));
+
+	let frames = s.get_resource_mut("frames".to_string()).unwrap();
+	let mut frames = frames.write().unwrap();
+	*frames = StarValue::Int(1);
+
+	// other resources can be read, written and inserted while frames is held
+	assert_eq!(s.get_resource("name".to_string()), Some(StarValue::from("pipeline")));
+	*s.get_any_resource::<Duration>().unwrap().write().unwrap() = Duration::from_secs(2);
+	s.insert_resource("other".to_string(), StarValue::Bool(true));
+	drop(frames);
+
+	assert_eq!(s.get_resource("frames".to_string()), Some(StarValue::Int(1)));
+	assert_eq!(*s.get_any_resource::<Duration>().unwrap().read().unwrap(), Duration::from_secs(2));
+}
+
+#[test]
+fn serializable_resources_are_saved() {
+	let mut s = StarSystem::<StarValue>::new_blocking();
+	s.insert_resource("frames".to_string(), StarValue::Int(7));
+	s.insert_any_serialized_resource(settings());
+	s.insert_any_resource(Duration::from_secs(1));
+
+	let json = serde_json::to_string(&s).unwrap();
+	let loaded: StarSystem<StarValue> = serde_json::from_str(&json).unwrap();
+	assert_eq!(loaded.get_resource("frames".to_string()), Some(StarValue::Int(7)));
+
+	// a resource that is not restored yet is saved again as it was read
+	let json = serde_json::to_string(&loaded).unwrap();
+	let mut loaded: StarSystem<StarValue> = serde_json::from_str(&json).unwrap();
+
+	assert!(loaded.get_any_resource::<Settings>().is_none());
+	assert!(loaded.restore_any_resource::<Settings>().unwrap());
+	assert_eq!(*loaded.get_any_resource::<Settings>().unwrap().read().unwrap(), settings());
+
+	// only serializable resources are saved
+	assert!(loaded.get_any_resource::<Duration>().is_none());
+	assert!(!loaded.restore_any_resource::<Duration>().unwrap());
+}
@@ -0,0 +1,104 @@
+use starsystem::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const STEP: Duration = Duration::from_millis(10);
+
+// a starsystem with a system that records the fixed time of every step
+fn setup() -> (StarSystem<StarValue>, Arc<Mutex<Vec<FixedTime>>>) {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let steps = Arc::new(Mutex::new(Vec::new()));
+	let log = steps.clone();
+	starsystem.add_system("step".to_string(), Query::new(), move |ctx| {
+		let time = *ctx.resources.get_any::<FixedTime>().unwrap().read().unwrap();
+		log.lock().unwrap().push(time);
+	});
+	(starsystem, steps)
+}
+
+#[test]
+fn first_frame_only_starts_the_clock() {
+	let (mut starsystem, steps) = setup();
+	let clock = FakeClock::new();
+	clock.advance(Duration::from_secs(5));
+	let mut runner = Runner::with_clock(STEP, clock);
+	assert_eq!(runner.frame(&mut starsystem).unwrap(), 0);
+	assert!(steps.lock().unwrap().is_empty());
+}
+
+#[test]
+fn accumulator_catches_up_over_several_steps() {
+	let (mut starsystem, steps) = setup();
+	let clock = FakeClock::new();
+	let mut runner = Runner::with_clock(STEP, clock.clone());
+	runner.frame(&mut starsystem).unwrap();
+
+	clock.advance(Duration::from_millis(35));
+	assert_eq!(runner.frame(&mut starsystem).unwrap(), 3);
+	assert_eq!(runner.accumulator, Duration::from_millis(5));
+
+	// the 5ms left over count towards the next frame
+	clock.advance(Duration::from_millis(5));
+	assert_eq!(runner.frame(&mut starsystem).unwrap(), 1);
+	assert_eq!(runner.accumulator, Duration::ZERO);
+	assert_eq!(runner.steps, 4);
+
+	let steps = steps.lock().unwrap();
+	assert_eq!(steps.iter().map(|t| t.step).collect::<Vec<u64>>(), vec![0, 1, 2, 3]);
+	assert!(steps.iter().all(|t| t.delta == STEP));
+}
+
+#[test]
+fn steps_over_max_steps_are_dropped() {
+	let (mut starsystem, steps) = setup();
+	let clock = FakeClock::new();
+	let mut runner = Runner::with_clock(STEP, clock.clone()).max_steps(3);
+	runner.frame(&mut starsystem).unwrap();
+
+	// 10 steps and 4ms are due, only 3 run and the whole steps left are dropped
+	clock.advance(Duration::from_millis(104));
+	assert_eq!(runner.frame(&mut starsystem).unwrap(), 3);
+	assert_eq!(runner.accumulator, Duration::from_millis(4));
+	assert_eq!(steps.lock().unwrap().len(), 3);
+
+	// the next frame does not try to catch up
+	clock.advance(Duration::from_millis(6));
+	assert_eq!(runner.frame(&mut starsystem).unwrap(), 1);
+	assert_eq!(steps.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn run_until_stops() {
+	let (mut starsystem, steps) = setup();
+	let clock = FakeClock::new();
+	let mut runner = Runner::with_clock(STEP, clock.clone());
+	let log = steps.clone();
+	runner.run_until(&mut starsystem, |_| log.lock().unwrap().len() == 5).unwrap();
+
+	// sleeping on the fake clock advances it by one step at a time, so no frame runs more than one step
+	// the first frame only starts the clock, so it took one sleep more than steps
+	assert_eq!(steps.lock().unwrap().len(), 5);
+	assert_eq!(runner.steps, 5);
+	assert_eq!(clock.now(), STEP * 6);
+
+	// a condition that already holds runs nothing
+	runner.run_until(&mut starsystem, |_| true).unwrap();
+	assert_eq!(runner.steps, 5);
+}
+
+#[test]
+fn manual_step_ignores_the_clock() {
+	let (mut starsystem, steps) = setup();
+	let mut runner = Runner::with_clock(STEP, FakeClock::new());
+	runner.step(&mut starsystem).unwrap();
+	runner.step(&mut starsystem).unwrap();
+	assert_eq!(steps.lock().unwrap().iter().map(|t| t.step).collect::<Vec<u64>>(), vec![0, 1]);
+	assert_eq!(runner.accumulator, Duration::ZERO);
+}
+
+#[test]
+fn zero_timestep_is_an_error() {
+	let (mut starsystem, _) = setup();
+	let mut runner = Runner::with_clock(Duration::ZERO, FakeClock::new());
+	assert!(runner.frame(&mut starsystem).is_err());
+}
@@ -0,0 +1,77 @@
+use starsystem::*;
+
+// a starsystem with one being holding an int and a string property
+fn setup() -> (StarSystem<StarValue>, Uid, Uid) {
+	let mut s = StarSystem::<StarValue>::new_blocking();
+	let being = s.conceive_being_blocking("config".to_string()).unwrap();
+	let entity = s.constitute_being_blocking(being.clone(), "server".to_string()).unwrap();
+	let port = s.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	let host = s.add_property_blocking(being.clone(), entity, StarValue::String("localhost".to_string()), "host".to_string()).unwrap();
+	(s, port, host)
+}
+
+fn data(s: &StarSystem<StarValue>, property: &Uid) -> StarValue {
+	s.worlds.values().find_map(|w| w.get_component_blocking(property.clone()).ok()).unwrap().data
+}
+
+#[test]
+fn system_writes_are_stored() {
+	let (mut s, port, _) = setup();
+	s.add_system("update".to_string(), Query::new().write(StarValue::Int(0)), |ctx| {
+		for item in ctx.items.iter_mut() {
+			item.component.data = StarValue::Int(8080);
+		}
+	});
+	s.run_systems_blocking().unwrap();
+	assert_eq!(data(&s, &port), StarValue::Int(8080));
+}
+
+#[test]
+fn undeclared_variant_write_is_rejected() {
+	let (mut s, port, host) = setup();
+	// the query writes ints and strings, but turns the port into a bool
+	s.add_system("update".to_string(), Query::new().write(StarValue::Int(0)).write(StarValue::String(String::new())), |ctx| {
+		for item in ctx.items.iter_mut() {
+			item.component.data = match item.component.data {
+				StarValue::Int(_) => StarValue::Bool(true),
+				_ => StarValue::String("example.com".to_string()),
+			};
+		}
+	});
+	assert!(s.run_systems_blocking().is_err());
+
+	// none of the changes were stored
+	assert_eq!(data(&s, &port), StarValue::Int(80));
+	assert_eq!(data(&s, &host), StarValue::String("localhost".to_string()));
+}
+
+#[test]
+fn failing_system_keeps_commands_of_the_others() {
+	let (mut s, _, _) = setup();
+	s.add_system("update".to_string(), Query::new().write(StarValue::Int(0)), |ctx| {
+		for item in ctx.items.iter_mut() {
+			item.component.data = StarValue::Null;
+		}
+	});
+	s.add_system("update".to_string(), Query::new().read(StarValue::String(String::new())), |ctx| {
+		ctx.commands.conceive_being("spawned".to_string());
+	});
+	assert!(s.run_systems_blocking().is_err());
+	assert!(s.beings.iter().any(|b| b.name == "spawned"));
+}
+
+#[test]
+fn par_for_each_mut_stamps_changed() {
+	let (mut s, port, host) = setup();
+	let since = s.tick;
+	s.advance_tick_blocking();
+	s.par_for_each_mut(StarValue::Int(0), |_, c| {
+		if let StarValue::Int(n) = &mut c.data {
+			*n += 1;
+		}
+	});
+	let changed: Vec<Uid> = s.query_since_blocking(&Query::new().read(StarValue::Int(0)).read(StarValue::String(String::new())).changed(), Some(since)).unwrap().into_iter().map(|i| i.id).collect();
+	assert_eq!(changed, vec![port.clone()]);
+	assert_eq!(data(&s, &port), StarValue::Int(81));
+	assert!(!changed.contains(&host));
+}
@@ -0,0 +1,71 @@
+use starsystem::*;
+
+fn schema() -> BeingSchema<StarValue> {
+	BeingSchema::new().entity(EntitySchema::new("server".to_string()).required().property(PropertySchema::new("port".to_string()).required().variant(StarValue::Int(0)).default(StarValue::Int(80))))
+}
+
+#[test]
+fn failed_conceive_leaves_no_being() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	// the default breaks the schema, so constituting the required entity fails
+	let broken = BeingSchema::new().entity(EntitySchema::new("server".to_string()).required().property(PropertySchema::new("port".to_string()).variant(StarValue::Int(0)).default(StarValue::Bool(true))));
+	starsystem.set_schema("config".to_string(), broken);
+
+	assert!(starsystem.conceive_being_blocking("config".to_string()).is_err());
+	assert!(starsystem.beings.is_empty());
+	assert!(starsystem.worlds.values().all(|w| w.entities.read().unwrap().is_empty()));
+}
+
+#[test]
+fn spawn_batch_completes_and_checks_specs() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	starsystem.set_schema("config".to_string(), schema());
+
+	let spawned = starsystem.spawn_batch_blocking(vec![BeingSpec::new("config".to_string())]).unwrap();
+	let ascended = starsystem.ascend_being_blocking(spawned[0].id.clone()).unwrap();
+	assert_eq!(ascended[0].entities[0].name, "server");
+	assert_eq!(ascended[0].entities[0].components[0].data, StarValue::Int(80));
+
+	let bad = BeingSpec::new("config".to_string()).entity(EntitySpec::new("server".to_string()).property("port".to_string(), StarValue::Bool(true)));
+	assert!(starsystem.spawn_batch_blocking(vec![bad]).is_err());
+	assert_eq!(starsystem.beings.len(), 1);
+}
+
+#[test]
+fn add_properties_batch_checks_every_value_first() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	starsystem.set_schema("config".to_string(), schema());
+	let being = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+	let entity = starsystem.get_being_blocking(being.clone()).unwrap().entities[0].id.clone();
+
+	let batch = vec![(being.clone(), entity.clone(), StarValue::Int(1), "workers".to_string()), (being.clone(), entity, StarValue::Bool(true), "port".to_string())];
+	assert!(starsystem.add_properties_batch_blocking(batch).is_err());
+	assert_eq!(starsystem.ascend_being_blocking(being).unwrap()[0].entities[0].components.len(), 1);
+}
+
+#[test]
+fn constraint_failures_stay_typed() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	starsystem.add_constraint("port".to_string(), Constraint::range(Some(1.0), Some(65535.0)));
+	let being = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+	let entity = starsystem.constitute_being_blocking(being.clone(), "server".to_string()).unwrap();
+
+	match starsystem.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(0), "port".to_string()) {
+		Err(PropertyError::Invalid(err)) => assert_eq!(err.path, "config/server/port"),
+		other => panic!("unexpected {:?}", other),
+	}
+	let port = starsystem.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	assert!(matches!(starsystem.set_property_by_id_blocking(port, StarValue::Int(70000)), Err(PropertyError::Invalid(_))));
+
+	let spec = BeingSpec::new("config".to_string()).entity(EntitySpec::new("server".to_string()).property("port".to_string(), StarValue::Int(0)));
+	assert!(starsystem.spawn_batch_blocking(vec![spec.clone()]).is_err());
+	assert!(starsystem.add_properties_batch_blocking(vec![(being, entity, StarValue::Int(0), "port".to_string())]).is_err());
+
+	let shared = SharedStarSystem::from(starsystem);
+	let being = shared.conceive_being_blocking("config".to_string()).unwrap();
+	let entity = shared.constitute_being_blocking(being.clone(), "server".to_string()).unwrap();
+	assert!(matches!(shared.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(0), "port".to_string()), Err(PropertyError::Invalid(_))));
+	let port = shared.add_property_blocking(being, entity, StarValue::Int(80), "port".to_string()).unwrap();
+	assert!(matches!(shared.set_property_by_id_blocking(port, StarValue::Int(0)), Err(PropertyError::Invalid(_))));
+	assert!(shared.spawn_batch_blocking(vec![spec]).is_err());
+}
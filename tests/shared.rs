@@ -0,0 +1,274 @@
+use rayon::prelude::*;
+use starsystem::*;
+use std::thread;
+
+const THREADS: usize = 8;
+const BEINGS_PER_THREAD: usize = 50;
+
+// the value of the only property of the only entity of a being
+fn value(shared: &SharedStarSystem<StarValue>, being: &Uid) -> StarValue {
+	let ascended = shared.ascend_being_blocking(being.clone()).unwrap();
+	ascended[0].entities[0].components[0].data.clone()
+}
+
+// the number of entities held by every world
+fn world_entities(shared: &SharedStarSystem<StarValue>) -> usize {
+	shared.worlds.read().unwrap().values().map(|w| w.entities.read().unwrap().len()).sum()
+}
+
+// spawn a being with one entity and one property
+fn spawn(shared: &SharedStarSystem<StarValue>, name: String, n: i64) -> (Uid, Uid, Uid) {
+	let being = shared.conceive_being_blocking(name).unwrap();
+	let entity = shared.constitute_being_blocking(being.clone(), "counter".to_string()).unwrap();
+	let property = shared.add_property_blocking(being.clone(), entity.clone(), StarValue::Int(n), "n".to_string()).unwrap();
+	(being, entity, property)
+}
+
+#[test]
+fn concurrent_spawn() {
+	let shared = SharedStarSystem::<StarValue>::new_blocking();
+	let handles: Vec<_> = (0..THREADS)
+		.map(|t| {
+			let shared = shared.clone();
+			thread::spawn(move || (0..BEINGS_PER_THREAD).map(|i| (spawn(&shared, format!("{}-{}", t, i), (t * BEINGS_PER_THREAD + i) as i64), (t * BEINGS_PER_THREAD + i) as i64)).collect::<Vec<_>>())
+		})
+		.collect();
+	let spawned: Vec<_> = handles.into_iter().flat_map(|h| h.join().unwrap()).collect();
+
+	assert_eq!(shared.beings.read().unwrap().len(), THREADS * BEINGS_PER_THREAD);
+	assert_eq!(world_entities(&shared), THREADS * BEINGS_PER_THREAD);
+	for ((being, ..), n) in spawned.iter() {
+		assert_eq!(value(&shared, being), StarValue::Int(*n));
+	}
+}
+
+#[test]
+fn concurrent_set() {
+	let shared = SharedStarSystem::<StarValue>::new_blocking();
+	let spawned: Vec<_> = (0..THREADS * BEINGS_PER_THREAD).map(|i| spawn(&shared, i.to_string(), 0)).collect();
+
+	// every thread writes its own value to every property, readers must only ever see one of them
+	let writers: Vec<_> = (1..=THREADS as i64)
+		.map(|t| {
+			let shared = shared.clone();
+			let spawned = spawned.clone();
+			thread::spawn(move || {
+				for (being, entity, property) in spawned.iter() {
+					shared.set_property_blocking(being.clone(), entity.clone(), property.clone(), StarValue::Int(t), "n".to_string()).unwrap();
+				}
+			})
+		})
+		.collect();
+	spawned.par_iter().for_each(|(being, ..)| match value(&shared, being) {
+		StarValue::Int(n) => assert!((0..=THREADS as i64).contains(&n)),
+		other => panic!("unexpected value {:?}", other),
+	});
+	for w in writers {
+		w.join().unwrap();
+	}
+
+	// then set every property by id in parallel
+	spawned.par_iter().enumerate().for_each(|(i, (_, _, property))| {
+		shared.set_property_by_id_blocking(property.clone(), StarValue::Int(i as i64)).unwrap();
+	});
+	for (i, (being, ..)) in spawned.iter().enumerate() {
+		assert_eq!(value(&shared, being), StarValue::Int(i as i64));
+	}
+}
+
+#[test]
+fn concurrent_kill() {
+	let shared = SharedStarSystem::<StarValue>::new_blocking();
+	let spawned: Vec<_> = (0..THREADS * BEINGS_PER_THREAD).map(|i| spawn(&shared, i.to_string(), i as i64)).collect();
+	let (killed, kept): (Vec<_>, Vec<_>) = spawned.iter().cloned().enumerate().partition(|(i, _)| i % 2 == 0);
+
+	// kill half of the beings while the other half is read and written
+	let killers: Vec<_> = killed
+		.chunks(BEINGS_PER_THREAD)
+		.map(|chunk| {
+			let shared = shared.clone();
+			let chunk = chunk.to_vec();
+			thread::spawn(move || {
+				for (_, (being, ..)) in chunk {
+					shared.kill_being_blocking(being).unwrap();
+				}
+			})
+		})
+		.collect();
+	kept.par_iter().for_each(|(i, (being, entity, property))| {
+		shared.set_property_blocking(being.clone(), entity.clone(), property.clone(), StarValue::Int(-(*i as i64)), "n".to_string()).unwrap();
+		assert_eq!(value(&shared, being), StarValue::Int(-(*i as i64)));
+	});
+	for k in killers {
+		k.join().unwrap();
+	}
+
+	assert_eq!(shared.beings.read().unwrap().len(), kept.len());
+	assert_eq!(world_entities(&shared), kept.len());
+	for (_, (being, ..)) in killed.iter() {
+		assert!(shared.get_being_blocking(being.clone()).is_err());
+		assert!(shared.kill_being_blocking(being.clone()).is_err());
+	}
+}
+
+#[test]
+fn concurrent_spawn_and_kill() {
+	let shared = SharedStarSystem::<StarValue>::new_blocking();
+	let handles: Vec<_> = (0..THREADS)
+		.map(|t| {
+			let shared = shared.clone();
+			thread::spawn(move || {
+				let mut kept = Vec::new();
+				for i in 0..BEINGS_PER_THREAD {
+					let (being, ..) = spawn(&shared, format!("{}-{}", t, i), i as i64);
+					if i % 3 == 0 {
+						shared.kill_being_blocking(being).unwrap();
+					} else {
+						kept.push(being);
+					}
+				}
+				kept
+			})
+		})
+		.collect();
+	let kept: Vec<Uid> = handles.into_iter().flat_map(|h| h.join().unwrap()).collect();
+
+	assert_eq!(shared.beings.read().unwrap().len(), kept.len());
+	assert_eq!(world_entities(&shared), kept.len());
+	kept.par_iter().for_each(|being| {
+		assert_eq!(shared.ascend_being_blocking(being.clone()).unwrap()[0].entities.len(), 1);
+	});
+}
+
+#[test]
+fn round_trip_keeps_state() {
+	#[derive(Clone)]
+	struct Ping;
+
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	starsystem.set_schema("config".to_string(), BeingSchema::new().entity(EntitySchema::new("server".to_string()).property(PropertySchema::new("port".to_string()).variant(StarValue::Int(0)))));
+	starsystem.add_constraint("port".to_string(), Constraint::range(Some(1.0), Some(65535.0)));
+	starsystem.add_system("update".to_string(), Query::new(), |_| {});
+	starsystem.add_event::<Ping>();
+	starsystem.events.writer::<Ping>().send(Ping);
+
+	let template = Template::new("server".to_string()).entity(EntitySpec::new("http".to_string()).property("host".to_string(), StarValue::from("localhost")));
+	let linked = starsystem.instantiate_linked_blocking(&template, "api".to_string(), Default::default()).unwrap();
+
+	let config = starsystem.conceive_being_blocking("config".to_string()).unwrap();
+	let server = starsystem.constitute_being_blocking(config.clone(), "server".to_string()).unwrap();
+	let port = starsystem.add_property_blocking(config.clone(), server.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	let next = starsystem.add_computed_property_blocking(config.clone(), server.clone(), "next".to_string(), vec![port.clone()], |v| StarValue::Int(v[0].as_int().unwrap() + 1)).unwrap();
+
+	let shared = SharedStarSystem::from(starsystem);
+
+	// writes through the handle are checked against the schema and the constraints and recompute
+	assert!(matches!(shared.set_property_by_id_blocking(port.clone(), StarValue::Float(80.0)), Err(PropertyError::Schema(_))));
+	assert!(matches!(shared.set_property_by_id_blocking(port.clone(), StarValue::Int(0)), Err(PropertyError::Invalid(_))));
+	assert!(shared.set_property_by_id_blocking(next.clone(), StarValue::Int(0)).is_err());
+	shared.set_property_blocking(config.clone(), server.clone(), port.clone(), StarValue::Int(8080), "port".to_string()).unwrap();
+	let ascended = shared.ascend_being_blocking(config.clone()).unwrap();
+	let values: Vec<(String, StarValue)> = ascended[0].entities[0].components.iter().map(|c| (c.name.clone(), c.data.clone())).collect();
+	assert!(values.contains(&("port".to_string(), StarValue::Int(8080))));
+	assert!(values.contains(&("next".to_string(), StarValue::Int(8081))));
+
+	// and everything comes back
+	let mut starsystem = shared.to_star_system_blocking();
+	assert!(starsystem.schemas.contains_key("config"));
+	assert!(starsystem.validate_value("config", "server", "port", &StarValue::Int(0)).is_err());
+	assert!(starsystem.computed.is_computed(&next));
+	assert!(starsystem.templates.contains_key("server"));
+	assert!(starsystem.template_links.contains_key(&linked.id));
+	assert_eq!(starsystem.schedule.stages.len(), 1);
+	assert_eq!(starsystem.event_reader::<Ping>().read().len(), 1);
+
+	starsystem.set_property_by_id_blocking(port, StarValue::Int(443)).unwrap();
+	let ascended = starsystem.ascend_being_blocking(config).unwrap();
+	assert_eq!(ascended[0].entities[0].components.iter().find(|c| c.name == "next").unwrap().data, StarValue::Int(444));
+}
+
+#[test]
+fn shared_conceive_follows_schema() {
+	let shared = SharedStarSystem::<StarValue>::new_blocking();
+	shared.set_schema("config".to_string(), BeingSchema::new().entity(EntitySchema::new("server".to_string()).required().property(PropertySchema::new("port".to_string()).required().default(StarValue::Int(80)))));
+
+	let config = shared.conceive_being_blocking("config".to_string()).unwrap();
+	let ascended = shared.ascend_being_blocking(config.clone()).unwrap();
+	assert_eq!(ascended[0].entities[0].name, "server");
+	assert_eq!(ascended[0].entities[0].components[0].data, StarValue::Int(80));
+
+	let port = ascended[0].entities[0].components[0].id.clone();
+	assert!(shared.remove_property_blocking(port.clone()).is_err());
+	shared.set_schema_mode(SchemaMode::Warn);
+	shared.remove_property_blocking(port).unwrap();
+	assert_eq!(shared.schema_warnings.read().unwrap().len(), 1);
+}
+
+#[test]
+fn par_for_each_mut_with_concurrent_writes() {
+	let shared = SharedStarSystem::<StarValue>::new_blocking();
+	let spawned: Vec<_> = (0..BEINGS_PER_THREAD).map(|i| spawn(&shared, i.to_string(), i as i64)).collect();
+	let (done, finished) = std::sync::mpsc::channel();
+
+	// the transform moves every other component to another variant, which relocates it on its entity
+	let transformer = {
+		let shared = shared.clone();
+		let done = done.clone();
+		thread::spawn(move || {
+			for _ in 0..20 {
+				shared.par_for_each_mut(StarValue::Int(0), |_, c| {
+					if let StarValue::Int(n) = c.data {
+						c.data = if n % 2 == 0 { StarValue::Float(n as f64) } else { StarValue::Int(n + 1) };
+					}
+				});
+				shared.par_for_each_mut(StarValue::Float(0.0), |_, c| {
+					if let StarValue::Float(n) = c.data {
+						c.data = StarValue::Int(n as i64 + 1);
+					}
+				});
+			}
+			done.send(()).unwrap();
+		})
+	};
+	let setters: Vec<_> = (0..THREADS / 2)
+		.map(|t| {
+			let shared = shared.clone();
+			let spawned = spawned.clone();
+			let done = done.clone();
+			thread::spawn(move || {
+				for _ in 0..10 {
+					for (being, entity, property) in spawned.iter() {
+						shared.set_property_blocking(being.clone(), entity.clone(), property.clone(), StarValue::Int(t as i64), "n".to_string()).unwrap();
+					}
+				}
+				done.send(()).unwrap();
+			})
+		})
+		.collect();
+	let spawners: Vec<_> = (0..THREADS / 2)
+		.map(|t| {
+			let shared = shared.clone();
+			let done = done.clone();
+			thread::spawn(move || {
+				for i in 0..10 {
+					let specs = (0..10).map(|j| BeingSpec::new(format!("{}-{}-{}", t, i, j)).entity(EntitySpec::new("counter".to_string()).property("n".to_string(), StarValue::Int(j))));
+					assert_eq!(shared.spawn_batch_blocking(specs).unwrap().len(), 10);
+				}
+				done.send(()).unwrap();
+			})
+		})
+		.collect();
+
+	// a deadlock between the transform and the writers shows up as a timeout instead of a hanging test
+	for _ in 0..1 + setters.len() + spawners.len() {
+		finished.recv_timeout(std::time::Duration::from_secs(60)).expect("the transform and the writers deadlocked");
+	}
+	transformer.join().unwrap();
+	setters.into_iter().chain(spawners).for_each(|h| h.join().unwrap());
+
+	// every entity still finds its component where its location says
+	assert_eq!(shared.beings.read().unwrap().len(), BEINGS_PER_THREAD + THREADS / 2 * 100);
+	for being in shared.beings.read().unwrap().keys() {
+		assert!(matches!(value(&shared, being), StarValue::Int(_) | StarValue::Float(_)));
+	}
+}
@@ -0,0 +1,108 @@
+use starsystem::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+// a starsystem with a few worlds to spread the spawned entities over
+fn setup() -> StarSystem<StarValue> {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	for _ in 0..3 {
+		let world = World::<StarValue>::new_blocking();
+		starsystem.worlds.insert(world.id.clone(), world);
+	}
+	starsystem
+}
+
+fn spec(name: &str, port: i64) -> BeingSpec<StarValue> {
+	BeingSpec::new(name.to_string()).entity(EntitySpec::new("server".to_string()).property("port".to_string(), StarValue::Int(port)).property("host".to_string(), StarValue::from("localhost"))).entity(EntitySpec::new("headers".to_string()).property("Content-Type".to_string(), StarValue::from("application/json")))
+}
+
+// the name and value of a property of an ascended being
+fn property(being: &AscendedBeing<StarValue>, id: &Uid) -> (String, StarValue) {
+	let c = being.entities.iter().flat_map(|e| e.components.iter()).find(|c| c.id == *id).unwrap();
+	(c.name.clone(), c.data.clone())
+}
+
+// the names and values of a being, without its ids
+fn shape(being: &AscendedBeing<StarValue>) -> Vec<(String, Vec<(String, StarValue)>)> {
+	being.entities.iter().map(|e| (e.name.clone(), e.components.iter().map(|c| (c.name.clone(), c.data.clone())).collect())).collect()
+}
+
+#[test]
+fn ids_come_back_in_spec_order() {
+	let mut starsystem = setup();
+	let specs: Vec<BeingSpec<StarValue>> = (0..10).map(|i| spec(&format!("config{}", i), 8000 + i)).collect();
+	let spawned = starsystem.spawn_batch_blocking(specs).unwrap();
+	assert_eq!(spawned.len(), 10);
+
+	for (i, s) in spawned.iter().enumerate() {
+		let being = starsystem.get_being_blocking(s.id.clone()).unwrap();
+		assert_eq!(being.name, format!("config{}", i));
+		assert_eq!(being.entities.iter().map(|e| e.id.clone()).collect::<Vec<Uid>>(), s.entities.iter().map(|e| e.id.clone()).collect::<Vec<Uid>>());
+
+		// the property ids follow the order of the properties in the spec
+		let ascended = starsystem.ascend_being_blocking(s.id.clone()).unwrap().remove(0);
+		let server = &s.entities[0].properties;
+		assert_eq!(property(&ascended, &server[0]), ("port".to_string(), StarValue::Int(8000 + i as i64)));
+		assert_eq!(property(&ascended, &server[1]), ("host".to_string(), StarValue::from("localhost")));
+		assert_eq!(property(&ascended, &s.entities[1].properties[0]).0, "Content-Type");
+	}
+}
+
+#[test]
+fn each_world_is_written_once() {
+	let mut starsystem = setup();
+	let worlds: Vec<World<StarValue>> = starsystem.worlds.values().cloned().collect();
+	let done = Arc::new(AtomicBool::new(false));
+
+	// every look at a world sees none or all of the entities spawned into it
+	let watching = done.clone();
+	let watcher = thread::spawn(move || {
+		let mut seen = Vec::new();
+		while !watching.load(Ordering::SeqCst) {
+			seen.push(worlds.iter().map(|w| w.entities.read().unwrap().len()).collect::<Vec<usize>>());
+		}
+		(worlds, seen)
+	});
+	let specs: Vec<BeingSpec<StarValue>> = (0..200).map(|i| spec(&format!("config{}", i), 8000 + i)).collect();
+	starsystem.spawn_batch_blocking(specs).unwrap();
+	done.store(true, Ordering::SeqCst);
+	let (worlds, seen) = watcher.join().unwrap();
+
+	let totals: Vec<usize> = worlds.iter().map(|w| w.entities.read().unwrap().len()).collect();
+	assert_eq!(totals.iter().sum::<usize>(), 400);
+	for counts in seen {
+		for (count, total) in counts.iter().zip(totals.iter()) {
+			assert!(*count == 0 || count == total, "saw {} of {} entities", count, total);
+		}
+	}
+	for (world, total) in worlds.iter().zip(totals.iter()) {
+		assert_eq!(*world.entities_count.read().unwrap(), *total);
+	}
+}
+
+#[test]
+fn failing_add_properties_batch_writes_nothing() {
+	let mut starsystem = setup();
+	starsystem.add_constraint("port".to_string(), Constraint::range(Some(1.0), Some(65535.0)));
+	let spawned = starsystem.spawn_batch_blocking(vec![spec("a", 80), spec("b", 443)]).unwrap();
+	let (a, b) = (&spawned[0], &spawned[1]);
+	let before: Vec<_> = spawned.iter().map(|s| shape(&starsystem.ascend_being_blocking(s.id.clone()).unwrap()[0])).collect();
+
+	// a missing entity at the end of the batch
+	let missing = starsystem.add_properties_batch_blocking(vec![(a.id.clone(), a.entities[1].id.clone(), StarValue::from("gzip"), "Accept-Encoding".to_string()), (b.id.clone(), Uid::new(), StarValue::from("x"), "x".to_string())]);
+	assert!(missing.is_err());
+
+	// a value breaking a constraint after a valid one on another being
+	let invalid = starsystem.add_properties_batch_blocking(vec![(a.id.clone(), a.entities[1].id.clone(), StarValue::from("gzip"), "Accept-Encoding".to_string()), (b.id.clone(), b.entities[0].id.clone(), StarValue::Int(70000), "port".to_string())]);
+	assert!(invalid.is_err());
+
+	let after: Vec<_> = spawned.iter().map(|s| shape(&starsystem.ascend_being_blocking(s.id.clone()).unwrap()[0])).collect();
+	assert_eq!(before, after);
+	assert_eq!(starsystem.worlds.values().map(|w| w.components().values().map(|c| c.len()).sum::<usize>()).sum::<usize>(), 6);
+
+	// the same batch goes through once it is valid, and the ids follow the input order
+	let ids = starsystem.add_properties_batch_blocking(vec![(a.id.clone(), a.entities[1].id.clone(), StarValue::from("gzip"), "Accept-Encoding".to_string()), (b.id.clone(), b.entities[0].id.clone(), StarValue::Int(8443), "tls_port".to_string())]).unwrap();
+	assert_eq!(property(&starsystem.ascend_being_blocking(a.id.clone()).unwrap()[0], &ids[0]), ("Accept-Encoding".to_string(), StarValue::from("gzip")));
+	assert_eq!(property(&starsystem.ascend_being_blocking(b.id.clone()).unwrap()[0], &ids[1]), ("tls_port".to_string(), StarValue::Int(8443)));
+}
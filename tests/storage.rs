@@ -0,0 +1,103 @@
+use starsystem::*;
+
+// a dense world holding one entity with one int component
+fn dense() -> (World<StarValue>, Uid, Uid) {
+	let world = World::<StarValue>::with_storage_blocking(StorageKind::Dense);
+	let entity = world.create_entity_blocking("server".to_string()).unwrap();
+	let id = world.add_component_to_entity_blocking(entity.clone(), StarValue::Int(1), "port".to_string()).unwrap();
+	(world, entity, id)
+}
+
+#[test]
+fn updates_keep_dense_handles() {
+	let (world, entity, id) = dense();
+	let handle = world.handle(id.clone()).unwrap();
+
+	world.set_component_blocking(id.clone(), StarValue::Int(2)).unwrap();
+	assert_eq!(world.handle(id.clone()), Some(handle));
+	world.set_component_to_entity_blocking(entity.clone(), StarValue::Int(3), "port".to_string(), id.clone()).unwrap();
+	assert_eq!(world.handle(id.clone()), Some(handle));
+	world.update_components_blocking(vec![(entity.clone(), id.clone(), StarValue::Int(4))], 1).unwrap();
+	assert_eq!(world.handle(id.clone()), Some(handle));
+	assert_eq!(world.get_component_blocking(id.clone()).unwrap().data, StarValue::Int(4));
+
+	// changing the variant moves the component to another column
+	world.set_component_blocking(id.clone(), StarValue::Bool(true)).unwrap();
+	assert!(world.handle(id.clone()).is_some());
+	assert_eq!(world.get_component_blocking(id).unwrap().data, StarValue::Bool(true));
+}
+
+#[test]
+fn dense_world_round_trips_as_sparse_map() {
+	let (world, _, id) = dense();
+	// the components read the same whatever the storage
+	assert_eq!(world.components()[&StarValue::Int(0).index()][&id].data, StarValue::Int(1));
+
+	let json = serde_json::to_string(&world).unwrap();
+	let loaded: World<StarValue> = serde_json::from_str(&json).unwrap();
+	assert_eq!(loaded.storage_kind(), StorageKind::Dense);
+	assert_eq!(loaded.get_component_blocking(id.clone()).unwrap().data, StarValue::Int(1));
+
+	loaded.set_storage(StorageKind::Sparse);
+	assert_eq!(loaded.components()[&StarValue::Int(0).index()][&id].data, StarValue::Int(1));
+	assert_eq!(serde_json::to_string(&loaded).unwrap().replace("\"Sparse\"", "\"Dense\""), json);
+}
+
+#[test]
+fn set_components_files_each_component_under_its_variant() {
+	for kind in [StorageKind::Sparse, StorageKind::Dense] {
+		let world = World::<StarValue>::with_storage_blocking(kind);
+		let entity = world.create_entity_blocking("server".to_string()).unwrap();
+		let port = world.add_component_to_entity_blocking(entity.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+		let retries = world.add_component_to_entity_blocking(entity.clone(), StarValue::Int(3), "retries".to_string()).unwrap();
+		let host = world.add_component_to_entity_blocking(entity.clone(), StarValue::from("localhost"), "host".to_string()).unwrap();
+		let alias = world.add_component_to_entity_blocking(entity.clone(), StarValue::from("api"), "alias".to_string()).unwrap();
+		world.set_tick(1);
+
+		// one batch with two variants, the port turns into a string and the alias is left out
+		let mut components = world.get_components_of_type_blocking(StarValue::from("")).unwrap();
+		components.remove(&alias);
+		components.get_mut(&host).unwrap().data = StarValue::from("example.com");
+		let mut moved = world.get_component_blocking(port.clone()).unwrap();
+		moved.data = StarValue::from("8080");
+		components.insert(port.clone(), moved);
+		let new = Uid::new();
+		components.insert(new.clone(), Component { name: "tls".to_string(), data: StarValue::Bool(true), added: 0, changed: 0 });
+		world.set_components_blocking(components).unwrap();
+
+		let by_variant = world.components();
+		assert_eq!(by_variant[&StarValue::from("").index()].len(), 2);
+		assert_eq!(by_variant[&StarValue::Bool(false).index()][&new].added, 1);
+		// the port moved out of the int column, which is not replaced since no int is in the batch
+		assert_eq!(by_variant[&StarValue::Int(0).index()].keys().cloned().collect::<Vec<Uid>>(), vec![retries.clone()]);
+		assert_eq!(world.removals_since_blocking(None).iter().map(|r| r.id.clone()).collect::<Vec<Uid>>(), vec![alias]);
+
+		// the entity points to the new column of the port and no longer to the removed component
+		let on_entity: Vec<(Uid, StarValue)> = world.get_entity_components_blocking(entity).unwrap().into_iter().map(|(id, c)| (id, c.data)).collect();
+		assert_eq!(on_entity, vec![(port.clone(), StarValue::from("8080")), (retries, StarValue::Int(3)), (host, StarValue::from("example.com"))]);
+		assert_eq!(world.get_component_blocking(port).unwrap().changed, 1);
+	}
+}
+
+#[test]
+fn readers_never_see_a_column_being_replaced() {
+	let world = World::<StarValue>::with_storage_blocking(StorageKind::Dense);
+	let entity = world.create_entity_blocking("counters".to_string()).unwrap();
+	for i in 0..100 {
+		world.add_component_to_entity_blocking(entity.clone(), StarValue::Int(i), i.to_string()).unwrap();
+	}
+	let column = world.get_components_of_type_blocking(StarValue::Int(0)).unwrap();
+
+	let writer = {
+		let world = world.clone();
+		std::thread::spawn(move || {
+			for _ in 0..200 {
+				world.set_components_blocking(column.clone()).unwrap();
+			}
+		})
+	};
+	while !writer.is_finished() {
+		assert_eq!(world.get_components_of_type_blocking(StarValue::Int(0)).unwrap().len(), 100);
+	}
+	writer.join().unwrap();
+}
@@ -0,0 +1,42 @@
+use starsystem::*;
+use std::collections::BTreeMap;
+
+fn template(port: i64) -> Template<StarValue> {
+	Template::new("server".to_string()).entity(EntitySpec::new("http".to_string()).property("port".to_string(), StarValue::Int(port)))
+}
+
+fn port(starsystem: &StarSystem<StarValue>, being: &Uid) -> StarValue {
+	starsystem.ascend_being_blocking(being.clone()).unwrap()[0].entities[0].components[0].data.clone()
+}
+
+#[test]
+fn failed_update_keeps_the_old_template() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	let a = starsystem.instantiate_linked_blocking(&template(80), "a".to_string(), BTreeMap::new()).unwrap().id;
+	let b = starsystem.instantiate_linked_blocking(&template(80), "b".to_string(), BTreeMap::new()).unwrap().id;
+	starsystem.add_path_constraint("b/http/port".to_string(), Constraint::range(Some(1.0), Some(1024.0)));
+
+	assert!(starsystem.update_template_blocking(template(8080)).is_err());
+	assert_eq!(starsystem.templates["server"], template(80));
+	assert_eq!(port(&starsystem, &a), StarValue::Int(80));
+	assert_eq!(port(&starsystem, &b), StarValue::Int(80));
+
+	starsystem.update_template_blocking(template(443)).unwrap();
+	assert_eq!(starsystem.templates["server"], template(443));
+	assert_eq!(port(&starsystem, &a), StarValue::Int(443));
+}
+
+#[test]
+fn instantiate_follows_schema_and_constraints() {
+	let mut starsystem = StarSystem::<StarValue>::new_blocking();
+	starsystem.set_schema("server".to_string(), BeingSchema::new().entity(EntitySchema::new("http".to_string()).property(PropertySchema::new("host".to_string()).default(StarValue::String("localhost".to_string())))));
+	starsystem.add_constraint("port".to_string(), Constraint::range(Some(1.0), Some(65535.0)));
+
+	let spawned = starsystem.instantiate_blocking(&template(80), "server".to_string(), BTreeMap::new()).unwrap();
+	let names: Vec<String> = starsystem.ascend_being_blocking(spawned.id).unwrap()[0].entities[0].components.iter().map(|c| c.name.clone()).collect();
+	assert_eq!(names, vec!["port".to_string(), "host".to_string()]);
+
+	let overrides = BTreeMap::from([("http/port".to_string(), StarValue::Int(0))]);
+	assert!(starsystem.instantiate_blocking(&template(80), "server".to_string(), overrides).is_err());
+	assert_eq!(starsystem.beings.len(), 1);
+}
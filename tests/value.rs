@@ -0,0 +1,106 @@
+use starsystem::*;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+#[test]
+fn conversions_into_star_value() {
+	assert_eq!(StarValue::from(()), StarValue::Null);
+	assert_eq!(StarValue::from(true), StarValue::Bool(true));
+	assert_eq!(StarValue::from(-8i8), StarValue::Int(-8));
+	assert_eq!(StarValue::from(u32::MAX), StarValue::Int(u32::MAX as i64));
+	assert_eq!(StarValue::from(1.5f32), StarValue::Float(1.5));
+	assert_eq!(StarValue::from("localhost"), StarValue::String("localhost".to_string()));
+	assert_eq!(StarValue::from(vec![1u8, 2]), StarValue::Bytes(vec![1, 2]));
+	assert_eq!(StarValue::from(&[3u8][..]), StarValue::Bytes(vec![3]));
+	assert_eq!(StarValue::from(vec![StarValue::Int(1)]), StarValue::List(vec![StarValue::Int(1)]));
+
+	let map: HashMap<String, StarValue> = [("port".to_string(), StarValue::Int(80))].into_iter().collect();
+	assert_eq!(StarValue::from(map), StarValue::Map([("port".to_string(), StarValue::Int(80))].into_iter().collect()));
+
+	// none becomes null, some is converted
+	assert_eq!(StarValue::from(None::<i32>), StarValue::Null);
+	assert_eq!(StarValue::from(Some("x")), StarValue::from("x"));
+
+	// u64 and usize only fit while they fit in an i64
+	assert_eq!(StarValue::try_from(i64::MAX as u64), Ok(StarValue::Int(i64::MAX)));
+	assert_eq!(StarValue::try_from(u64::MAX), Err(format!("{} does not fit in an int", u64::MAX)));
+	assert_eq!(StarValue::try_from(7usize), Ok(StarValue::Int(7)));
+	assert!(StarValue::try_from(usize::MAX).is_err());
+}
+
+#[test]
+fn conversions_out_of_star_value() {
+	assert_eq!(u16::try_from(StarValue::Int(8080)), Ok(8080));
+	assert_eq!(bool::try_from(StarValue::Bool(true)), Ok(true));
+	assert_eq!(String::try_from(StarValue::from("host")), Ok("host".to_string()));
+	assert_eq!(Vec::<u8>::try_from(StarValue::Bytes(vec![1])), Ok(vec![1]));
+	assert_eq!(Vec::<StarValue>::try_from(StarValue::List(vec![])), Ok(vec![]));
+	assert_eq!(BTreeMap::<String, StarValue>::try_from(StarValue::Map(BTreeMap::new())), Ok(BTreeMap::new()));
+
+	// ints are widened to floats, but floats are never narrowed to ints
+	assert_eq!(f64::try_from(StarValue::Int(3)), Ok(3.0));
+	assert_eq!(f32::try_from(StarValue::Float(0.5)), Ok(0.5));
+	assert_eq!(i64::try_from(StarValue::Float(3.0)), Err("expected int, found float".to_string()));
+
+	// a wrong variant names what was expected and what was found
+	assert_eq!(bool::try_from(StarValue::Null), Err("expected bool, found null".to_string()));
+	assert_eq!(String::try_from(StarValue::Int(1)), Err("expected string, found int".to_string()));
+	assert_eq!(Vec::<u8>::try_from(StarValue::from("x")), Err("expected bytes, found string".to_string()));
+}
+
+#[test]
+fn numeric_narrowing_fails() {
+	assert_eq!(u8::try_from(StarValue::Int(255)), Ok(255));
+	assert_eq!(u8::try_from(StarValue::Int(256)), Err("256 does not fit in u8".to_string()));
+	assert_eq!(i8::try_from(StarValue::Int(-129)), Err("-129 does not fit in i8".to_string()));
+	assert_eq!(i16::try_from(StarValue::Int(40000)), Err("40000 does not fit in i16".to_string()));
+	assert_eq!(i32::try_from(StarValue::Int(i64::MAX)), Err(format!("{} does not fit in i32", i64::MAX)));
+	assert_eq!(u32::try_from(StarValue::Int(-1)), Err("-1 does not fit in u32".to_string()));
+	assert_eq!(u64::try_from(StarValue::Int(-1)), Err("-1 does not fit in u64".to_string()));
+	assert_eq!(usize::try_from(StarValue::Int(-1)), Err("-1 does not fit in usize".to_string()));
+	assert_eq!(u64::try_from(StarValue::Int(i64::MAX)), Ok(i64::MAX as u64));
+}
+
+#[test]
+fn parses_text_like_an_environment_variable() {
+	assert_eq!(StarValue::from_str("true"), Ok(StarValue::Bool(true)));
+	assert_eq!(StarValue::from_str("8080"), Ok(StarValue::Int(8080)));
+	assert_eq!(StarValue::from_str("-3"), Ok(StarValue::Int(-3)));
+	assert_eq!(StarValue::from_str("0.25"), Ok(StarValue::Float(0.25)));
+
+	// anything else stays a string, including floats that are not finite
+	assert_eq!("localhost".parse::<StarValue>(), Ok(StarValue::from("localhost")));
+	assert_eq!("True".parse::<StarValue>(), Ok(StarValue::from("True")));
+	assert_eq!("inf".parse::<StarValue>(), Ok(StarValue::from("inf")));
+	assert_eq!("NaN".parse::<StarValue>(), Ok(StarValue::from("NaN")));
+	assert_eq!("".parse::<StarValue>(), Ok(StarValue::from("")));
+
+	// ints too big for an i64 fall back to a float
+	assert_eq!("99999999999999999999".parse::<StarValue>(), Ok(StarValue::Float(1e20)));
+}
+
+#[test]
+fn iterates_every_variant_in_index_order() {
+	let values: Vec<StarValue> = StarValue::iter().collect();
+	assert_eq!(values.len(), 8);
+	assert_eq!(values.iter().map(|v| v.index()).collect::<Vec<usize>>(), (0..8).collect::<Vec<usize>>());
+	assert_eq!(values.iter().map(|v| v.type_name()).collect::<Vec<&str>>(), vec!["null", "bool", "int", "float", "string", "bytes", "list", "map"]);
+
+	// the index depends on the variant only
+	assert_eq!(StarValue::Int(1).index(), StarValue::Int(-1).index());
+	assert_eq!(StarValue::from("x").index(), StarValue::String(String::new()).index());
+	assert_eq!(StarValue::default().index(), 0);
+}
+
+#[test]
+fn star_value_components_go_to_their_variant() {
+	let world = World::<StarValue>::new_blocking();
+	let entity = world.create_entity_blocking("server".to_string()).unwrap();
+	let port = world.add_component_to_entity_blocking(entity.clone(), StarValue::Int(80), "port".to_string()).unwrap();
+	let host = world.add_component_to_entity_blocking(entity, StarValue::from("localhost"), "host".to_string()).unwrap();
+
+	let components = world.components();
+	assert!(components[&StarValue::Int(0).index()].contains_key(&port));
+	assert!(components[&StarValue::String(String::new()).index()].contains_key(&host));
+}